@@ -1,7 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::prelude::*;
 
-use meshr::geometry::{Aabb, Intersects, Triangle, Vector3};
+use meshr::geometry::{Aabb, Intersects, Ray, Triangle, Vector3};
 
 /// AABB/Triangle intersection test benchmark
 pub fn benchmark_intersects_aabb_triangle(c: &mut Criterion) {
@@ -37,6 +37,28 @@ pub fn benchmark_intersects_triangle_triangle(c: &mut Criterion) {
     });
 }
 
+/// AABB/Ray intersection test benchmark comparing a Ray computing the
+/// inverse direction per test to a precomputed Ray
+pub fn benchmark_intersects_aabb_ray(c: &mut Criterion) {
+    let origin = generate_vector3();
+    let direction = generate_vector3();
+    let aabbs: Vec<Aabb> = (0..1000)
+        .map(|_| Aabb::new(generate_vector3(), Vector3::ones() * 0.1))
+        .collect();
+
+    c.bench_function("AABB/Ray Intersection", |b| {
+        let ray = Ray::new(origin, direction);
+
+        b.iter(|| aabbs.iter().filter(|a| ray.intersects(*a)).count());
+    });
+
+    c.bench_function("AABB/Ray Intersection (Precomputed)", |b| {
+        let ray = Ray::precomputed(origin, direction);
+
+        b.iter(|| aabbs.iter().filter(|a| ray.intersects(*a)).count());
+    });
+}
+
 /// Generate a random Vector3 in the range [-4, 4]
 fn generate_vector3() -> Vector3 {
     let mut rng = rand::thread_rng();
@@ -50,6 +72,7 @@ criterion_group!(
     benches,
     benchmark_intersects_aabb_triangle,
     benchmark_intersects_triangle_triangle,
+    benchmark_intersects_aabb_ray,
 );
 
 criterion_main!(benches);
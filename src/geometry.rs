@@ -1,5 +1,7 @@
 pub mod aabb;
+pub mod frustum;
 pub mod line;
+pub mod plane;
 pub mod ray;
 pub mod sphere;
 pub mod triangle;
@@ -10,15 +12,20 @@ mod collision;
 
 // Re-exports
 pub use aabb::Aabb;
+pub use frustum::Frustum;
 pub use line::Line;
-pub use ray::Ray;
+pub use plane::Plane;
+pub use ray::{Culling, Ray};
 pub use sphere::Sphere;
-pub use triangle::Triangle;
+pub use triangle::{Triangle, TriangleRegion};
 pub use vector3::Vector3;
 
-/// Geometric tolerance
+/// Geometric tolerance shared by all geometry and collision routines
 pub const EPSILON: f64 = 1e-8;
 
+/// Homogeneous 4x4 matrix describing an affine transformation
+pub type Matrix4 = nalgebra::Matrix4<f64>;
+
 /// Get the shortest distance between two geometric entities
 pub trait Distance<T> {
     fn distance(&self, other: &T) -> f64;
@@ -39,6 +46,16 @@ pub trait Clip<T> {
     fn clip(&self, other: &T) -> Option<Geometry>;
 }
 
+/// Get the axis-aligned bounding box of a geometric entity
+pub trait Bounded {
+    fn bounds(&self) -> Aabb;
+}
+
+/// Apply an affine transformation to a geometric entity
+pub trait Transform {
+    fn transform(&self, m: &Matrix4) -> Self;
+}
+
 #[derive(Debug, Clone)]
 pub enum Geometry {
     Aabb(Aabb),
@@ -47,6 +64,105 @@ pub enum Geometry {
     Ray(Ray),
     Sphere(Sphere),
     Triangle(Triangle),
+    Polygon(Vec<Vector3>),
+}
+
+impl Geometry {
+    /// Get the axis-aligned bounding box. A point is bounded by a degenerate
+    /// box and a ray, being unbounded, by an infinite box about its origin.
+    pub fn bounds(&self) -> Aabb {
+        match self {
+            Geometry::Aabb(aabb) => aabb.bounds(),
+            Geometry::Line(line) => line.bounds(),
+            Geometry::Point(point) => point.bounds(),
+            Geometry::Ray(ray) => ray.bounds(),
+            Geometry::Sphere(sphere) => sphere.bounds(),
+            Geometry::Triangle(triangle) => triangle.bounds(),
+            Geometry::Polygon(points) => points
+                .iter()
+                .skip(1)
+                .fold(points[0].bounds(), |bounds, point| {
+                    Aabb::from_bounds(bounds.min().min(point), bounds.max().max(point))
+                }),
+        }
+    }
+
+    /// Get the Aabb (if the geometry is an Aabb)
+    pub fn as_aabb(&self) -> Option<&Aabb> {
+        match self {
+            Geometry::Aabb(aabb) => Some(aabb),
+            _ => None,
+        }
+    }
+
+    /// Get the Line (if the geometry is a Line)
+    pub fn as_line(&self) -> Option<&Line> {
+        match self {
+            Geometry::Line(line) => Some(line),
+            _ => None,
+        }
+    }
+
+    /// Get the point (if the geometry is a Point)
+    pub fn as_point(&self) -> Option<&Vector3> {
+        match self {
+            Geometry::Point(point) => Some(point),
+            _ => None,
+        }
+    }
+
+    /// Get the Ray (if the geometry is a Ray)
+    pub fn as_ray(&self) -> Option<&Ray> {
+        match self {
+            Geometry::Ray(ray) => Some(ray),
+            _ => None,
+        }
+    }
+
+    /// Get the Sphere (if the geometry is a Sphere)
+    pub fn as_sphere(&self) -> Option<&Sphere> {
+        match self {
+            Geometry::Sphere(sphere) => Some(sphere),
+            _ => None,
+        }
+    }
+
+    /// Get the Triangle (if the geometry is a Triangle)
+    pub fn as_triangle(&self) -> Option<&Triangle> {
+        match self {
+            Geometry::Triangle(triangle) => Some(triangle),
+            _ => None,
+        }
+    }
+
+    /// Get the vertices of the Polygon (if the geometry is a Polygon)
+    pub fn as_polygon(&self) -> Option<&[Vector3]> {
+        match self {
+            Geometry::Polygon(points) => Some(points),
+            _ => None,
+        }
+    }
+}
+
+impl Bounded for Geometry {
+    fn bounds(&self) -> Aabb {
+        Geometry::bounds(self)
+    }
+}
+
+impl Intersects<Aabb> for Geometry {
+    fn intersects(&self, other: &Aabb) -> bool {
+        match self {
+            Geometry::Aabb(aabb) => aabb.intersects(other),
+            Geometry::Line(line) => line.intersects(other),
+            Geometry::Point(point) => point.intersects(other),
+            Geometry::Ray(ray) => ray.intersects(other),
+            Geometry::Sphere(sphere) => sphere.intersects(other),
+            Geometry::Triangle(triangle) => triangle.intersects(other),
+            Geometry::Polygon(points) => (1..points.len() - 1)
+                .any(|i| Triangle::new(points[0], points[i], points[i + 1]).intersects(other)),
+        }
+    }
 }
 
 impl From<Aabb> for Geometry {
@@ -66,3 +182,249 @@ impl From<Vector3> for Geometry {
         Geometry::Point(value)
     }
 }
+
+impl From<Ray> for Geometry {
+    fn from(value: Ray) -> Geometry {
+        Geometry::Ray(value)
+    }
+}
+
+impl From<Sphere> for Geometry {
+    fn from(value: Sphere) -> Geometry {
+        Geometry::Sphere(value)
+    }
+}
+
+impl From<Triangle> for Geometry {
+    fn from(value: Triangle) -> Geometry {
+        Geometry::Triangle(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_bounds(geometry: Geometry, min: Vector3, max: Vector3) {
+        let bounds = geometry.bounds();
+
+        assert_eq!(bounds.min(), min);
+        assert_eq!(bounds.max(), max);
+    }
+
+    #[test]
+    fn bounds_aabb() {
+        let aabb = Aabb::unit();
+        let min = Vector3::new(-0.5, -0.5, -0.5);
+        let max = Vector3::new(0.5, 0.5, 0.5);
+
+        assert_bounds(aabb.into(), min, max);
+    }
+
+    #[test]
+    fn bounds_line() {
+        let p = Vector3::new(1., -2., 3.);
+        let q = Vector3::new(-1., 2., 5.);
+        let line = Line::new(p, q);
+
+        assert_bounds(
+            line.into(),
+            Vector3::new(-1., -2., 3.),
+            Vector3::new(1., 2., 5.),
+        );
+    }
+
+    #[test]
+    fn bounds_point() {
+        let p = Vector3::new(1., 2., 3.);
+
+        assert_bounds(p.into(), p, p);
+    }
+
+    #[test]
+    fn bounds_ray() {
+        let o = Vector3::new(1., 2., 3.);
+        let d = Vector3::new(1., 0., 0.);
+        let ray = Ray::new(o, d);
+        let inf = Vector3::ones() * f64::INFINITY;
+
+        assert_bounds(ray.into(), -inf, inf);
+    }
+
+    #[test]
+    fn bounds_sphere() {
+        let c = Vector3::new(1., 2., 3.);
+        let sphere = Sphere::new(c, 2.);
+
+        assert_bounds(
+            sphere.into(),
+            Vector3::new(-1., 0., 1.),
+            Vector3::new(3., 4., 5.),
+        );
+    }
+
+    #[test]
+    fn bounds_polygon() {
+        let points = vec![
+            Vector3::new(0., 0., 0.),
+            Vector3::new(1., 0., -1.),
+            Vector3::new(1., 2., 0.),
+            Vector3::new(0., 2., 1.),
+        ];
+
+        assert_bounds(
+            Geometry::Polygon(points),
+            Vector3::new(0., 0., -1.),
+            Vector3::new(1., 2., 1.),
+        );
+    }
+
+    #[test]
+    fn bounds_triangle() {
+        let p = Vector3::new(0., 0., 0.);
+        let q = Vector3::new(1., 0., -1.);
+        let r = Vector3::new(0., 2., 0.);
+        let triangle = Triangle::new(p, q, r);
+
+        assert_bounds(
+            triangle.into(),
+            Vector3::new(0., 0., -1.),
+            Vector3::new(1., 2., 0.),
+        );
+    }
+
+    #[test]
+    fn bounded() {
+        let p = Vector3::new(1., -2., 3.);
+        let q = Vector3::new(-1., 2., 5.);
+        let r = Vector3::new(0., 4., -1.);
+
+        let contains = |bounds: Aabb, points: &[Vector3]| {
+            points.iter().all(|&point| {
+                point.min(&bounds.min()) == bounds.min() && point.max(&bounds.max()) == bounds.max()
+            })
+        };
+
+        let sphere = Sphere::new(p, 2.);
+        let extremes = [
+            p + Vector3::new(2., 0., 0.),
+            p - Vector3::new(2., 0., 0.),
+            p + Vector3::new(0., 2., 0.),
+            p - Vector3::new(0., 2., 0.),
+            p + Vector3::new(0., 0., 2.),
+            p - Vector3::new(0., 0., 2.),
+        ];
+
+        let aabb = Aabb::from_bounds(q.min(&r), q.max(&r));
+        let ray = Ray::new(p, Vector3::new(1., 1., 0.));
+
+        assert_eq!(p.bounds().min(), p);
+        assert_eq!(p.bounds().max(), p);
+        assert!(contains(Line::new(p, q).bounds(), &[p, q]));
+        assert!(contains(Triangle::new(p, q, r).bounds(), &[p, q, r]));
+        assert!(contains(sphere.bounds(), &extremes));
+        assert!(contains(aabb.bounds(), &[aabb.min(), aabb.max()]));
+        assert!(contains(ray.bounds(), &[p, p + ray.direction() * 1e12]));
+    }
+
+    #[test]
+    fn accessors() {
+        let geometry: Geometry =
+            Triangle::new(Vector3::zeros(), Vector3::ones(), Vector3::ones()).into();
+
+        assert!(geometry.as_triangle().is_some());
+        assert!(geometry.as_aabb().is_none());
+        assert!(geometry.as_line().is_none());
+        assert!(geometry.as_point().is_none());
+        assert!(geometry.as_ray().is_none());
+        assert!(geometry.as_sphere().is_none());
+    }
+
+    #[test]
+    fn intersects_aabb() {
+        let aabb = Aabb::unit();
+        let inside: Geometry = Vector3::zeros().into();
+        let outside: Geometry = Vector3::ones().into();
+
+        assert!(inside.intersects(&aabb));
+        assert!(!outside.intersects(&aabb));
+    }
+
+    // Rotate a quarter turn about +z, scale by 2 and then translate by (1, 2, 3)
+    fn composite() -> Matrix4 {
+        let translation = nalgebra::Vector3::new(1., 2., 3.);
+        let rotation = nalgebra::Rotation3::from_axis_angle(
+            &nalgebra::Vector3::z_axis(),
+            std::f64::consts::FRAC_PI_2,
+        );
+
+        Matrix4::new_translation(&translation) * rotation.to_homogeneous().append_scaling(2.)
+    }
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        assert!((a - b).mag() < 1e-12, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn transform_vector3() {
+        let point = Vector3::new(1., 0., 0.).transform(&composite());
+        assert_close(point, Vector3::new(1., 4., 3.));
+    }
+
+    #[test]
+    fn transform_line() {
+        let line = Line::new(Vector3::zeros(), Vector3::new(0., 1., 0.));
+        let line = line.transform(&composite());
+
+        assert_close(line.p(), Vector3::new(1., 2., 3.));
+        assert_close(line.q(), Vector3::new(-1., 2., 3.));
+    }
+
+    #[test]
+    fn transform_triangle() {
+        let triangle = Triangle::new(
+            Vector3::zeros(),
+            Vector3::new(1., 0., 0.),
+            Vector3::new(0., 1., 0.),
+        );
+        let triangle = triangle.transform(&composite());
+
+        assert_close(triangle[0], Vector3::new(1., 2., 3.));
+        assert_close(triangle[1], Vector3::new(1., 4., 3.));
+        assert_close(triangle[2], Vector3::new(-1., 2., 3.));
+        assert_close(triangle.unit_normal(), Vector3::new(0., 0., 1.));
+    }
+
+    #[test]
+    fn transform_ray() {
+        let ray = Ray::new(Vector3::zeros(), Vector3::new(1., 0., 0.));
+        let ray = ray.transform(&composite());
+
+        assert_close(ray.origin(), Vector3::new(1., 2., 3.));
+        assert_close(ray.direction(), Vector3::new(0., 2., 0.));
+
+        let ray = Ray::precomputed(Vector3::zeros(), Vector3::new(1., 0., 0.));
+        let ray = ray.transform(&composite());
+
+        assert_close(ray.direction(), Vector3::new(0., 1., 0.));
+        assert!(ray.intersects(&Aabb::new(Vector3::new(1., 5., 3.), Vector3::ones())));
+    }
+
+    #[test]
+    fn transform_sphere() {
+        let sphere = Sphere::new(Vector3::new(1., 0., 0.), 0.5);
+        let sphere = sphere.transform(&composite());
+
+        assert_close(sphere.center(), Vector3::new(1., 4., 3.));
+        assert!((sphere.radius() - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn transform_aabb() {
+        let aabb = Aabb::from_bounds(Vector3::zeros(), Vector3::new(1., 2., 3.));
+        let aabb = aabb.transform(&composite());
+
+        assert_close(aabb.min(), Vector3::new(-3., 2., 3.));
+        assert_close(aabb.max(), Vector3::new(1., 4., 9.));
+    }
+}
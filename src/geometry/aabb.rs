@@ -1,7 +1,8 @@
 use crate::geometry::collision;
-use crate::geometry::{Ray, Sphere, Triangle, Vector3};
+use crate::geometry::{Frustum, Geometry, Line, Matrix4, Ray, Sphere, Triangle, Vector3};
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Aabb {
     center: Vector3,
     halfsize: Vector3,
@@ -47,6 +48,53 @@ impl Aabb {
         self.center + self.halfsize
     }
 
+    /// Get the corners ordered like the octants. Corner 0 is the min bound
+    /// and corner 7 is the max bound.
+    pub fn corners(&self) -> [Vector3; 8] {
+        let h = self.halfsize;
+
+        [0, 1, 2, 3, 4, 5, 6, 7].map(|i| {
+            let dx = if (i & 4) == 0 { -h[0] } else { h[0] };
+            let dy = if (i & 2) == 0 { -h[1] } else { h[1] };
+            let dz = if (i & 1) == 0 { -h[2] } else { h[2] };
+            self.center + Vector3::new(dx, dy, dz)
+        })
+    }
+
+    /// Get the Aabb bounding the corners transformed by an affine matrix.
+    /// This bounds the transformed box conservatively without the geometry
+    /// it encloses.
+    pub fn transformed(&self, m: &Matrix4) -> Aabb {
+        let corners = self.corners().map(|c| {
+            let p = m * nalgebra::Vector4::new(c[0], c[1], c[2], 1.);
+            Vector3::new(p[0], p[1], p[2]) / p[3]
+        });
+
+        let min = corners.iter().fold(corners[0], |min, c| min.min(c));
+        let max = corners.iter().fold(corners[0], |max, c| max.max(c));
+
+        Aabb::from_bounds(min, max)
+    }
+
+    /// Get the closest point within the Aabb to a point
+    pub fn closest_point(&self, point: &Vector3) -> Vector3 {
+        point.clamp(&self.min(), &self.max())
+    }
+
+    /// Get the overlapping box of two Aabbs or None if they are disjoint.
+    /// Boxes touching along a face, edge or corner overlap in a degenerate
+    /// box with a zero extent.
+    pub fn overlap(&self, other: &Aabb) -> Option<Aabb> {
+        let min = self.min().max(&other.min());
+        let max = self.max().min(&other.max());
+
+        if (0..3).all(|i| min[i] <= max[i]) {
+            Some(Aabb::from_bounds(min, max))
+        } else {
+            None
+        }
+    }
+
     /// Get the octant AABB using Morton encoding (Z-order)
     /// to identify the octant. 0 is the front/lower/left
     /// octant and 7 is the back/upper/right octant.
@@ -63,6 +111,68 @@ impl Aabb {
 
         Aabb::new(center, h)
     }
+
+    /// Subdivide into a regular grid of cells with the given number along
+    /// each axis. The cells are ordered with x varying fastest, then y, then
+    /// z. The cell bounds are computed from the grid indices so the grid
+    /// tiles the Aabb without accumulating gaps. A zero count along any axis
+    /// gives no cells.
+    pub fn subdivide(&self, nx: usize, ny: usize, nz: usize) -> Vec<Aabb> {
+        let min = self.min();
+        let size = self.max() - min;
+        let bound = |i: usize, j: usize, k: usize| {
+            min + Vector3::new(
+                size[0] * i as f64 / nx as f64,
+                size[1] * j as f64 / ny as f64,
+                size[2] * k as f64 / nz as f64,
+            )
+        };
+
+        let mut cells = Vec::with_capacity(nx * ny * nz);
+
+        for k in 0..nz {
+            for j in 0..ny {
+                for i in 0..nx {
+                    cells.push(Aabb::from_bounds(
+                        bound(i, j, k),
+                        bound(i + 1, j + 1, k + 1),
+                    ));
+                }
+            }
+        }
+
+        cells
+    }
+
+    /// Get the entry/exit parameters of a ray through the Aabb. The entry
+    /// parameter is clamped at zero for a ray starting inside.
+    pub fn ray_interval(&self, ray: &Ray) -> Option<(f64, f64)> {
+        collision::intersects::interval_aabb_ray(self, ray)
+    }
+}
+
+impl crate::geometry::Bounded for Aabb {
+    fn bounds(&self) -> Aabb {
+        *self
+    }
+}
+
+impl crate::geometry::Transform for Aabb {
+    fn transform(&self, m: &Matrix4) -> Aabb {
+        self.transformed(m)
+    }
+}
+
+impl crate::geometry::Distance<Aabb> for Aabb {
+    fn distance(&self, other: &Aabb) -> f64 {
+        collision::distance::distance_aabb_aabb(self, other)
+    }
+}
+
+impl crate::geometry::Intersection<Ray> for Aabb {
+    fn intersection(&self, other: &Ray) -> Option<Geometry> {
+        collision::intersection::intersection_aabb_ray(self, other)
+    }
 }
 
 impl crate::geometry::Intersects<Aabb> for Aabb {
@@ -71,6 +181,18 @@ impl crate::geometry::Intersects<Aabb> for Aabb {
     }
 }
 
+impl crate::geometry::Intersects<Frustum> for Aabb {
+    fn intersects(&self, other: &Frustum) -> bool {
+        collision::intersects::intersects_aabb_frustum(self, other)
+    }
+}
+
+impl crate::geometry::Intersects<Line> for Aabb {
+    fn intersects(&self, other: &Line) -> bool {
+        collision::intersects::intersects_aabb_line(self, other)
+    }
+}
+
 impl crate::geometry::Intersects<Ray> for Aabb {
     fn intersects(&self, other: &Ray) -> bool {
         collision::intersects::intersects_aabb_ray(self, other)
@@ -94,3 +216,125 @@ impl crate::geometry::Intersects<Vector3> for Aabb {
         collision::intersects::intersects_aabb_vector3(self, other)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::EPSILON;
+
+    #[test]
+    fn corners() {
+        let aabb = Aabb::from_bounds(Vector3::new(0., 1., 2.), Vector3::new(1., 3., 5.));
+        let corners = aabb.corners();
+
+        assert_eq!(corners[0], aabb.min());
+        assert_eq!(corners[7], aabb.max());
+        assert_eq!(corners[1], Vector3::new(0., 1., 5.));
+        assert_eq!(corners[2], Vector3::new(0., 3., 2.));
+        assert_eq!(corners[4], Vector3::new(1., 1., 2.));
+
+        for (i, corner) in corners.iter().enumerate() {
+            assert_eq!(aabb.octant(i).closest_point(corner), *corner);
+        }
+    }
+
+    #[test]
+    fn subdivide() {
+        let aabb = Aabb::unit();
+        let cells = aabb.subdivide(2, 2, 2);
+        let volume: f64 = cells
+            .iter()
+            .map(|c| {
+                let h = c.halfsize();
+                8. * h[0] * h[1] * h[2]
+            })
+            .sum();
+
+        assert_eq!(cells.len(), 8);
+        assert!((volume - 1.).abs() < EPSILON);
+
+        for octant in 0..8 {
+            let expected = aabb.octant(octant);
+            let matches = cells
+                .iter()
+                .filter(|c| c.min() == expected.min() && c.max() == expected.max())
+                .count();
+
+            assert_eq!(matches, 1);
+        }
+
+        let aabb = Aabb::from_bounds(Vector3::new(0., 1., 2.), Vector3::new(1., 3., 5.));
+        let cells = aabb.subdivide(3, 1, 2);
+
+        assert_eq!(cells.len(), 6);
+        assert!((cells[0].min() - aabb.min()).mag() < EPSILON);
+        assert!((cells[5].max() - aabb.max()).mag() < EPSILON);
+        assert!((cells[0].max()[0] - cells[1].min()[0]).abs() < EPSILON);
+        assert!((cells[2].max()[2] - cells[3].min()[2]).abs() < EPSILON);
+
+        assert!(aabb.subdivide(0, 2, 2).is_empty());
+        assert!(aabb.subdivide(2, 2, 0).is_empty());
+    }
+
+    #[test]
+    fn closest_point() {
+        let aabb = Aabb::unit();
+
+        let inside = Vector3::new(0.1, -0.2, 0.3);
+        assert_eq!(aabb.closest_point(&inside), inside);
+
+        let face = Vector3::new(2., 0.1, -0.2);
+        assert_eq!(aabb.closest_point(&face), Vector3::new(0.5, 0.1, -0.2));
+
+        let edge = Vector3::new(2., -3., 0.25);
+        assert_eq!(aabb.closest_point(&edge), Vector3::new(0.5, -0.5, 0.25));
+
+        let corner = Vector3::new(-2., 3., 4.);
+        assert_eq!(aabb.closest_point(&corner), Vector3::new(-0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn transformed() {
+        let angle = std::f64::consts::FRAC_PI_4;
+        let axis = nalgebra::Vector3::z_axis();
+        let m = Matrix4::from_axis_angle(&axis, angle).append_translation(&[1., 2., 3.].into());
+        let aabb = Aabb::unit().transformed(&m);
+        let h = 0.5 * 2f64.sqrt();
+
+        assert!((aabb.center() - Vector3::new(1., 2., 3.)).mag() < EPSILON);
+        assert!((aabb.halfsize() - Vector3::new(h, h, 0.5)).mag() < EPSILON);
+
+        let aabb = Aabb::unit().transformed(&Matrix4::identity());
+        assert_eq!(aabb.min(), Aabb::unit().min());
+        assert_eq!(aabb.max(), Aabb::unit().max());
+    }
+
+    #[test]
+    fn overlap() {
+        let a = Aabb::from_bounds(Vector3::new(0., 0., 0.), Vector3::new(2., 2., 2.));
+        let b = Aabb::from_bounds(Vector3::new(1., -1., 1.), Vector3::new(3., 1., 4.));
+        let overlap = a.overlap(&b).unwrap();
+
+        assert_eq!(overlap.min(), Vector3::new(1., 0., 1.));
+        assert_eq!(overlap.max(), Vector3::new(2., 1., 2.));
+    }
+
+    #[test]
+    fn overlap_touching() {
+        let a = Aabb::from_bounds(Vector3::new(0., 0., 0.), Vector3::new(1., 1., 1.));
+        let b = Aabb::from_bounds(Vector3::new(1., 0., 0.), Vector3::new(2., 1., 1.));
+        let overlap = a.overlap(&b).unwrap();
+
+        assert_eq!(overlap.min(), Vector3::new(1., 0., 0.));
+        assert_eq!(overlap.max(), Vector3::new(1., 1., 1.));
+        assert_eq!(overlap.halfsize()[0], 0.);
+    }
+
+    #[test]
+    fn overlap_disjoint() {
+        let a = Aabb::from_bounds(Vector3::new(0., 0., 0.), Vector3::new(1., 1., 1.));
+        let b = Aabb::from_bounds(Vector3::new(0., 1.5, 0.), Vector3::new(1., 2., 1.));
+
+        assert!(a.overlap(&b).is_none());
+    }
+}
@@ -1,2 +1,3 @@
+pub mod distance;
 pub mod intersection;
 pub mod intersects;
@@ -0,0 +1,14 @@
+pub mod aabb_aabb;
+pub mod line_ray;
+pub mod line_triangle;
+pub mod ray_ray;
+pub mod sphere_sphere;
+pub mod sphere_triangle;
+
+// Re-exports
+pub use aabb_aabb::distance_aabb_aabb;
+pub use line_ray::distance_line_ray;
+pub use line_triangle::distance_line_triangle;
+pub use ray_ray::distance_ray_ray;
+pub use sphere_sphere::distance_sphere_sphere;
+pub use sphere_triangle::distance_sphere_triangle;
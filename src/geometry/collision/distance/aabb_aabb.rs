@@ -0,0 +1,56 @@
+use crate::geometry::{Aabb, Vector3};
+
+/// Compute the shortest distance between two AABBs. This is zero when the
+/// AABBs overlap.
+pub fn distance_aabb_aabb(a: &Aabb, b: &Aabb) -> f64 {
+    let min_a = a.min();
+    let max_a = a.max();
+    let min_b = b.min();
+    let max_b = b.max();
+
+    let mut gap = Vector3::zeros();
+
+    for i in 0..3 {
+        if max_a[i] < min_b[i] {
+            gap[i] = min_b[i] - max_a[i];
+        } else if max_b[i] < min_a[i] {
+            gap[i] = min_a[i] - max_b[i];
+        }
+    }
+
+    gap.mag()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn overlap() {
+        let a = Aabb::unit();
+        let c = Vector3::new(0.4, 0.4, 0.4);
+        let h = Vector3::new(0.2, 0.2, 0.2);
+        let b = Aabb::new(c, h);
+
+        assert_eq!(distance_aabb_aabb(&a, &b), 0.);
+    }
+
+    #[test]
+    fn separated_axis() {
+        let a = Aabb::unit();
+        let c = Vector3::new(2., 0., 0.);
+        let b = Aabb::new(c, Vector3::new(0.5, 0.5, 0.5));
+
+        assert_eq!(distance_aabb_aabb(&a, &b), 1.);
+        assert_eq!(distance_aabb_aabb(&b, &a), 1.);
+    }
+
+    #[test]
+    fn separated_diagonal() {
+        let a = Aabb::unit();
+        let c = Vector3::new(2., 2., 0.);
+        let b = Aabb::new(c, Vector3::new(0.5, 0.5, 0.5));
+
+        assert_eq!(distance_aabb_aabb(&a, &b), 2f64.sqrt());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::geometry::{Line, Ray, Vector3, EPSILON};
+
+/// Compute the shortest distance between a Line segment and a Ray. When the
+/// closest points of the supporting lines lie within the segment and ahead
+/// of the origin that pair is used. Otherwise (including parallel cases) the
+/// closest points lie at an endpoint of the segment or the origin of the Ray,
+/// so those candidates are compared.
+pub fn distance_line_ray(l: &Line, r: &Ray) -> f64 {
+    let d1 = l.direction();
+    let d2 = r.direction();
+    let w = l.p() - r.origin();
+    let a = Vector3::dot(&d1, &d1);
+    let b = Vector3::dot(&d1, &d2);
+    let e = Vector3::dot(&d2, &d2);
+    let denom = a * e - b * b;
+
+    if denom > EPSILON * a * e {
+        let c = Vector3::dot(&d1, &w);
+        let f = Vector3::dot(&d2, &w);
+        let s = (b * f - c * e) / denom;
+        let t = (a * f - b * c) / denom;
+
+        if (0. ..=1.).contains(&s) && t >= 0. {
+            return (l.p() + d1 * s - r.origin() - d2 * t).mag();
+        }
+    }
+
+    let endpoints = [l.p(), l.q()]
+        .into_iter()
+        .map(|p| (r.closest_point(&p) - p).mag());
+
+    let origin = Line::new(r.origin(), r.origin());
+    let (p, q) = l.closest_points(&origin);
+
+    endpoints.fold((p - q).mag(), f64::min)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn skew() {
+        let l = Line::new(Vector3::new(-1., 0., 0.), Vector3::new(1., 0., 0.));
+        let r = Ray::new(Vector3::new(0., -1., 2.), Vector3::new(0., 1., 0.));
+
+        assert!((distance_line_ray(&l, &r) - 2.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn skew_past_endpoint() {
+        let l = Line::new(Vector3::new(1., 0., 0.), Vector3::new(2., 0., 0.));
+        let r = Ray::new(Vector3::new(0., -1., 2.), Vector3::new(0., 1., 0.));
+        let expected = 5_f64.sqrt();
+
+        assert!((distance_line_ray(&l, &r) - expected).abs() < EPSILON);
+    }
+
+    #[test]
+    fn parallel() {
+        let l = Line::new(Vector3::new(-3., 2., 0.), Vector3::new(-1., 2., 0.));
+        let r = Ray::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let expected = 5_f64.sqrt();
+
+        assert!((distance_line_ray(&l, &r) - expected).abs() < EPSILON);
+    }
+
+    #[test]
+    fn intersecting() {
+        let l = Line::new(Vector3::new(1., -1., 0.), Vector3::new(1., 1., 0.));
+        let r = Ray::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+
+        assert!(distance_line_ray(&l, &r) < EPSILON);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::geometry::collision::intersects::hit_ray_triangle;
+use crate::geometry::{Line, Ray, Triangle};
+
+/// Compute the shortest distance between a Line segment and a Triangle. This
+/// is zero when the segment pierces or touches the Triangle. Otherwise the
+/// closest points lie on an endpoint of the segment or an edge of the
+/// Triangle, so those candidates are compared.
+pub fn distance_line_triangle(l: &Line, t: &Triangle) -> f64 {
+    let ray = Ray::new(l.p(), l.direction());
+
+    if hit_ray_triangle(&ray, t, false).is_some_and(|s| s <= 1.) {
+        return 0.;
+    }
+
+    let endpoints = [l.p(), l.q()]
+        .into_iter()
+        .map(|p| (t.closest_point(&p) - p).mag());
+
+    let edges = (0..3).map(|i| {
+        let edge = Line::new(t[i], t[(i + 1) % 3]);
+        let (p, q) = l.closest_points(&edge);
+        (p - q).mag()
+    });
+
+    endpoints.chain(edges).fold(f64::INFINITY, f64::min)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::{Vector3, EPSILON};
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Vector3::new(0., 0., 0.),
+            Vector3::new(1., 0., 0.),
+            Vector3::new(0., 1., 0.),
+        )
+    }
+
+    #[test]
+    fn piercing() {
+        let l = Line::new(Vector3::new(0.25, 0.25, -1.), Vector3::new(0.25, 0.25, 1.));
+        assert_eq!(distance_line_triangle(&l, &triangle()), 0.);
+    }
+
+    #[test]
+    fn parallel_above() {
+        let l = Line::new(Vector3::new(-1., 0.2, 0.5), Vector3::new(2., 0.2, 0.5));
+        assert!((distance_line_triangle(&l, &triangle()) - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn beside_edge() {
+        let l = Line::new(Vector3::new(1., 1., -1.), Vector3::new(1., 1., 1.));
+        let expected = 0.5_f64.sqrt();
+
+        assert!((distance_line_triangle(&l, &triangle()) - expected).abs() < EPSILON);
+    }
+
+    #[test]
+    fn short_of_face() {
+        let l = Line::new(Vector3::new(0.25, 0.25, 3.), Vector3::new(0.25, 0.25, 1.));
+        assert!((distance_line_triangle(&l, &triangle()) - 1.).abs() < EPSILON);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::geometry::{Ray, Vector3, EPSILON};
+
+/// Compute the shortest distance between two Rays. When the closest points of
+/// the supporting lines lie ahead of both origins that pair is used.
+/// Otherwise (including parallel Rays) the closest points lie at one of the
+/// origins, so the distances from each origin to the other Ray are compared.
+pub fn distance_ray_ray(r1: &Ray, r2: &Ray) -> f64 {
+    let d1 = r1.direction();
+    let d2 = r2.direction();
+    let r = r1.origin() - r2.origin();
+    let a = Vector3::dot(&d1, &d1);
+    let b = Vector3::dot(&d1, &d2);
+    let e = Vector3::dot(&d2, &d2);
+    let denom = a * e - b * b;
+
+    if denom > EPSILON * a * e {
+        let c = Vector3::dot(&d1, &r);
+        let f = Vector3::dot(&d2, &r);
+        let s = (b * f - c * e) / denom;
+        let t = (a * f - b * c) / denom;
+
+        if s >= 0. && t >= 0. {
+            return (r1.origin() + d1 * s - r2.origin() - d2 * t).mag();
+        }
+    }
+
+    let d12 = (r2.closest_point(&r1.origin()) - r1.origin()).mag();
+    let d21 = (r1.closest_point(&r2.origin()) - r2.origin()).mag();
+
+    d12.min(d21)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn skew() {
+        let r1 = Ray::new(Vector3::new(-1., 0., 0.), Vector3::new(1., 0., 0.));
+        let r2 = Ray::new(Vector3::new(0., -1., 2.), Vector3::new(0., 1., 0.));
+
+        assert!((distance_ray_ray(&r1, &r2) - 2.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn skew_behind() {
+        let r1 = Ray::new(Vector3::new(1., 0., 0.), Vector3::new(1., 0., 0.));
+        let r2 = Ray::new(Vector3::new(0., -1., 2.), Vector3::new(0., 1., 0.));
+        let expected = 5_f64.sqrt();
+
+        assert!((distance_ray_ray(&r1, &r2) - expected).abs() < EPSILON);
+    }
+
+    #[test]
+    fn parallel() {
+        let r1 = Ray::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let r2 = Ray::new(Vector3::new(5., 3., 0.), Vector3::new(1., 0., 0.));
+
+        assert!((distance_ray_ray(&r1, &r2) - 3.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn parallel_opposite() {
+        let r1 = Ray::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let r2 = Ray::new(Vector3::new(-4., 3., 0.), Vector3::new(-1., 0., 0.));
+
+        assert!((distance_ray_ray(&r1, &r2) - 5.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn intersecting() {
+        let r1 = Ray::new(Vector3::new(-1., 0., 0.), Vector3::new(1., 0., 0.));
+        let r2 = Ray::new(Vector3::new(0., -1., 0.), Vector3::new(0., 2., 0.));
+
+        assert!(distance_ray_ray(&r1, &r2) < EPSILON);
+    }
+}
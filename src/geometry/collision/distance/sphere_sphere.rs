@@ -0,0 +1,31 @@
+use crate::geometry::Sphere;
+
+/// Compute the shortest distance between two Spheres. This is zero when the
+/// Spheres overlap.
+pub fn distance_sphere_sphere(a: &Sphere, b: &Sphere) -> f64 {
+    let d = (a.center() - b.center()).mag();
+    (d - a.radius() - b.radius()).max(0.)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Vector3;
+
+    #[test]
+    fn concentric() {
+        let o = Vector3::new(1., 2., 3.);
+        let a = Sphere::new(o, 1.);
+        let b = Sphere::new(o, 0.5);
+
+        assert_eq!(distance_sphere_sphere(&a, &b), 0.);
+    }
+
+    #[test]
+    fn separated() {
+        let a = Sphere::new(Vector3::new(0., 0., 0.), 1.);
+        let b = Sphere::new(Vector3::new(0., 4., 0.), 0.5);
+
+        assert_eq!(distance_sphere_sphere(&a, &b), 2.5);
+    }
+}
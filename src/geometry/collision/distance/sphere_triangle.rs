@@ -0,0 +1,41 @@
+use crate::geometry::{Sphere, Triangle};
+
+/// Compute the signed gap between the surface of a Sphere and a Triangle.
+/// This is negative when they overlap, giving the penetration depth of the
+/// Triangle into the Sphere.
+pub fn distance_sphere_triangle(s: &Sphere, t: &Triangle) -> f64 {
+    let closest = t.closest_point(&s.center());
+    (closest - s.center()).mag() - s.radius()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::{Vector3, EPSILON};
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Vector3::new(0., 0., 0.),
+            Vector3::new(1., 0., 0.),
+            Vector3::new(0., 1., 0.),
+        )
+    }
+
+    #[test]
+    fn above_face() {
+        let s = Sphere::new(Vector3::new(0.25, 0.25, 2.), 0.5);
+        assert!((distance_sphere_triangle(&s, &triangle()) - 1.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn past_edge() {
+        let s = Sphere::new(Vector3::new(0.5, -3., 4.), 1.);
+        assert!((distance_sphere_triangle(&s, &triangle()) - 4.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn overlapping() {
+        let s = Sphere::new(Vector3::new(0.25, 0.25, 0.25), 1.);
+        assert!((distance_sphere_triangle(&s, &triangle()) + 0.75).abs() < EPSILON);
+    }
+}
@@ -1,6 +1,16 @@
+pub mod aabb_ray;
+pub mod line_line;
+pub mod line_sphere;
 pub mod line_triangle;
+pub mod plane_ray;
+pub mod ray_sphere;
 pub mod triangle_triangle;
 
 // Re-exports
+pub use aabb_ray::intersection_aabb_ray;
+pub use line_line::intersection_line_line;
+pub use line_sphere::intersection_line_sphere;
 pub use line_triangle::intersection_line_triangle;
-pub use triangle_triangle::intersection_triangle_triangle;
+pub use plane_ray::intersection_plane_ray;
+pub use ray_sphere::intersection_ray_sphere;
+pub use triangle_triangle::{intersection_triangle_triangle, intersection_triangle_triangle_eps};
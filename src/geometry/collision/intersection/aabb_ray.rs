@@ -0,0 +1,45 @@
+use crate::geometry::collision::intersects::interval_aabb_ray;
+use crate::geometry::{Aabb, Geometry, Ray};
+
+/// Compute the intersection of a Ray with an AABB as the Point at which the
+/// ray enters the AABB. This is the ray origin for a ray starting inside.
+pub fn intersection_aabb_ray(a: &Aabb, r: &Ray) -> Option<Geometry> {
+    let (tmin, _) = interval_aabb_ray(a, r)?;
+    Some(Geometry::Point(r.origin() + r.direction() * tmin))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::{Vector3, EPSILON};
+
+    #[test]
+    fn entry() {
+        let a = Aabb::unit();
+        let r = Ray::new(Vector3::new(-2., 0.1, 0.2), Vector3::new(1., 0., 0.));
+
+        match intersection_aabb_ray(&a, &r) {
+            Some(Geometry::Point(p)) => assert!((p - Vector3::new(-0.5, 0.1, 0.2)).mag() < EPSILON),
+            _ => panic!("expected a point"),
+        }
+    }
+
+    #[test]
+    fn inside() {
+        let a = Aabb::unit();
+        let r = Ray::new(Vector3::new(0.1, 0.1, 0.1), Vector3::new(0., 0., 1.));
+
+        match intersection_aabb_ray(&a, &r) {
+            Some(Geometry::Point(p)) => assert_eq!(p, r.origin()),
+            _ => panic!("expected a point"),
+        }
+    }
+
+    #[test]
+    fn miss() {
+        let a = Aabb::unit();
+        let r = Ray::new(Vector3::new(-2., 0., 0.), Vector3::new(-1., 0., 0.));
+
+        assert!(intersection_aabb_ray(&a, &r).is_none());
+    }
+}
@@ -0,0 +1,97 @@
+use crate::geometry::{Geometry, Line, Vector3, EPSILON};
+
+/// Compute the intersection of two Line segments. Segments meeting at a
+/// single point return a Point geometry and overlapping collinear segments
+/// return the shared Line segment.
+pub fn intersection_line_line(a: &Line, b: &Line) -> Option<Geometry> {
+    let (p, q) = a.closest_points(b);
+
+    if (p - q).mag() > EPSILON {
+        return None;
+    }
+
+    let d1 = a.direction();
+    let d2 = b.direction();
+    let n = Vector3::cross(&d1, &d2).mag();
+
+    // Collinear segments may overlap along a shared sub-segment
+    if n <= EPSILON * d1.mag() * d2.mag() && a.length() > EPSILON && b.length() > EPSILON {
+        let l = Vector3::dot(&d1, &d1);
+        let s0 = Vector3::dot(&(b.p() - a.p()), &d1) / l;
+        let s1 = Vector3::dot(&(b.q() - a.p()), &d1) / l;
+        let lo = s0.min(s1).max(0.);
+        let hi = s0.max(s1).min(1.);
+
+        if (hi - lo) * a.length() > EPSILON {
+            let line = Line::new(a.p() + d1 * lo, a.p() + d1 * hi);
+            return Some(Geometry::Line(line));
+        }
+    }
+
+    Some(Geometry::Point(p))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn crossing() {
+        let a = Line::new(Vector3::new(-1., 0., 1.), Vector3::new(1., 0., 1.));
+        let b = Line::new(Vector3::new(0.25, -1., 1.), Vector3::new(0.25, 1., 1.));
+
+        match intersection_line_line(&a, &b) {
+            Some(Geometry::Point(p)) => assert!((p - Vector3::new(0.25, 0., 1.)).mag() < EPSILON),
+            _ => panic!("expected a point"),
+        }
+    }
+
+    #[test]
+    fn skew() {
+        let a = Line::new(Vector3::new(-1., 0., 0.), Vector3::new(1., 0., 0.));
+        let b = Line::new(Vector3::new(0., -1., 1.), Vector3::new(0., 1., 1.));
+
+        assert!(intersection_line_line(&a, &b).is_none());
+    }
+
+    #[test]
+    fn parallel() {
+        let a = Line::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let b = Line::new(Vector3::new(0., 1., 0.), Vector3::new(1., 1., 0.));
+
+        assert!(intersection_line_line(&a, &b).is_none());
+    }
+
+    #[test]
+    fn collinear_overlap() {
+        let a = Line::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let b = Line::new(Vector3::new(2., 0., 0.), Vector3::new(0.5, 0., 0.));
+
+        match intersection_line_line(&a, &b) {
+            Some(Geometry::Line(line)) => {
+                assert!((line.p() - Vector3::new(0.5, 0., 0.)).mag() < EPSILON);
+                assert!((line.q() - Vector3::new(1., 0., 0.)).mag() < EPSILON);
+            }
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn collinear_touching() {
+        let a = Line::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let b = Line::new(Vector3::new(1., 0., 0.), Vector3::new(2., 0., 0.));
+
+        match intersection_line_line(&a, &b) {
+            Some(Geometry::Point(p)) => assert!((p - Vector3::new(1., 0., 0.)).mag() < EPSILON),
+            _ => panic!("expected a point"),
+        }
+    }
+
+    #[test]
+    fn collinear_disjoint() {
+        let a = Line::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let b = Line::new(Vector3::new(2., 0., 0.), Vector3::new(3., 0., 0.));
+
+        assert!(intersection_line_line(&a, &b).is_none());
+    }
+}
@@ -0,0 +1,70 @@
+use crate::geometry::collision::intersects::interval_line_sphere;
+use crate::geometry::{Geometry, Line, Sphere, EPSILON};
+
+/// Compute the intersection of a Line segment with a Sphere. The portion of
+/// the segment inside the Sphere is returned as a Line, or as a Point when
+/// the segment only touches the Sphere.
+pub fn intersection_line_sphere(l: &Line, s: &Sphere) -> Option<Geometry> {
+    let (tmin, tmax) = interval_line_sphere(l, s)?;
+    let p = l.p() + l.direction() * tmin;
+    let q = l.p() + l.direction() * tmax;
+
+    if (q - p).mag() <= EPSILON {
+        Some(Geometry::Point(p))
+    } else {
+        Some(Geometry::Line(Line::new(p, q)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Vector3;
+
+    #[test]
+    fn diametral() {
+        let l = Line::new(Vector3::new(-2., 0., 0.), Vector3::new(2., 0., 0.));
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        match intersection_line_sphere(&l, &s) {
+            Some(Geometry::Line(line)) => {
+                assert!((line.p() - Vector3::new(-1., 0., 0.)).mag() < EPSILON);
+                assert!((line.q() - Vector3::new(1., 0., 0.)).mag() < EPSILON);
+            }
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn clamped() {
+        let l = Line::new(Vector3::zeros(), Vector3::new(0.5, 0., 0.));
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        match intersection_line_sphere(&l, &s) {
+            Some(Geometry::Line(line)) => {
+                assert_eq!(line.p(), l.p());
+                assert_eq!(line.q(), l.q());
+            }
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn touching() {
+        let l = Line::new(Vector3::new(-2., 0., 0.), Vector3::new(-1., 0., 0.));
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        match intersection_line_sphere(&l, &s) {
+            Some(Geometry::Point(p)) => assert!((p - Vector3::new(-1., 0., 0.)).mag() < EPSILON),
+            _ => panic!("expected a point"),
+        }
+    }
+
+    #[test]
+    fn short() {
+        let l = Line::new(Vector3::new(-3., 0., 0.), Vector3::new(-2., 0., 0.));
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        assert!(intersection_line_sphere(&l, &s).is_none());
+    }
+}
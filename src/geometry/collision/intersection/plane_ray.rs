@@ -0,0 +1,54 @@
+use crate::geometry::{Geometry, Plane, Ray, Vector3, EPSILON};
+
+/// Compute the intersection of a Ray with a Plane as the Point at which the
+/// ray crosses the Plane. A ray parallel to the Plane (including one lying
+/// in it) or crossing it behind its origin does not intersect.
+pub fn intersection_plane_ray(p: &Plane, r: &Ray) -> Option<Geometry> {
+    let d = Vector3::dot(&p.normal(), &r.direction());
+
+    if d.abs() <= EPSILON * r.direction().mag() {
+        return None;
+    }
+
+    let t = -p.signed_distance(&r.origin()) / d;
+
+    if t < 0. {
+        return None;
+    }
+
+    Some(Geometry::Point(r.origin() + r.direction() * t))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hit() {
+        let p = Plane::new(Vector3::new(0., 0., 1.), Vector3::new(0., 1., 1.));
+        let r = Ray::new(Vector3::new(0., 0., 3.), Vector3::new(0., 0., -2.));
+
+        match intersection_plane_ray(&p, &r) {
+            Some(Geometry::Point(q)) => assert!((q - Vector3::new(0., 0., 1.)).mag() < EPSILON),
+            _ => panic!("expected a point"),
+        }
+    }
+
+    #[test]
+    fn behind() {
+        let p = Plane::new(Vector3::zeros(), Vector3::new(0., 0., 1.));
+        let r = Ray::new(Vector3::new(0., 0., 1.), Vector3::new(0., 0., 1.));
+
+        assert!(intersection_plane_ray(&p, &r).is_none());
+    }
+
+    #[test]
+    fn parallel() {
+        let p = Plane::new(Vector3::zeros(), Vector3::new(0., 0., 1.));
+        let r = Ray::new(Vector3::new(0., 0., 1.), Vector3::new(1., 0., 0.));
+        let s = Ray::new(Vector3::zeros(), Vector3::new(1., 1., 0.));
+
+        assert!(intersection_plane_ray(&p, &r).is_none());
+        assert!(intersection_plane_ray(&p, &s).is_none());
+    }
+}
@@ -0,0 +1,71 @@
+use crate::geometry::collision::intersects::interval_ray_sphere;
+use crate::geometry::{Geometry, Line, Ray, Sphere, EPSILON};
+
+/// Compute the intersection of a Ray with a Sphere. A ray passing through
+/// the Sphere returns the Line between its entry and exit points and a
+/// tangent ray returns the Point of contact. The entry point is the ray
+/// origin for a ray starting inside the Sphere.
+pub fn intersection_ray_sphere(r: &Ray, s: &Sphere) -> Option<Geometry> {
+    let (tmin, tmax) = interval_ray_sphere(r, s)?;
+    let p = r.origin() + r.direction() * tmin;
+    let q = r.origin() + r.direction() * tmax;
+
+    if (q - p).mag() <= EPSILON {
+        Some(Geometry::Point(p))
+    } else {
+        Some(Geometry::Line(Line::new(p, q)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Vector3;
+
+    #[test]
+    fn through() {
+        let r = Ray::new(Vector3::new(-2., 0., 0.), Vector3::new(1., 0., 0.));
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        match intersection_ray_sphere(&r, &s) {
+            Some(Geometry::Line(line)) => {
+                assert!((line.p() - Vector3::new(-1., 0., 0.)).mag() < EPSILON);
+                assert!((line.q() - Vector3::new(1., 0., 0.)).mag() < EPSILON);
+            }
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn inside() {
+        let r = Ray::new(Vector3::zeros(), Vector3::new(0., 2., 0.));
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        match intersection_ray_sphere(&r, &s) {
+            Some(Geometry::Line(line)) => {
+                assert!((line.p() - Vector3::zeros()).mag() < EPSILON);
+                assert!((line.q() - Vector3::new(0., 1., 0.)).mag() < EPSILON);
+            }
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn tangent() {
+        let r = Ray::new(Vector3::new(-2., 1., 0.), Vector3::new(1., 0., 0.));
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        match intersection_ray_sphere(&r, &s) {
+            Some(Geometry::Point(p)) => assert!((p - Vector3::new(0., 1., 0.)).mag() < EPSILON),
+            _ => panic!("expected a point"),
+        }
+    }
+
+    #[test]
+    fn miss() {
+        let r = Ray::new(Vector3::new(-2., 0., 0.), Vector3::new(-1., 0., 0.));
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        assert!(intersection_ray_sphere(&r, &s).is_none());
+    }
+}
@@ -1,9 +1,333 @@
-use crate::geometry::{Geometry, Triangle};
+use crate::geometry::{Geometry, Line, Triangle, Vector3, EPSILON};
 
 /// Compute the intersection of a Triangle/Triangle. For most cases, this
 /// will return a line segment. In the case of coplanar triangles, this
-/// may return a point, line segment, or a triangle.
-pub fn intersection_triangle_triangle(_t0: &Triangle, _t1: &Triangle) -> Option<Geometry> {
-    // TODO: implement
-    unimplemented!();
+/// may return a point, line segment, triangle, or a convex polygon. A
+/// degenerate triangle has no plane, so coplanar input with a degenerate
+/// triangle returns None.
+pub fn intersection_triangle_triangle(t0: &Triangle, t1: &Triangle) -> Option<Geometry> {
+    intersection_triangle_triangle_eps(t0, t1, EPSILON)
+}
+
+/// Compute the intersection of a Triangle/Triangle using a tolerance on the
+/// plane distances and the overlap
+pub fn intersection_triangle_triangle_eps(
+    t0: &Triangle,
+    t1: &Triangle,
+    eps: f64,
+) -> Option<Geometry> {
+    let p0 = t0.plane();
+    let p1 = t1.plane();
+    let n0 = p0.normal();
+    let n1 = p1.normal();
+
+    // Compute the signed distances of each triangle's vertices to the
+    // plane of the other triangle.
+    let d0 = [0, 1, 2].map(|i| p1.signed_distance_eps(&t0[i], eps));
+    let d1 = [0, 1, 2].map(|i| p0.signed_distance_eps(&t1[i], eps));
+
+    if d0.iter().all(|&d| d == 0.) {
+        return intersection_coplanar(t0, t1, eps);
+    }
+
+    let s0 = plane_crossing(t0, &d0)?;
+    let s1 = plane_crossing(t1, &d1)?;
+
+    // Both segments lie on the line of intersection of the two planes so
+    // the overlap is computed from their projection onto the line.
+    let direction = Vector3::cross(&n0, &n1);
+    let (a0, a1) = project_segment(&s0, &direction);
+    let (b0, b1) = project_segment(&s1, &direction);
+
+    let (p, tp) = if a0.1 >= b0.1 { a0 } else { b0 };
+    let (q, tq) = if a1.1 <= b1.1 { a1 } else { b1 };
+
+    if tq - tp < -eps {
+        return None;
+    }
+
+    if (q - p).mag() <= eps {
+        return Some(Geometry::Point(p));
+    }
+
+    Some(Geometry::Line(Line::new(p, q)))
+}
+
+/// Compute the intersection of coplanar triangles by clipping the first
+/// triangle against the edges of the second. The side of an edge is found
+/// from the projection onto the plane normal to the dominant axis of the
+/// normal and scaled to a distance within the plane.
+fn intersection_coplanar(t0: &Triangle, t1: &Triangle, eps: f64) -> Option<Geometry> {
+    if t0.is_degenerate() || t1.is_degenerate() {
+        return None;
+    }
+
+    let n1 = t1.unit_normal();
+    let axis = n1.abs().max_index();
+    let mut points = vec![t0[0], t0[1], t0[2]];
+
+    for i in 0..3 {
+        let a = t1[i];
+        let b = t1[(i + 1) % 3];
+        let scale = (b - a).mag() * n1[axis];
+        let side = |p: &Vector3| Vector3::cross(&(b - a), &(*p - a))[axis] / scale;
+        let mut clipped = vec![];
+
+        for j in 0..points.len() {
+            let p = points[j];
+            let q = points[(j + 1) % points.len()];
+            let (dp, dq) = (side(&p), side(&q));
+
+            if dp >= -eps {
+                clipped.push(p);
+            }
+
+            if (dp >= -eps) != (dq >= -eps) {
+                clipped.push(p + (q - p) * (dp / (dp - dq)));
+            }
+        }
+
+        points = clipped;
+    }
+
+    points.dedup_by(|p, q| (*p - *q).mag() <= eps);
+
+    while points.len() > 1 && (points[0] - points[points.len() - 1]).mag() <= eps {
+        points.pop();
+    }
+
+    match points.len() {
+        0 => None,
+        1 => Some(Geometry::Point(points[0])),
+        _ if is_collinear(&points, eps) => {
+            let direction = points[1] - points[0];
+            let t = |p: &Vector3| Vector3::dot(&direction, &(*p - points[0]));
+            let p = points.iter().min_by(|a, b| t(a).total_cmp(&t(b)))?;
+            let q = points.iter().max_by(|a, b| t(a).total_cmp(&t(b)))?;
+            Some(Geometry::Line(Line::new(*p, *q)))
+        }
+        3 => Some(Geometry::Triangle(Triangle::new(
+            points[0], points[1], points[2],
+        ))),
+        _ => Some(Geometry::Polygon(points)),
+    }
+}
+
+/// Check if the points lie within a distance eps of the line through the
+/// first two points
+fn is_collinear(points: &[Vector3], eps: f64) -> bool {
+    let direction = points[1] - points[0];
+    let length = direction.mag();
+
+    points
+        .iter()
+        .all(|p| Vector3::cross(&direction, &(*p - points[0])).mag() <= eps * length)
+}
+
+/// Compute the segment where a triangle crosses a plane from the signed
+/// distances of its vertices to the plane.
+fn plane_crossing(t: &Triangle, d: &[f64; 3]) -> Option<Line> {
+    let mut points = vec![];
+
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+
+        if d[i] == 0. {
+            points.push(t[i]);
+        } else if d[j] != 0. && (d[i] < 0.) != (d[j] < 0.) {
+            let s = d[i] / (d[i] - d[j]);
+            points.push(t[i] + (t[j] - t[i]) * s);
+        }
+    }
+
+    match points.len() {
+        1 => Some(Line::new(points[0], points[0])),
+        2 => Some(Line::new(points[0], points[1])),
+        _ => None,
+    }
+}
+
+/// Project a segment onto a direction and return its endpoints ordered by
+/// their parameter along the direction.
+fn project_segment(s: &Line, direction: &Vector3) -> ((Vector3, f64), (Vector3, f64)) {
+    let tp = Vector3::dot(direction, &s.p());
+    let tq = Vector3::dot(direction, &s.q());
+
+    if tp <= tq {
+        ((s.p(), tp), (s.q(), tq))
+    } else {
+        ((s.q(), tq), (s.p(), tp))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn line() {
+        let a = Vector3::new(0., 0., 0.);
+        let b = Vector3::new(2., 0., 0.);
+        let c = Vector3::new(2., 2., 0.);
+        let t0 = Triangle::new(a, b, c);
+
+        let d = Vector3::new(1., 0.1, -0.5);
+        let e = Vector3::new(1., 0.1, 1.);
+        let f = Vector3::new(1., 1.5, 1.);
+        let t1 = Triangle::new(d, e, f);
+
+        match intersection_triangle_triangle(&t0, &t1) {
+            Some(Geometry::Line(line)) => {
+                let (p, q) = line.vertices();
+                let (p, q) = if p[1] < q[1] { (p, q) } else { (q, p) };
+
+                assert!((p - Vector3::new(1., 0.1, 0.)).mag() < EPSILON);
+                assert!((q - Vector3::new(1., 0.566666666666, 0.)).mag() < 1e-9);
+            }
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn point() {
+        let a = Vector3::new(0., 0., 0.);
+        let b = Vector3::new(2., 0., 0.);
+        let c = Vector3::new(2., 2., 0.);
+        let t0 = Triangle::new(a, b, c);
+
+        let d = Vector3::new(1., 0.5, 0.);
+        let e = Vector3::new(1., 0.5, 1.);
+        let f = Vector3::new(1.5, 0.5, 1.);
+        let t1 = Triangle::new(d, e, f);
+
+        match intersection_triangle_triangle(&t0, &t1) {
+            Some(Geometry::Point(p)) => {
+                assert!((p - Vector3::new(1., 0.5, 0.)).mag() < EPSILON);
+            }
+            _ => panic!("expected a point"),
+        }
+    }
+
+    #[test]
+    fn miss() {
+        let a = Vector3::new(0., 0., 0.);
+        let b = Vector3::new(1., 0., 0.);
+        let c = Vector3::new(1., 1., 0.);
+        let t0 = Triangle::new(a, b, c);
+
+        let d = Vector3::new(3., 0.1, -0.5);
+        let e = Vector3::new(3., 0.1, 1.);
+        let f = Vector3::new(3., 1.5, 1.);
+        let t1 = Triangle::new(d, e, f);
+
+        assert!(intersection_triangle_triangle(&t0, &t1).is_none());
+    }
+
+    #[test]
+    fn coplanar_polygon() {
+        let t0 = Triangle::new(
+            Vector3::new(0., 0., 1.),
+            Vector3::new(3., 0., 1.),
+            Vector3::new(1.5, 3., 1.),
+        );
+
+        let t1 = Triangle::new(
+            Vector3::new(0., 2., 1.),
+            Vector3::new(1.5, -1., 1.),
+            Vector3::new(3., 2., 1.),
+        );
+
+        match intersection_triangle_triangle(&t0, &t1) {
+            Some(Geometry::Polygon(points)) => {
+                assert_eq!(points.len(), 6);
+
+                for p in points.iter() {
+                    assert!(t0.contains(p, 1e-9));
+                    assert!(t1.contains(p, 1e-9));
+                }
+            }
+            _ => panic!("expected a polygon"),
+        }
+    }
+
+    #[test]
+    fn coplanar_triangle() {
+        let t0 = Triangle::new(
+            Vector3::new(0., 0., 0.),
+            Vector3::new(0., 4., 0.),
+            Vector3::new(0., 0., 4.),
+        );
+
+        let t1 = Triangle::new(
+            Vector3::new(0., 1., 1.),
+            Vector3::new(0., 1., 2.),
+            Vector3::new(0., 2., 1.),
+        );
+
+        match intersection_triangle_triangle(&t0, &t1) {
+            Some(Geometry::Triangle(t)) => {
+                assert!((t.area() - t1.area()).abs() < EPSILON);
+                assert!((t.center() - t1.center()).mag() < EPSILON);
+            }
+            _ => panic!("expected a triangle"),
+        }
+    }
+
+    #[test]
+    fn coplanar_touching() {
+        let a = Vector3::new(0., 0., 0.);
+        let b = Vector3::new(1., 0., 0.);
+        let c = Vector3::new(0., 1., 0.);
+        let t0 = Triangle::new(a, b, c);
+
+        let t1 = Triangle::new(b, Vector3::new(1., 1., 0.), c);
+
+        match intersection_triangle_triangle(&t0, &t1) {
+            Some(Geometry::Line(line)) => {
+                let (p, q) = line.vertices();
+                let (p, q) = if p[0] > q[0] { (p, q) } else { (q, p) };
+
+                assert!((p - b).mag() < EPSILON);
+                assert!((q - c).mag() < EPSILON);
+            }
+            _ => panic!("expected a line"),
+        }
+
+        let t1 = Triangle::new(b, Vector3::new(2., 0., 0.), Vector3::new(2., 1., 0.));
+
+        match intersection_triangle_triangle(&t0, &t1) {
+            Some(Geometry::Point(p)) => assert!((p - b).mag() < EPSILON),
+            _ => panic!("expected a point"),
+        }
+
+        let t1 = Triangle::new(
+            Vector3::new(2., 0., 0.),
+            Vector3::new(3., 0., 0.),
+            Vector3::new(2., 1., 0.),
+        );
+
+        assert!(intersection_triangle_triangle(&t0, &t1).is_none());
+    }
+
+    #[test]
+    fn tolerance() {
+        let t0 = Triangle::new(
+            Vector3::new(0., 0., 0.),
+            Vector3::new(1., 0., 0.),
+            Vector3::new(0., 1., 0.),
+        );
+
+        let t1 = Triangle::new(
+            Vector3::new(0.2, 0.2, 1e-6),
+            Vector3::new(0.2, 0.2, 1.),
+            Vector3::new(0.5, 0.2, 1.),
+        );
+
+        assert!(intersection_triangle_triangle_eps(&t0, &t1, 1e-8).is_none());
+
+        match intersection_triangle_triangle_eps(&t0, &t1, 1e-5) {
+            Some(Geometry::Point(p)) => assert!((p - Vector3::new(0.2, 0.2, 1e-6)).mag() < 1e-5),
+            _ => panic!("expected a point"),
+        }
+    }
 }
@@ -1,8 +1,12 @@
 pub mod aabb_aabb;
+pub mod aabb_frustum;
+pub mod aabb_line;
 pub mod aabb_ray;
 pub mod aabb_sphere;
 pub mod aabb_triangle;
 pub mod aabb_vector3;
+pub mod frustum_sphere;
+pub mod line_sphere;
 pub mod ray_sphere;
 pub mod ray_triangle;
 pub mod sphere_sphere;
@@ -11,12 +15,19 @@ pub mod triangle_triangle;
 
 // Re-exports
 pub use aabb_aabb::intersects_aabb_aabb;
-pub use aabb_ray::intersects_aabb_ray;
+pub use aabb_frustum::intersects_aabb_frustum;
+pub use aabb_line::intersects_aabb_line;
+pub use aabb_ray::{intersects_aabb_ray, interval_aabb_ray};
 pub use aabb_sphere::intersects_aabb_sphere;
 pub use aabb_triangle::intersects_aabb_triangle;
 pub use aabb_vector3::intersects_aabb_vector3;
-pub use ray_sphere::intersects_ray_sphere;
-pub use ray_triangle::intersects_ray_triangle;
+pub use frustum_sphere::intersects_frustum_sphere;
+pub use line_sphere::interval_line_sphere;
+pub use ray_sphere::{intersects_ray_sphere, interval_ray_sphere};
+pub use ray_triangle::{
+    hit_ray_triangle, hit_ray_triangle_culling, hit_ray_triangle_eps, intersects_ray_triangle,
+    intersects_ray_triangle_two_sided,
+};
 pub use sphere_sphere::intersects_sphere_sphere;
 pub use sphere_vector3::intersects_sphere_vector3;
-pub use triangle_triangle::intersects_triangle_triangle;
+pub use triangle_triangle::{intersects_triangle_triangle, intersects_triangle_triangle_eps};
@@ -0,0 +1,21 @@
+use crate::geometry::{Aabb, Frustum, Vector3};
+
+/// Check for an AABB/Frustum spatial intersection. The AABB is rejected if
+/// its corner farthest along a plane normal is outside that plane. This is
+/// conservative and may report an intersection for an AABB just outside a
+/// corner of the Frustum.
+pub fn intersects_aabb_frustum(a: &Aabb, f: &Frustum) -> bool {
+    let min = a.min();
+    let max = a.max();
+
+    f.planes().iter().all(|plane| {
+        let n = plane.normal();
+        let corner = Vector3::new(
+            if n[0] >= 0. { max[0] } else { min[0] },
+            if n[1] >= 0. { max[1] } else { min[1] },
+            if n[2] >= 0. { max[2] } else { min[2] },
+        );
+
+        plane.signed_distance(&corner) >= 0.
+    })
+}
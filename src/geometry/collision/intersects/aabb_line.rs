@@ -0,0 +1,83 @@
+use crate::geometry::{Aabb, Line};
+
+/// Check for an AABB/Line spatial intersection
+pub fn intersects_aabb_line(a: &Aabb, l: &Line) -> bool {
+    let origin = l.p();
+    let inv = l.direction().inv();
+    let min = a.min();
+    let max = a.max();
+
+    let mut tmin: f64 = 0.;
+    let mut tmax: f64 = 1.;
+
+    for i in 0..3 {
+        if inv[i].is_infinite() {
+            if origin[i] < min[i] || origin[i] > max[i] {
+                return false;
+            }
+        } else {
+            let t0 = (min[i] - origin[i]) * inv[i];
+            let t1 = (max[i] - origin[i]) * inv[i];
+            tmin = tmin.max(t0.min(t1));
+            tmax = tmax.min(t0.max(t1));
+        }
+    }
+
+    tmax >= tmin
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Vector3;
+
+    #[test]
+    fn hit_through() {
+        let p = Vector3::new(-1., -1., -1.);
+        let q = Vector3::new(1., 1., 1.);
+        let l = Line::new(p, q);
+        let a = Aabb::unit();
+
+        assert!(intersects_aabb_line(&a, &l));
+    }
+
+    #[test]
+    fn hit_contained() {
+        let p = Vector3::new(-0.1, 0., 0.);
+        let q = Vector3::new(0.1, 0., 0.);
+        let l = Line::new(p, q);
+        let a = Aabb::unit();
+
+        assert!(intersects_aabb_line(&a, &l));
+    }
+
+    #[test]
+    fn hit_x_aligned() {
+        let p = Vector3::new(-1., 0., 0.);
+        let q = Vector3::new(0., 0., 0.);
+        let l = Line::new(p, q);
+        let a = Aabb::unit();
+
+        assert!(intersects_aabb_line(&a, &l));
+    }
+
+    #[test]
+    fn miss_short() {
+        let p = Vector3::new(-2., 0., 0.);
+        let q = Vector3::new(-1., 0., 0.);
+        let l = Line::new(p, q);
+        let a = Aabb::unit();
+
+        assert!(!intersects_aabb_line(&a, &l));
+    }
+
+    #[test]
+    fn miss_aligned() {
+        let p = Vector3::new(-1., 1., 0.);
+        let q = Vector3::new(1., 1., 0.);
+        let l = Line::new(p, q);
+        let a = Aabb::unit();
+
+        assert!(!intersects_aabb_line(&a, &l));
+    }
+}
@@ -2,8 +2,14 @@ use crate::geometry::{Aabb, Ray};
 
 /// Check for an AABB/Ray spatial intersection
 pub fn intersects_aabb_ray(a: &Aabb, r: &Ray) -> bool {
+    interval_aabb_ray(a, r).is_some()
+}
+
+/// Compute the entry/exit parameters of a Ray through an AABB. The entry
+/// parameter is clamped at zero for a ray starting inside the AABB.
+pub fn interval_aabb_ray(a: &Aabb, r: &Ray) -> Option<(f64, f64)> {
     let origin = r.origin();
-    let inv = r.direction().inv();
+    let inv = r.inv_direction();
     let min = a.min();
     let max = a.max();
 
@@ -22,7 +28,13 @@ pub fn intersects_aabb_ray(a: &Aabb, r: &Ray) -> bool {
     let tmin = tmin.max(tz0.min(tz1));
     let tmax = tmax.min(tz0.max(tz1));
 
-    tmax >= tmin.max(0.)
+    let tmin = tmin.max(0.);
+
+    if tmax >= tmin {
+        Some((tmin, tmax))
+    } else {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -109,4 +121,34 @@ mod test {
 
         assert!(!intersects_aabb_ray(&a, &r));
     }
+
+    #[test]
+    fn interval_enter_exit() {
+        let o = Vector3::new(-2., 0., 0.);
+        let d = Vector3::new(1., 0., 0.);
+        let r = Ray::new(o, d);
+        let a = Aabb::unit();
+
+        assert_eq!(interval_aabb_ray(&a, &r), Some((1.5, 2.5)));
+    }
+
+    #[test]
+    fn interval_inside() {
+        let o = Vector3::new(0.25, 0., 0.);
+        let d = Vector3::new(0., 0., 2.);
+        let r = Ray::new(o, d);
+        let a = Aabb::unit();
+
+        assert_eq!(interval_aabb_ray(&a, &r), Some((0., 0.25)));
+    }
+
+    #[test]
+    fn interval_miss() {
+        let o = Vector3::new(-2., 2., 0.);
+        let d = Vector3::new(1., 0., 0.);
+        let r = Ray::new(o, d);
+        let a = Aabb::unit();
+
+        assert_eq!(interval_aabb_ray(&a, &r), None);
+    }
 }
@@ -1,31 +1,17 @@
-use crate::geometry::{Aabb, Sphere};
+use crate::geometry::{Aabb, Sphere, Vector3};
 
 /// Check for an AABB/Sphere spatial intersection
 pub fn intersects_aabb_sphere(a: &Aabb, s: &Sphere) -> bool {
     let center = s.center();
     let radius = s.radius();
-    let min = a.min();
-    let max = a.max();
-
-    let mut d = 0.;
-
-    for i in 0..3 {
-        if center[i] < min[i] {
-            let t = center[i] - min[i];
-            d += t * t;
-        } else if center[i] > max[i] {
-            let t = center[i] - max[i];
-            d += t * t;
-        }
-    }
+    let v = a.closest_point(&center) - center;
 
-    d <= radius * radius
+    Vector3::dot(&v, &v) <= radius * radius
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::geometry::Vector3;
 
     #[test]
     fn hit_overlap_full() {
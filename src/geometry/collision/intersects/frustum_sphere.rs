@@ -0,0 +1,10 @@
+use crate::geometry::{Frustum, Sphere};
+
+/// Check for a Frustum/Sphere spatial intersection. The Sphere is rejected if
+/// it is entirely outside any plane, which is conservative near the edges and
+/// corners of the Frustum.
+pub fn intersects_frustum_sphere(f: &Frustum, s: &Sphere) -> bool {
+    f.planes()
+        .iter()
+        .all(|plane| plane.signed_distance(&s.center()) >= -s.radius())
+}
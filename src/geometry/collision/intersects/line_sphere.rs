@@ -0,0 +1,51 @@
+use crate::geometry::collision::intersects::interval_ray_sphere;
+use crate::geometry::{Line, Ray, Sphere};
+
+/// Compute the near/far parameters (in [0, 1]) of the portion of a Line
+/// inside a Sphere
+pub fn interval_line_sphere(l: &Line, s: &Sphere) -> Option<(f64, f64)> {
+    let r = Ray::new(l.p(), l.direction());
+    let (tmin, tmax) = interval_ray_sphere(&r, s)?;
+
+    if tmin > 1. {
+        return None;
+    }
+
+    Some((tmin, tmax.min(1.)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Vector3;
+
+    #[test]
+    fn diametral() {
+        let p = Vector3::new(-2., 0., 0.);
+        let q = Vector3::new(2., 0., 0.);
+        let l = Line::new(p, q);
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        assert_eq!(interval_line_sphere(&l, &s), Some((0.25, 0.75)));
+    }
+
+    #[test]
+    fn clamped() {
+        let p = Vector3::new(0., 0., 0.);
+        let q = Vector3::new(0.5, 0., 0.);
+        let l = Line::new(p, q);
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        assert_eq!(interval_line_sphere(&l, &s), Some((0., 1.)));
+    }
+
+    #[test]
+    fn short() {
+        let p = Vector3::new(-3., 0., 0.);
+        let q = Vector3::new(-2., 0., 0.);
+        let l = Line::new(p, q);
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        assert_eq!(interval_line_sphere(&l, &s), None);
+    }
+}
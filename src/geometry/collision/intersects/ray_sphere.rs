@@ -14,6 +14,33 @@ pub fn intersects_ray_sphere(r: &Ray, s: &Sphere) -> bool {
     (ll - ld * ld) <= rr
 }
 
+/// Compute the near/far parameters of a Ray through a Sphere. The near
+/// parameter is clamped at zero for a ray starting inside the Sphere and
+/// both parameters are equal for a tangent ray.
+pub fn interval_ray_sphere(r: &Ray, s: &Sphere) -> Option<(f64, f64)> {
+    let l = r.origin() - s.center();
+    let d = r.direction();
+
+    let a = Vector3::dot(&d, &d);
+    let b = Vector3::dot(&l, &d);
+    let c = Vector3::dot(&l, &l) - s.radius() * s.radius();
+    let discriminant = b * b - a * c;
+
+    if discriminant < 0. || a == 0. {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    let tmin = (-b - root) / a;
+    let tmax = (-b + root) / a;
+
+    if tmax < 0. {
+        return None;
+    }
+
+    Some((tmin.max(0.), tmax))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -39,4 +66,44 @@ mod test {
 
         assert!(!intersects_ray_sphere(&r, &s));
     }
+
+    #[test]
+    fn interval_diametral() {
+        let o = Vector3::new(-2., 0., 0.);
+        let d = Vector3::new(1., 0., 0.);
+        let r = Ray::new(o, d);
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        assert_eq!(interval_ray_sphere(&r, &s), Some((1., 3.)));
+    }
+
+    #[test]
+    fn interval_inside() {
+        let o = Vector3::new(0.5, 0., 0.);
+        let d = Vector3::new(2., 0., 0.);
+        let r = Ray::new(o, d);
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        assert_eq!(interval_ray_sphere(&r, &s), Some((0., 0.25)));
+    }
+
+    #[test]
+    fn interval_tangent() {
+        let o = Vector3::new(-2., 1., 0.);
+        let d = Vector3::new(1., 0., 0.);
+        let r = Ray::new(o, d);
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        assert_eq!(interval_ray_sphere(&r, &s), Some((2., 2.)));
+    }
+
+    #[test]
+    fn interval_behind() {
+        let o = Vector3::new(2., 0., 0.);
+        let d = Vector3::new(1., 0., 0.);
+        let r = Ray::new(o, d);
+        let s = Sphere::new(Vector3::zeros(), 1.);
+
+        assert_eq!(interval_ray_sphere(&r, &s), None);
+    }
 }
@@ -1,7 +1,52 @@
-use crate::geometry::{Ray, Triangle, Vector3, EPSILON};
+use crate::geometry::{Culling, Ray, Triangle, Vector3, EPSILON};
 
-/// Check for a Ray/Triangle spatial intersection
+/// Check for a Ray/Triangle spatial intersection. Triangles facing away from
+/// the ray are culled.
 pub fn intersects_ray_triangle(r: &Ray, t: &Triangle) -> bool {
+    hit_ray_triangle(r, t, true).is_some()
+}
+
+/// Check for a Ray/Triangle spatial intersection regardless of which side of
+/// the triangle the ray approaches from.
+pub fn intersects_ray_triangle_two_sided(r: &Ray, t: &Triangle) -> bool {
+    hit_ray_triangle(r, t, false).is_some()
+}
+
+/// Compute the parameter along the ray at which it hits the triangle. This is
+/// the distance to the hit when the ray direction is a unit vector.
+pub fn hit_ray_triangle(r: &Ray, t: &Triangle, cull_backfaces: bool) -> Option<f64> {
+    hit_ray_triangle_eps(r, t, cull_backfaces, EPSILON)
+}
+
+/// Compute the parameter along the ray at which it hits the triangle using a
+/// tolerance on the determinant and the hit distance. Degenerate triangles
+/// are never hit.
+pub fn hit_ray_triangle_eps(r: &Ray, t: &Triangle, cull_backfaces: bool, eps: f64) -> Option<f64> {
+    let culling = if cull_backfaces {
+        Culling::Backfaces
+    } else {
+        Culling::None
+    };
+
+    hit_ray_triangle_culling(r, t, culling, eps, eps)
+}
+
+/// Compute the parameter along the ray at which it hits the triangle with the
+/// faces culled by orientation set independently of the tolerances. Rays
+/// within the parallel tolerance of the triangle plane (measured on the
+/// determinant) and hits within the distance tolerance of the ray origin are
+/// rejected. Degenerate triangles are never hit.
+pub fn hit_ray_triangle_culling(
+    r: &Ray,
+    t: &Triangle,
+    culling: Culling,
+    parallel_eps: f64,
+    distance_eps: f64,
+) -> Option<f64> {
+    if t.is_degenerate() {
+        return None;
+    }
+
     let origin = r.origin();
     let direction = r.direction();
 
@@ -11,26 +56,38 @@ pub fn intersects_ray_triangle(r: &Ray, t: &Triangle) -> bool {
     let p = Vector3::cross(&direction, &e1);
     let d = Vector3::dot(&e0, &p);
 
-    if d < EPSILON {
-        return false;
+    if d.abs() < parallel_eps {
+        return None;
+    }
+
+    match culling {
+        Culling::Backfaces if d < 0. => return None,
+        Culling::Frontfaces if d > 0. => return None,
+        _ => (),
     }
 
     let d_inv = 1. / d;
     let s = origin - t[0];
     let u = d_inv * Vector3::dot(&s, &p);
 
-    if u < 0. || u > 1. {
-        return false;
+    if !(0. ..=1.).contains(&u) {
+        return None;
     }
 
     let q = Vector3::cross(&s, &e0);
     let v = d_inv * Vector3::dot(&direction, &q);
 
     if v < 0. || u + v > 1. {
-        return false;
+        return None;
     }
 
-    (d_inv * Vector3::dot(&e1, &q)) > EPSILON
+    let distance = d_inv * Vector3::dot(&e1, &q);
+
+    if distance > distance_eps {
+        Some(distance)
+    } else {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -63,6 +120,52 @@ mod test {
         let t = Triangle::new(a, b, c);
 
         assert!(!intersects_ray_triangle(&r, &t));
+        assert!(intersects_ray_triangle_two_sided(&r, &t));
+    }
+
+    #[test]
+    fn hit_distance() {
+        let o = Vector3::new(0.25, 0.25, 0.);
+        let d = Vector3::new(0., 0., 1.);
+        let r = Ray::new(o, d);
+
+        let a = Vector3::new(0., 0., 2.);
+        let b = Vector3::new(1., 0., 2.);
+        let c = Vector3::new(0., 1., 2.);
+        let t = Triangle::new(a, b, c);
+
+        assert_eq!(hit_ray_triangle(&r, &t, true), None);
+        assert_eq!(hit_ray_triangle(&r, &t, false), Some(2.));
+    }
+
+    #[test]
+    fn tolerance() {
+        let a = Vector3::new(0., 0., 1.);
+        let b = Vector3::new(0., 1., 1.);
+        let c = Vector3::new(1., 0., 1.);
+        let t = Triangle::new(a, b, c);
+        let o = Vector3::new(0.25, 0.25, 0.);
+
+        // The determinant scales with the direction, so a short enough
+        // direction is treated as parallel using the shared tolerance.
+        let r = Ray::new(o, Vector3::new(0., 0., 0.5 * EPSILON));
+        assert_eq!(hit_ray_triangle(&r, &t, false), None);
+
+        let r = Ray::new(o, Vector3::new(0., 0., 2. * EPSILON));
+        assert!(hit_ray_triangle(&r, &t, false).is_some());
+    }
+
+    #[test]
+    fn tolerance_eps() {
+        let a = Vector3::new(0., 0., 1.);
+        let b = Vector3::new(0., 1., 1.);
+        let c = Vector3::new(1., 0., 1.);
+        let t = Triangle::new(a, b, c);
+        let o = Vector3::new(0.25, 0.25, 0.);
+        let r = Ray::new(o, Vector3::new(0., 0., 1e-6));
+
+        assert!(hit_ray_triangle_eps(&r, &t, false, 1e-8).is_some());
+        assert!(hit_ray_triangle_eps(&r, &t, false, 1e-5).is_none());
     }
 
     #[test]
@@ -78,4 +181,56 @@ mod test {
 
         assert!(!intersects_ray_triangle(&r, &t));
     }
+
+    #[test]
+    fn degenerate() {
+        let o = Vector3::new(0.5, 0.5, 0.);
+        let r = Ray::new(o, Vector3::new(0., 0., 1.));
+
+        let a = Vector3::new(0., 0., 1.);
+        let b = Vector3::new(1., 1., 1.);
+        let t = Triangle::new(a, b, b);
+
+        assert!(!intersects_ray_triangle(&r, &t));
+        assert!(hit_ray_triangle(&r, &t, false).is_none());
+    }
+
+    #[test]
+    fn culling() {
+        let a = Vector3::new(0., 0., 1.);
+        let b = Vector3::new(1., 0., 1.);
+        let c = Vector3::new(0., 1., 1.);
+        let t = Triangle::new(a, b, c);
+
+        let below = Ray::new(Vector3::new(0.2, 0.2, 0.), Vector3::new(0., 0., 1.));
+        let above = Ray::new(Vector3::new(0.2, 0.2, 2.), Vector3::new(0., 0., -1.));
+
+        let hit = |r: &Ray, culling| hit_ray_triangle_culling(r, &t, culling, EPSILON, EPSILON);
+
+        assert!(hit(&below, Culling::Backfaces).is_none());
+        assert!(hit(&below, Culling::Frontfaces).is_some());
+        assert!(hit(&below, Culling::None).is_some());
+        assert!(hit(&above, Culling::Backfaces).is_some());
+        assert!(hit(&above, Culling::Frontfaces).is_none());
+        assert!(hit(&above, Culling::None).is_some());
+    }
+
+    #[test]
+    fn grazing() {
+        let a = Vector3::new(0., 0., 0.);
+        let b = Vector3::new(1e-3, 0., 0.);
+        let c = Vector3::new(0., 1e-3, 0.);
+        let t = Triangle::new(a, b, c);
+
+        // Skim the small triangle at a shallow angle toward its front face
+        let direction = Vector3::new(1., 0., -1e-3).unit();
+        let target = Vector3::new(2.5e-4, 2.5e-4, 0.);
+        let r = Ray::new(target - direction, direction);
+
+        assert!(hit_ray_triangle(&r, &t, true).is_none());
+
+        let distance =
+            hit_ray_triangle_culling(&r, &t, Culling::Backfaces, 1e-12, EPSILON).unwrap();
+        assert!((distance - 1.).abs() < 1e-9);
+    }
 }
@@ -2,7 +2,8 @@ use crate::geometry::{Sphere, Vector3};
 
 /// Check for a Sphere/Vector3 spatial intersection
 pub fn intersects_sphere_vector3(s: &Sphere, v: &Vector3) -> bool {
-    (*v - s.center()).mag() <= s.radius() * s.radius()
+    let d = *v - s.center();
+    Vector3::dot(&d, &d) <= s.radius() * s.radius()
 }
 
 #[cfg(test)]
@@ -3,23 +3,28 @@ use crate::geometry::{Triangle, Vector3, EPSILON};
 /// Check for a spatial intersection two Triangles
 /// https://fileadmin.cs.lth.se/cs/Personal/Tomas_Akenine-Moller/code/tritri_isectline.txt
 pub fn intersects_triangle_triangle(t1: &Triangle, t2: &Triangle) -> bool {
+    intersects_triangle_triangle_eps(t1, t2, EPSILON)
+}
+
+/// Check for a spatial intersection two Triangles using a tolerance on the
+/// signed distances of the vertices to the plane of the other triangle.
+/// Degenerate triangles have no plane and never intersect.
+pub fn intersects_triangle_triangle_eps(t1: &Triangle, t2: &Triangle, eps: f64) -> bool {
+    if t1.is_degenerate() || t2.is_degenerate() {
+        return false;
+    }
+
     // Unpack the vertices to match the nomenclature in the article
     let (v0, v1, v2) = t1.vertices();
     let (u0, u1, u2) = t2.vertices();
 
-    // Compute the plane equation of the triangle t1
-    let n1 = t1.normal();
-    let d1 = -Vector3::dot(&n1, &v0);
-
     // Compute the signed distances to the plane of triangle t1 for each of
     // the vertices in triangle t2
-    let du0 = Vector3::dot(&n1, &u0) + d1;
-    let du1 = Vector3::dot(&n1, &u1) + d1;
-    let du2 = Vector3::dot(&n1, &u2) + d1;
-
-    let du0 = if du0 < EPSILON { 0. } else { du0 };
-    let du1 = if du1 < EPSILON { 0. } else { du1 };
-    let du2 = if du2 < EPSILON { 0. } else { du2 };
+    let plane1 = t1.plane();
+    let n1 = plane1.normal();
+    let du0 = plane1.signed_distance_eps(&u0, eps);
+    let du1 = plane1.signed_distance_eps(&u1, eps);
+    let du2 = plane1.signed_distance_eps(&u2, eps);
 
     let du0du1 = du0 * du1;
     let du0du2 = du0 * du2;
@@ -31,19 +36,13 @@ pub fn intersects_triangle_triangle(t1: &Triangle, t2: &Triangle) -> bool {
         return false;
     }
 
-    // Compute the plane equation of triangle t2
-    let n2 = t2.normal();
-    let d2 = -Vector3::dot(&n2, &u0);
-
     // Compute the signed distances to the plane of triangle t2 for each of
     // the vertices in triangle t1.
-    let dv0 = Vector3::dot(&n2, &v0) + d2;
-    let dv1 = Vector3::dot(&n2, &v1) + d2;
-    let dv2 = Vector3::dot(&n2, &v2) + d2;
-
-    let dv0 = if dv0 < EPSILON { 0. } else { dv0 };
-    let dv1 = if dv1 < EPSILON { 0. } else { dv1 };
-    let dv2 = if dv2 < EPSILON { 0. } else { dv2 };
+    let plane2 = t2.plane();
+    let n2 = plane2.normal();
+    let dv0 = plane2.signed_distance_eps(&v0, eps);
+    let dv1 = plane2.signed_distance_eps(&v1, eps);
+    let dv2 = plane2.signed_distance_eps(&v2, eps);
 
     let dv0dv1 = dv0 * dv1;
     let dv0dv2 = dv0 * dv2;
@@ -390,6 +389,23 @@ mod test {
         assert!(!intersects);
     }
 
+    #[test]
+    fn test_triangle_triangle_fail_below() {
+        let a = Vector3::new(0., 0., 0.);
+        let b = Vector3::new(1., 0., 0.);
+        let c = Vector3::new(0., 1., 0.);
+        let t1 = Triangle::new(a, b, c);
+
+        let d = Vector3::new(0.2, 0.2, -1.);
+        let e = Vector3::new(0.3, 0.2, -2.);
+        let f = Vector3::new(0.2, 0.3, -2.);
+        let t2 = Triangle::new(d, e, f);
+
+        let intersects = intersects_triangle_triangle(&t1, &t2);
+
+        assert!(!intersects);
+    }
+
     #[test]
     fn test_triangle_triangle_fail_beside() {
         let a = Vector3::new(0., 0., 1.);
@@ -423,4 +439,44 @@ mod test {
 
         assert!(!intersects);
     }
+
+    #[test]
+    fn tolerance() {
+        let t1 = Triangle::new(
+            Vector3::new(0., 0., 0.),
+            Vector3::new(1., 0., 0.),
+            Vector3::new(0., 1., 0.),
+        );
+
+        // The second triangle hovers just above the plane of the first
+        let t2 = Triangle::new(
+            Vector3::new(0.2, 0.2, 1e-6),
+            Vector3::new(0.2, 0.2, 1.),
+            Vector3::new(0.5, 0.2, 1.),
+        );
+
+        assert!(!intersects_triangle_triangle_eps(&t1, &t2, 1e-8));
+        assert!(intersects_triangle_triangle_eps(&t1, &t2, 1e-5));
+        assert!(!intersects_triangle_triangle(&t1, &t2));
+    }
+
+    #[test]
+    fn degenerate() {
+        let t1 = Triangle::new(
+            Vector3::new(0., 0., 0.),
+            Vector3::new(1., 0., 0.),
+            Vector3::new(0., 1., 0.),
+        );
+
+        // A zero area sliver lying across the first triangle
+        let t2 = Triangle::new(
+            Vector3::new(0.2, 0.2, -1.),
+            Vector3::new(0.2, 0.2, 1.),
+            Vector3::new(0.2, 0.2, 0.),
+        );
+
+        assert!(!intersects_triangle_triangle(&t1, &t2));
+        assert!(!intersects_triangle_triangle(&t2, &t1));
+        assert!(!intersects_triangle_triangle(&t2, &t2));
+    }
 }
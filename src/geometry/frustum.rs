@@ -0,0 +1,94 @@
+use crate::geometry::collision;
+use crate::geometry::{Aabb, Plane, Sphere};
+
+#[derive(Debug, Copy, Clone)]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Construct a Frustum from its six bounding planes. The plane normals
+    /// point toward the inside of the Frustum.
+    pub fn new(planes: [Plane; 6]) -> Frustum {
+        Frustum { planes }
+    }
+
+    /// Get the bounding planes
+    pub fn planes(&self) -> &[Plane; 6] {
+        &self.planes
+    }
+}
+
+impl crate::geometry::Intersects<Aabb> for Frustum {
+    fn intersects(&self, other: &Aabb) -> bool {
+        collision::intersects::intersects_aabb_frustum(other, self)
+    }
+}
+
+impl crate::geometry::Intersects<Sphere> for Frustum {
+    fn intersects(&self, other: &Sphere) -> bool {
+        collision::intersects::intersects_frustum_sphere(self, other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::{Intersects, Vector3};
+    use crate::spatial::{Octree, Query};
+
+    // Frustum looking along +z from the origin with a 45 degree half angle
+    fn frustum() -> Frustum {
+        let o = Vector3::zeros();
+
+        Frustum::new([
+            Plane::new(Vector3::new(0., 0., 1.), Vector3::new(0., 0., 1.)),
+            Plane::new(Vector3::new(0., 0., 10.), Vector3::new(0., 0., -1.)),
+            Plane::new(o, Vector3::new(-1., 0., 1.)),
+            Plane::new(o, Vector3::new(1., 0., 1.)),
+            Plane::new(o, Vector3::new(0., -1., 1.)),
+            Plane::new(o, Vector3::new(0., 1., 1.)),
+        ])
+    }
+
+    #[test]
+    fn intersects_aabb() {
+        let frustum = frustum();
+        let halfsize = Vector3::ones() * 0.5;
+
+        let inside = Aabb::new(Vector3::new(0., 0., 5.), halfsize);
+        assert!(frustum.intersects(&inside));
+
+        let outside = Aabb::new(Vector3::new(20., 0., 5.), halfsize);
+        assert!(!frustum.intersects(&outside));
+
+        let behind = Aabb::new(Vector3::new(0., 0., -5.), halfsize);
+        assert!(!frustum.intersects(&behind));
+
+        let straddling = Aabb::new(Vector3::new(5., 0., 5.), halfsize);
+        assert!(frustum.intersects(&straddling));
+    }
+
+    #[test]
+    fn intersects_sphere() {
+        let frustum = frustum();
+
+        assert!(frustum.intersects(&Sphere::new(Vector3::new(0., 0., 5.), 0.5)));
+        assert!(!frustum.intersects(&Sphere::new(Vector3::new(0., 0., 12.), 1.)));
+        assert!(frustum.intersects(&Sphere::new(Vector3::new(0., 0., 10.5), 1.)));
+    }
+
+    #[test]
+    fn query_octree() {
+        let bounds = Aabb::new(Vector3::zeros(), Vector3::ones() * 20.);
+        let mut octree = Octree::<Sphere>::new(bounds);
+        let inside = octree.insert(Sphere::new(Vector3::new(0., 0., 5.), 0.5));
+        let straddling = octree.insert(Sphere::new(Vector3::new(5., 0., 5.), 0.5));
+        octree.insert(Sphere::new(Vector3::new(-15., 0., 5.), 0.5));
+
+        let mut results = octree.query(&frustum());
+        results.sort();
+
+        assert_eq!(results, vec![inside, straddling]);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::geometry::collision;
-use crate::geometry::{Geometry, Triangle, Vector3};
+use crate::geometry::{Aabb, Geometry, Matrix4, Ray, Sphere, Triangle, Vector3, EPSILON};
 
 #[derive(Debug, Copy, Clone)]
 pub struct Line {
@@ -37,6 +37,59 @@ impl Line {
     pub fn length(&self) -> f64 {
         self.direction().mag()
     }
+
+    /// Get the closest points between two segments as a tuple of the point
+    /// on this segment and the point on the other. For parallel segments,
+    /// this returns one of the equally close pairs.
+    pub fn closest_points(&self, other: &Line) -> (Vector3, Vector3) {
+        let d1 = self.direction();
+        let d2 = other.direction();
+        let r = self.p - other.p;
+        let a = Vector3::dot(&d1, &d1);
+        let e = Vector3::dot(&d2, &d2);
+        let f = Vector3::dot(&d2, &r);
+
+        // Degenerate (zero length) segments are treated as points
+        let (s, t) = if a <= EPSILON && e <= EPSILON {
+            (0., 0.)
+        } else if a <= EPSILON {
+            (0., (f / e).clamp(0., 1.))
+        } else {
+            let c = Vector3::dot(&d1, &r);
+
+            if e <= EPSILON {
+                ((-c / a).clamp(0., 1.), 0.)
+            } else {
+                let b = Vector3::dot(&d1, &d2);
+                let denom = a * e - b * b;
+
+                // Parallel segments pick an arbitrary s and correct it below
+                let s = if denom > EPSILON * a * e {
+                    ((b * f - c * e) / denom).clamp(0., 1.)
+                } else {
+                    0.
+                };
+
+                let t = (b * s + f) / e;
+
+                if t < 0. {
+                    ((-c / a).clamp(0., 1.), 0.)
+                } else if t > 1. {
+                    (((b - c) / a).clamp(0., 1.), 1.)
+                } else {
+                    (s, t)
+                }
+            }
+        };
+
+        (self.p + d1 * s, other.p + d2 * t)
+    }
+
+    /// Get the near/far parameters (in [0, 1]) of the portion of the segment
+    /// inside a Sphere
+    pub fn sphere_intersections(&self, sphere: &Sphere) -> Option<(f64, f64)> {
+        collision::intersects::interval_line_sphere(self, sphere)
+    }
 }
 
 impl std::ops::Index<usize> for Line {
@@ -61,8 +114,105 @@ impl std::ops::IndexMut<usize> for Line {
     }
 }
 
+impl crate::geometry::Bounded for Line {
+    fn bounds(&self) -> Aabb {
+        Aabb::from_bounds(self.p.min(&self.q), self.p.max(&self.q))
+    }
+}
+
+impl crate::geometry::Transform for Line {
+    fn transform(&self, m: &Matrix4) -> Line {
+        Line::new(self.p.transform(m), self.q.transform(m))
+    }
+}
+
+impl crate::geometry::Distance<Ray> for Line {
+    fn distance(&self, other: &Ray) -> f64 {
+        collision::distance::distance_line_ray(self, other)
+    }
+}
+
+impl crate::geometry::Distance<Triangle> for Line {
+    fn distance(&self, other: &Triangle) -> f64 {
+        collision::distance::distance_line_triangle(self, other)
+    }
+}
+
+impl crate::geometry::Intersects<Aabb> for Line {
+    fn intersects(&self, other: &Aabb) -> bool {
+        collision::intersects::intersects_aabb_line(other, self)
+    }
+}
+
+impl crate::geometry::Intersection<Line> for Line {
+    fn intersection(&self, other: &Line) -> Option<Geometry> {
+        collision::intersection::intersection_line_line(self, other)
+    }
+}
+
+impl crate::geometry::Intersection<Sphere> for Line {
+    fn intersection(&self, other: &Sphere) -> Option<Geometry> {
+        collision::intersection::intersection_line_sphere(self, other)
+    }
+}
+
 impl crate::geometry::Intersection<Triangle> for Line {
     fn intersection(&self, other: &Triangle) -> Option<Geometry> {
         collision::intersection::intersection_line_triangle(self, other)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn closest_points_crossing() {
+        let a = Line::new(Vector3::new(-1., 0., 0.), Vector3::new(1., 0., 0.));
+        let b = Line::new(Vector3::new(0., -1., 0.), Vector3::new(0., 1., 0.));
+        let (p, q) = a.closest_points(&b);
+
+        assert!(p.mag() < EPSILON);
+        assert!(q.mag() < EPSILON);
+    }
+
+    #[test]
+    fn closest_points_skew() {
+        let a = Line::new(Vector3::new(-1., 0., 0.), Vector3::new(1., 0., 0.));
+        let b = Line::new(Vector3::new(0.5, -1., 2.), Vector3::new(0.5, 1., 2.));
+        let (p, q) = a.closest_points(&b);
+
+        assert!((p - Vector3::new(0.5, 0., 0.)).mag() < EPSILON);
+        assert!((q - Vector3::new(0.5, 0., 2.)).mag() < EPSILON);
+    }
+
+    #[test]
+    fn closest_points_endpoints() {
+        let a = Line::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let b = Line::new(Vector3::new(2., 1., 0.), Vector3::new(2., 3., 0.));
+        let (p, q) = a.closest_points(&b);
+
+        assert_eq!(p, Vector3::new(1., 0., 0.));
+        assert_eq!(q, Vector3::new(2., 1., 0.));
+    }
+
+    #[test]
+    fn closest_points_parallel() {
+        let a = Line::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let b = Line::new(Vector3::new(0.5, 1., 0.), Vector3::new(2., 1., 0.));
+        let (p, q) = a.closest_points(&b);
+
+        assert!(((p - q).mag() - 1.).abs() < EPSILON);
+        assert!(p[0] >= 0.5 - EPSILON && p[0] <= 1. + EPSILON);
+    }
+
+    #[test]
+    fn closest_points_degenerate() {
+        let a = Line::new(Vector3::new(0.5, 1., 0.), Vector3::new(0.5, 1., 0.));
+        let b = Line::new(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.));
+        let (p, q) = a.closest_points(&b);
+
+        assert_eq!(p, Vector3::new(0.5, 1., 0.));
+        assert_eq!(q, Vector3::new(0.5, 0., 0.));
+    }
+}
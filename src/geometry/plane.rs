@@ -0,0 +1,85 @@
+use crate::geometry::collision;
+use crate::geometry::{Geometry, Ray, Vector3};
+
+#[derive(Debug, Copy, Clone)]
+pub struct Plane {
+    origin: Vector3,
+    normal: Vector3,
+}
+
+impl Plane {
+    /// Construct a Plane from a point on the plane and its normal. The
+    /// normal is stored as a unit vector.
+    pub fn new(origin: Vector3, normal: Vector3) -> Plane {
+        Plane {
+            origin,
+            normal: normal.unit(),
+        }
+    }
+
+    /// Get the origin
+    pub fn origin(&self) -> Vector3 {
+        self.origin
+    }
+
+    /// Get the unit normal
+    pub fn normal(&self) -> Vector3 {
+        self.normal
+    }
+
+    /// Compute the signed distance from the plane to a point. The distance
+    /// is positive on the side of the plane the normal points to.
+    pub fn signed_distance(&self, point: &Vector3) -> f64 {
+        Vector3::dot(&self.normal, &(*point - self.origin))
+    }
+
+    /// Compute the signed distance from the plane to a point, snapping
+    /// distances within eps of the plane to exactly zero so points on the
+    /// plane can be classified by sign.
+    pub fn signed_distance_eps(&self, point: &Vector3, eps: f64) -> f64 {
+        let distance = self.signed_distance(point);
+
+        if distance.abs() <= eps {
+            0.
+        } else {
+            distance
+        }
+    }
+}
+
+impl crate::geometry::Intersection<Ray> for Plane {
+    fn intersection(&self, other: &Ray) -> Option<Geometry> {
+        collision::intersection::intersection_plane_ray(self, other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn signed_distance() {
+        let o = Vector3::new(0., 0., 1.);
+        let n = Vector3::new(0., 0., 2.);
+        let p = Plane::new(o, n);
+
+        assert_eq!(p.signed_distance(&Vector3::new(3., 4., 3.)), 2.);
+        assert_eq!(p.signed_distance(&Vector3::new(3., 4., 0.)), -1.);
+        assert_eq!(p.signed_distance(&Vector3::new(3., 4., 1.)), 0.);
+    }
+
+    #[test]
+    fn signed_distance_eps() {
+        let p = Plane::new(Vector3::zeros(), Vector3::new(0., 0., 1.));
+
+        assert_eq!(p.signed_distance_eps(&Vector3::new(1., 2., 1e-9), 1e-8), 0.);
+        assert_eq!(
+            p.signed_distance_eps(&Vector3::new(1., 2., -1e-9), 1e-8),
+            0.
+        );
+        assert_eq!(
+            p.signed_distance_eps(&Vector3::new(1., 2., 1e-6), 1e-8),
+            1e-6
+        );
+    }
+}
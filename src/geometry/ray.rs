@@ -1,16 +1,45 @@
 use crate::geometry::collision;
-use crate::geometry::{Aabb, Sphere, Triangle, Vector3};
+use crate::geometry::{Aabb, Geometry, Line, Matrix4, Plane, Sphere, Triangle, Vector3, EPSILON};
+
+/// Faces of a triangle culled by their orientation to a ray
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Culling {
+    /// Hit faces from either side
+    None,
+    /// Skip faces whose normal points along the ray
+    Backfaces,
+    /// Skip faces whose normal points against the ray
+    Frontfaces,
+}
 
 #[derive(Debug, Copy, Clone)]
 pub struct Ray {
     origin: Vector3,
     direction: Vector3,
+    inv_direction: Option<Vector3>,
 }
 
 impl Ray {
     /// Construct a Ray from its origin and direction
     pub fn new(origin: Vector3, direction: Vector3) -> Ray {
-        Ray { origin, direction }
+        Ray {
+            origin,
+            direction,
+            inv_direction: None,
+        }
+    }
+
+    /// Construct a Ray from its origin and direction, normalizing the
+    /// direction and storing its inverse for repeated AABB tests (e.g.
+    /// traversing a spatial index).
+    pub fn precomputed(origin: Vector3, direction: Vector3) -> Ray {
+        let direction = direction.unit();
+
+        Ray {
+            origin,
+            direction,
+            inv_direction: Some(direction.inv()),
+        }
     }
 
     /// Get the origin
@@ -22,6 +51,87 @@ impl Ray {
     pub fn direction(&self) -> Vector3 {
         self.direction
     }
+
+    /// Get the inverse of the direction, using the stored inverse if the
+    /// Ray was precomputed
+    pub fn inv_direction(&self) -> Vector3 {
+        self.inv_direction.unwrap_or_else(|| self.direction.inv())
+    }
+
+    /// Get the closest point on the ray to a point. Points behind the origin
+    /// are closest to the origin.
+    pub fn closest_point(&self, point: &Vector3) -> Vector3 {
+        let length = Vector3::dot(&self.direction, &self.direction);
+
+        if length <= EPSILON {
+            return self.origin;
+        }
+
+        let t = Vector3::dot(&(*point - self.origin), &self.direction) / length;
+        self.origin + self.direction * t.max(0.)
+    }
+
+    /// Get the point at which the ray crosses a Plane. Rays parallel to the
+    /// Plane and crossings behind the origin return None.
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<Vector3> {
+        match collision::intersection::intersection_plane_ray(plane, self) {
+            Some(Geometry::Point(point)) => Some(point),
+            _ => None,
+        }
+    }
+
+    /// Check for an intersection with a Triangle from either side
+    pub fn intersects_two_sided(&self, triangle: &Triangle) -> bool {
+        collision::intersects::intersects_ray_triangle_two_sided(self, triangle)
+    }
+
+    /// Get the parameter along the ray at which it hits a Triangle. This is
+    /// the distance to the hit when the direction is a unit vector.
+    pub fn hit_triangle(&self, triangle: &Triangle, cull_backfaces: bool) -> Option<f64> {
+        collision::intersects::hit_ray_triangle(self, triangle, cull_backfaces)
+    }
+
+    /// Get the parameter along the ray at which it hits a Triangle using a
+    /// tolerance suited to the scale of the geometry
+    pub fn hit_triangle_eps(
+        &self,
+        triangle: &Triangle,
+        cull_backfaces: bool,
+        eps: f64,
+    ) -> Option<f64> {
+        collision::intersects::hit_ray_triangle_eps(self, triangle, cull_backfaces, eps)
+    }
+
+    /// Get the parameter along the ray at which it hits a Triangle with the
+    /// culled faces and the tolerances on grazing rays (parallel) and near
+    /// hits (distance) set independently
+    pub fn hit_triangle_culling(
+        &self,
+        triangle: &Triangle,
+        culling: Culling,
+        parallel_eps: f64,
+        distance_eps: f64,
+    ) -> Option<f64> {
+        collision::intersects::hit_ray_triangle_culling(
+            self,
+            triangle,
+            culling,
+            parallel_eps,
+            distance_eps,
+        )
+    }
+}
+
+impl crate::geometry::Distance<Line> for Ray {
+    fn distance(&self, other: &Line) -> f64 {
+        collision::distance::distance_line_ray(other, self)
+    }
+}
+
+impl crate::geometry::Distance<Ray> for Ray {
+    fn distance(&self, other: &Ray) -> f64 {
+        collision::distance::distance_ray_ray(self, other)
+    }
 }
 
 impl crate::geometry::Intersects<Aabb> for Ray {
@@ -41,3 +151,96 @@ impl crate::geometry::Intersects<Triangle> for Ray {
         collision::intersects::intersects_ray_triangle(self, other)
     }
 }
+
+impl crate::geometry::Intersection<Aabb> for Ray {
+    fn intersection(&self, other: &Aabb) -> Option<Geometry> {
+        collision::intersection::intersection_aabb_ray(other, self)
+    }
+}
+
+impl crate::geometry::Intersection<Plane> for Ray {
+    fn intersection(&self, other: &Plane) -> Option<Geometry> {
+        collision::intersection::intersection_plane_ray(other, self)
+    }
+}
+
+impl crate::geometry::Intersection<Sphere> for Ray {
+    fn intersection(&self, other: &Sphere) -> Option<Geometry> {
+        collision::intersection::intersection_ray_sphere(self, other)
+    }
+}
+
+impl crate::geometry::Bounded for Ray {
+    /// A ray is unbounded, so it is bounded by an infinite box about its
+    /// origin
+    fn bounds(&self) -> Aabb {
+        Aabb::new(self.origin, Vector3::ones() * f64::INFINITY)
+    }
+}
+
+impl crate::geometry::Transform for Ray {
+    /// Transform the origin as a point and the direction as a vector. A
+    /// precomputed ray stays precomputed.
+    fn transform(&self, m: &Matrix4) -> Ray {
+        let origin = self.origin.transform(m);
+        let d =
+            m.fixed_view::<3, 3>(0, 0) * nalgebra::Vector3::from(<[f64; 3]>::from(self.direction));
+        let direction = Vector3::new(d[0], d[1], d[2]);
+
+        match self.inv_direction {
+            Some(_) => Ray::precomputed(origin, direction),
+            None => Ray::new(origin, direction),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Intersects;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn precomputed() {
+        let ray = Ray::precomputed(Vector3::zeros(), Vector3::new(0., 3., 4.));
+
+        assert_eq!(ray.direction(), Vector3::new(0., 0.6, 0.8));
+        assert_eq!(ray.inv_direction(), ray.direction().inv());
+        assert_eq!(
+            Ray::new(Vector3::zeros(), Vector3::ones()).inv_direction(),
+            Vector3::ones()
+        );
+    }
+
+    #[test]
+    fn intersect_plane() {
+        let plane = Plane::new(Vector3::zeros(), Vector3::new(0., 0., 1.));
+        let down = Ray::new(Vector3::new(1., 2., 5.), Vector3::new(0., 0., -1.));
+        let parallel = Ray::new(Vector3::new(1., 2., 5.), Vector3::new(1., 0., 0.));
+
+        assert_eq!(down.intersect_plane(&plane), Some(Vector3::new(1., 2., 0.)));
+        assert_eq!(parallel.intersect_plane(&plane), None);
+    }
+
+    #[test]
+    fn precomputed_intersects_aabb() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut point = || {
+            Vector3::new(
+                rng.gen_range(-2.0..2.0),
+                rng.gen_range(-2.0..2.0),
+                rng.gen_range(-2.0..2.0),
+            )
+        };
+
+        for _ in 0..1000 {
+            let origin = point();
+            let direction = point();
+            let aabb = Aabb::new(point() * 0.5, Vector3::ones() * 0.25);
+            let ray = Ray::new(origin, direction);
+            let cached = Ray::precomputed(origin, direction);
+
+            assert_eq!(ray.intersects(&aabb), cached.intersects(&aabb));
+        }
+    }
+}
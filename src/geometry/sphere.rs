@@ -1,5 +1,5 @@
 use crate::geometry::collision;
-use crate::geometry::{Aabb, Ray, Vector3};
+use crate::geometry::{Aabb, Frustum, Geometry, Line, Matrix4, Ray, Triangle, Vector3};
 
 #[derive(Debug, Copy, Clone)]
 pub struct Sphere {
@@ -22,6 +22,69 @@ impl Sphere {
     pub fn radius(&self) -> f64 {
         self.radius
     }
+
+    /// Check if a point lies inside or on the Sphere
+    pub fn contains(&self, point: Vector3) -> bool {
+        collision::intersects::intersects_sphere_vector3(self, &point)
+    }
+
+    /// Get the point on the surface of the Sphere at a polar angle theta
+    /// (measured from the +z axis) and an azimuthal angle phi (measured from
+    /// the +x axis toward the +y axis), both in radians
+    pub fn surface_point(&self, theta: f64, phi: f64) -> Vector3 {
+        let direction = Vector3::new(
+            theta.sin() * phi.cos(),
+            theta.sin() * phi.sin(),
+            theta.cos(),
+        );
+
+        self.center + direction * self.radius
+    }
+
+    /// Get the near/far parameters of a ray through the Sphere. The near
+    /// parameter is clamped at zero for a ray starting inside.
+    pub fn ray_intersections(&self, ray: &Ray) -> Option<(f64, f64)> {
+        collision::intersects::interval_ray_sphere(ray, self)
+    }
+}
+
+impl crate::geometry::Bounded for Sphere {
+    fn bounds(&self) -> Aabb {
+        Aabb::new(self.center, Vector3::ones() * self.radius)
+    }
+}
+
+impl crate::geometry::Transform for Sphere {
+    /// Transform the center. The radius is scaled assuming a uniform scale,
+    /// taken as the cube root of the linear part's determinant.
+    fn transform(&self, m: &Matrix4) -> Sphere {
+        let scale = m.fixed_view::<3, 3>(0, 0).determinant().abs().cbrt();
+        Sphere::new(self.center.transform(m), self.radius * scale)
+    }
+}
+
+impl crate::geometry::Distance<Sphere> for Sphere {
+    fn distance(&self, other: &Sphere) -> f64 {
+        collision::distance::distance_sphere_sphere(self, other)
+    }
+}
+
+impl crate::geometry::Intersection<Line> for Sphere {
+    fn intersection(&self, other: &Line) -> Option<Geometry> {
+        collision::intersection::intersection_line_sphere(other, self)
+    }
+}
+
+impl crate::geometry::Intersection<Ray> for Sphere {
+    fn intersection(&self, other: &Ray) -> Option<Geometry> {
+        collision::intersection::intersection_ray_sphere(other, self)
+    }
+}
+
+impl crate::geometry::Distance<Triangle> for Sphere {
+    fn distance(&self, other: &Triangle) -> f64 {
+        collision::distance::distance_sphere_triangle(self, other)
+    }
 }
 
 impl crate::geometry::Intersects<Aabb> for Sphere {
@@ -30,6 +93,12 @@ impl crate::geometry::Intersects<Aabb> for Sphere {
     }
 }
 
+impl crate::geometry::Intersects<Frustum> for Sphere {
+    fn intersects(&self, other: &Frustum) -> bool {
+        collision::intersects::intersects_frustum_sphere(other, self)
+    }
+}
+
 impl crate::geometry::Intersects<Ray> for Sphere {
     fn intersects(&self, other: &Ray) -> bool {
         collision::intersects::intersects_ray_sphere(other, self)
@@ -47,3 +116,31 @@ impl crate::geometry::Intersects<Vector3> for Sphere {
         collision::intersects::intersects_sphere_vector3(self, other)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::EPSILON;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn contains() {
+        let s = Sphere::new(Vector3::new(1., 2., 3.), 2.);
+
+        assert!(s.contains(s.center()));
+        assert!(s.contains(Vector3::new(1., 2., 5.)));
+        assert!(!s.contains(Vector3::new(1., 2., 5.5)));
+        assert!(!s.contains(Vector3::new(2.5, 3.5, 3.)));
+    }
+
+    #[test]
+    fn surface_point() {
+        let s = Sphere::new(Vector3::new(1., 2., 3.), 2.);
+        let p = s.surface_point(FRAC_PI_2, 0.);
+        let q = s.surface_point(0.7, 2.1);
+
+        assert!((p - Vector3::new(3., 2., 3.)).mag() < EPSILON);
+        assert!((s.surface_point(PI, 0.) - Vector3::new(1., 2., 1.)).mag() < EPSILON);
+        assert!(((q - s.center()).mag() - s.radius()).abs() < EPSILON);
+    }
+}
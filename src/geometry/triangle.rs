@@ -1,5 +1,14 @@
 use crate::geometry::collision;
-use crate::geometry::{Aabb, Geometry, Line, Ray, Vector3};
+use crate::geometry::{Aabb, Geometry, Line, Matrix4, Plane, Ray, Sphere, Vector3, EPSILON};
+
+/// Feature of a triangle containing the closest point to a query point.
+/// Vertices are numbered p, q, r and edge i runs from vertex i to the next.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TriangleRegion {
+    Vertex(usize),
+    Edge(usize),
+    Face,
+}
 
 #[derive(Debug, Copy, Clone)]
 pub struct Triangle {
@@ -26,16 +35,58 @@ impl Triangle {
         Vector3::cross(&u, &v)
     }
 
-    /// Get the unit normal
+    /// Check if the triangle is degenerate. The area is compared against
+    /// the square of the longest edge so the check does not depend on the
+    /// scale of the triangle.
+    pub fn is_degenerate(&self) -> bool {
+        let longest = [self.q - self.p, self.r - self.q, self.p - self.r]
+            .iter()
+            .map(|e| Vector3::dot(e, e))
+            .fold(0., f64::max);
+
+        self.normal().mag() <= EPSILON * longest
+    }
+
+    /// Get the unit normal (zeros for a degenerate triangle)
     pub fn unit_normal(&self) -> Vector3 {
         self.normal().unit()
     }
 
+    /// Get the plane through the first vertex with the unit normal
+    pub fn plane(&self) -> Plane {
+        Plane::new(self.p, self.normal())
+    }
+
+    /// Compute the signed distance from the plane of the triangle to a
+    /// point. The distance is positive on the side the normal points to.
+    pub fn signed_distance(&self, point: &Vector3) -> f64 {
+        self.plane().signed_distance(point)
+    }
+
     /// Get the area
     pub fn area(&self) -> f64 {
         self.normal().mag() * 0.5
     }
 
+    /// Get the shape quality as the ratio of twice the inradius to the
+    /// circumradius, from zero for a degenerate triangle to one for an
+    /// equilateral triangle
+    pub fn quality(&self) -> f64 {
+        let a = (self[1] - self[0]).mag();
+        let b = (self[2] - self[1]).mag();
+        let c = (self[0] - self[2]).mag();
+        let s = 0.5 * (a + b + c);
+        let area = self.area();
+
+        // The ratio is scale invariant so only an exactly zero denominator
+        // is degenerate
+        if s * a * b * c == 0. {
+            0.
+        } else {
+            8. * area * area / (s * a * b * c)
+        }
+    }
+
     /// Get the center
     pub fn center(&self) -> Vector3 {
         (self.p + self.q + self.r) / 3.
@@ -61,6 +112,151 @@ impl Triangle {
         Vector3::new(u, v, w)
     }
 
+    /// Get the circumcenter. This panics for a degenerate (collinear)
+    /// triangle.
+    pub fn circumcenter(&self) -> Vector3 {
+        self.try_circumcenter().expect("degenerate triangle")
+    }
+
+    /// Get the circumcenter or None for a degenerate (collinear) triangle
+    pub fn try_circumcenter(&self) -> Option<Vector3> {
+        if self.is_degenerate() {
+            return None;
+        }
+
+        let a = self.q - self.p;
+        let b = self.r - self.p;
+        let n = Vector3::cross(&a, &b);
+        let d = 2. * Vector3::dot(&n, &n);
+
+        let u = b * Vector3::dot(&a, &a) - a * Vector3::dot(&b, &b);
+        Some(self.p + Vector3::cross(&u, &n) / d)
+    }
+
+    /// Get the circumradius. This panics for a degenerate (collinear)
+    /// triangle.
+    pub fn circumradius(&self) -> f64 {
+        (self.circumcenter() - self.p).mag()
+    }
+
+    /// Check if a point lies within the triangle. The point must lie within
+    /// a distance eps of the triangle plane and no farther than eps outside
+    /// any edge, so points on an edge are contained. A degenerate triangle
+    /// contains no points.
+    pub fn contains(&self, point: &Vector3, eps: f64) -> bool {
+        if self.is_degenerate() {
+            return false;
+        }
+
+        let normal = self.unit_normal();
+
+        if Vector3::dot(&(*point - self.p), &normal).abs() > eps {
+            return false;
+        }
+
+        [(self.p, self.q), (self.q, self.r), (self.r, self.p)]
+            .iter()
+            .all(|&(a, b)| {
+                let edge = b - a;
+                let inside = Vector3::dot(&Vector3::cross(&edge, &(*point - a)), &normal);
+                inside >= -eps * edge.mag()
+            })
+    }
+
+    /// Get the closest point on the triangle to a point
+    pub fn closest_point(&self, point: &Vector3) -> Vector3 {
+        self.closest_point_region(point).0
+    }
+
+    /// Get the closest point on the triangle to a point along with the
+    /// vertex, edge or face region containing it
+    pub fn closest_point_region(&self, point: &Vector3) -> (Vector3, TriangleRegion) {
+        let (a, b, c) = (self.p, self.q, self.r);
+        let ab = b - a;
+        let ac = c - a;
+
+        // Vertex region of a
+        let ap = *point - a;
+        let d1 = Vector3::dot(&ab, &ap);
+        let d2 = Vector3::dot(&ac, &ap);
+
+        if d1 <= 0. && d2 <= 0. {
+            return (a, TriangleRegion::Vertex(0));
+        }
+
+        // Vertex region of b
+        let bp = *point - b;
+        let d3 = Vector3::dot(&ab, &bp);
+        let d4 = Vector3::dot(&ac, &bp);
+
+        if d3 >= 0. && d4 <= d3 {
+            return (b, TriangleRegion::Vertex(1));
+        }
+
+        // Edge region of ab
+        let vc = d1 * d4 - d3 * d2;
+
+        if vc <= 0. && d1 >= 0. && d3 <= 0. {
+            return (a + ab * (d1 / (d1 - d3)), TriangleRegion::Edge(0));
+        }
+
+        // Vertex region of c
+        let cp = *point - c;
+        let d5 = Vector3::dot(&ab, &cp);
+        let d6 = Vector3::dot(&ac, &cp);
+
+        if d6 >= 0. && d5 <= d6 {
+            return (c, TriangleRegion::Vertex(2));
+        }
+
+        // Edge region of ac
+        let vb = d5 * d2 - d1 * d6;
+
+        if vb <= 0. && d2 >= 0. && d6 <= 0. {
+            return (a + ac * (d2 / (d2 - d6)), TriangleRegion::Edge(2));
+        }
+
+        // Edge region of bc
+        let va = d3 * d6 - d5 * d4;
+
+        if va <= 0. && (d4 - d3) >= 0. && (d5 - d6) >= 0. {
+            let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+            return (b + (c - b) * t, TriangleRegion::Edge(1));
+        }
+
+        // Face region
+        let d = 1. / (va + vb + vc);
+        (a + ab * (vb * d) + ac * (vc * d), TriangleRegion::Face)
+    }
+
+    /// Split into four triangles at the edge midpoints. The corner triangles
+    /// are ordered by vertex and followed by the center triangle, all with
+    /// the same winding as the original.
+    pub fn subdivide(&self) -> [Triangle; 4] {
+        let pq = (self.p + self.q) * 0.5;
+        let qr = (self.q + self.r) * 0.5;
+        let rp = (self.r + self.p) * 0.5;
+
+        [
+            Triangle::new(self.p, pq, rp),
+            Triangle::new(pq, self.q, qr),
+            Triangle::new(rp, qr, self.r),
+            Triangle::new(pq, qr, rp),
+        ]
+    }
+
+    /// Check for an intersection with another Triangle using a tolerance
+    /// suited to the scale of the geometry
+    pub fn intersects_eps(&self, other: &Triangle, eps: f64) -> bool {
+        collision::intersects::intersects_triangle_triangle_eps(self, other, eps)
+    }
+
+    /// Get the intersection with another Triangle using a tolerance suited
+    /// to the scale of the geometry
+    pub fn intersection_eps(&self, other: &Triangle, eps: f64) -> Option<Geometry> {
+        collision::intersection::intersection_triangle_triangle_eps(self, other, eps)
+    }
+
     /// Get the edges of the triangle
     pub fn edges(&self) -> [Line; 3] {
         [
@@ -95,6 +291,36 @@ impl std::ops::IndexMut<usize> for Triangle {
     }
 }
 
+impl crate::geometry::Bounded for Triangle {
+    fn bounds(&self) -> Aabb {
+        let min = self.p.min(&self.q).min(&self.r);
+        let max = self.p.max(&self.q).max(&self.r);
+        Aabb::from_bounds(min, max)
+    }
+}
+
+impl crate::geometry::Transform for Triangle {
+    fn transform(&self, m: &Matrix4) -> Triangle {
+        Triangle::new(
+            self.p.transform(m),
+            self.q.transform(m),
+            self.r.transform(m),
+        )
+    }
+}
+
+impl crate::geometry::Distance<Line> for Triangle {
+    fn distance(&self, other: &Line) -> f64 {
+        collision::distance::distance_line_triangle(other, self)
+    }
+}
+
+impl crate::geometry::Distance<Sphere> for Triangle {
+    fn distance(&self, other: &Sphere) -> f64 {
+        collision::distance::distance_sphere_triangle(other, self)
+    }
+}
+
 impl crate::geometry::Intersects<Aabb> for Triangle {
     fn intersects(&self, other: &Aabb) -> bool {
         collision::intersects::intersects_aabb_triangle(other, self)
@@ -114,9 +340,8 @@ impl crate::geometry::Intersects<Triangle> for Triangle {
 }
 
 impl crate::geometry::Intersects<Vector3> for Triangle {
-    fn intersects(&self, _other: &Vector3) -> bool {
-        // TODO: implement
-        unimplemented!();
+    fn intersects(&self, other: &Vector3) -> bool {
+        self.contains(other, EPSILON)
     }
 }
 
@@ -131,3 +356,187 @@ impl crate::geometry::Intersection<Triangle> for Triangle {
         collision::intersection::intersection_triangle_triangle(self, other)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Intersects;
+
+    #[test]
+    fn circumcenter_equilateral() {
+        let p = Vector3::new(1., 0., 0.);
+        let q = Vector3::new(0., 1., 0.);
+        let r = Vector3::new(0., 0., 1.);
+        let t = Triangle::new(p, q, r);
+
+        assert!((t.circumcenter() - t.center()).mag() < EPSILON);
+        assert!((t.circumradius() - (2f64 / 3.).sqrt()).abs() < EPSILON);
+    }
+
+    #[test]
+    fn circumcenter_right() {
+        let p = Vector3::new(0., 0., 1.);
+        let q = Vector3::new(3., 0., 1.);
+        let r = Vector3::new(0., 4., 1.);
+        let t = Triangle::new(p, q, r);
+
+        assert!((t.circumcenter() - Vector3::new(1.5, 2., 1.)).mag() < EPSILON);
+        assert!((t.circumradius() - 2.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn circumcenter_small() {
+        let p = Vector3::new(0., 0., 1e-5);
+        let q = Vector3::new(3e-5, 0., 1e-5);
+        let r = Vector3::new(0., 4e-5, 1e-5);
+        let t = Triangle::new(p, q, r);
+        let center = t.try_circumcenter().unwrap();
+
+        assert!((center - Vector3::new(1.5e-5, 2e-5, 1e-5)).mag() < 1e-15);
+        assert!((t.circumradius() - 2.5e-5).abs() < 1e-15);
+
+        // A flat triangle has no circumcenter at any scale
+        let t = Triangle::new(p * 1e5, q * 1e5, (p + q) * 0.5e5);
+        assert!(t.try_circumcenter().is_none());
+    }
+
+    #[test]
+    fn plane() {
+        let p = Vector3::new(0., 0., 1.);
+        let q = Vector3::new(2., 0., 1.);
+        let r = Vector3::new(0., 2., 1.);
+        let t = Triangle::new(p, q, r);
+        let plane = t.plane();
+
+        assert_eq!(plane.origin(), p);
+        assert_eq!(plane.normal(), Vector3::new(0., 0., 1.));
+        assert_eq!(t.signed_distance(&Vector3::new(5., 5., 3.)), 2.);
+        assert_eq!(t.signed_distance(&Vector3::new(0.5, 0.5, 0.)), -1.);
+
+        // Reversing the winding flips the sign
+        let t = Triangle::new(p, r, q);
+        assert_eq!(t.signed_distance(&Vector3::new(5., 5., 3.)), -2.);
+    }
+
+    #[test]
+    fn quality() {
+        let p = Vector3::new(0., 0., 0.);
+        let q = Vector3::new(1., 0., 0.);
+        let r = Vector3::new(0.5, 3f64.sqrt() / 2., 0.);
+
+        assert!((Triangle::new(p, q, r).quality() - 1.).abs() <= EPSILON);
+        assert!((Triangle::new(p * 1e-3, q * 1e-3, r * 1e-3).quality() - 1.).abs() <= EPSILON);
+        assert!(Triangle::new(p, q, Vector3::new(0.5, 1e-3, 0.)).quality() < 0.01);
+        assert_eq!(Triangle::new(p, q, q * 2.).quality(), 0.);
+        assert_eq!(Triangle::new(p, p, p).quality(), 0.);
+    }
+
+    #[test]
+    fn subdivide() {
+        let p = Vector3::new(0., 0., 0.);
+        let q = Vector3::new(2., 0., 0.);
+        let r = Vector3::new(0., 2., 0.);
+        let t = Triangle::new(p, q, r);
+        let children = t.subdivide();
+
+        assert_eq!(
+            children[0].vertices(),
+            (p, Vector3::new(1., 0., 0.), Vector3::new(0., 1., 0.))
+        );
+        assert_eq!(children[3].center(), t.center());
+
+        for child in children.iter() {
+            assert_eq!(child.area(), t.area() / 4.);
+            assert_eq!(child.unit_normal(), t.unit_normal());
+        }
+    }
+
+    #[test]
+    fn closest_point() {
+        let p = Vector3::new(0., 0., 0.);
+        let q = Vector3::new(1., 0., 0.);
+        let r = Vector3::new(0., 1., 0.);
+        let t = Triangle::new(p, q, r);
+
+        let face = Vector3::new(0.25, 0.25, 1.);
+        assert_eq!(t.closest_point(&face), Vector3::new(0.25, 0.25, 0.));
+
+        let edge = Vector3::new(1., 1., 0.);
+        assert_eq!(t.closest_point(&edge), Vector3::new(0.5, 0.5, 0.));
+
+        let vertex = Vector3::new(-1., -1., -1.);
+        assert_eq!(t.closest_point(&vertex), p);
+    }
+
+    #[test]
+    fn closest_point_region() {
+        let p = Vector3::new(0., 0., 0.);
+        let q = Vector3::new(1., 0., 0.);
+        let r = Vector3::new(0., 1., 0.);
+        let t = Triangle::new(p, q, r);
+
+        let region = |x: f64, y: f64| t.closest_point_region(&Vector3::new(x, y, 1.)).1;
+
+        assert_eq!(region(0.25, 0.25), TriangleRegion::Face);
+        assert_eq!(region(-1., -1.), TriangleRegion::Vertex(0));
+        assert_eq!(region(2., -0.5), TriangleRegion::Vertex(1));
+        assert_eq!(region(-0.5, 2.), TriangleRegion::Vertex(2));
+        assert_eq!(region(0.5, -1.), TriangleRegion::Edge(0));
+        assert_eq!(region(1., 1.), TriangleRegion::Edge(1));
+        assert_eq!(region(-1., 0.5), TriangleRegion::Edge(2));
+    }
+
+    #[test]
+    fn circumcenter_degenerate() {
+        let p = Vector3::new(0., 0., 0.);
+        let q = Vector3::new(1., 1., 1.);
+        let r = Vector3::new(2., 2., 2.);
+        let t = Triangle::new(p, q, r);
+
+        assert!(t.try_circumcenter().is_none());
+    }
+
+    #[test]
+    fn is_degenerate() {
+        let p = Vector3::new(0., 0., 0.);
+        let q = Vector3::new(1., 1., 1.);
+
+        assert!(Triangle::new(p, q, q * 2.).is_degenerate());
+        assert!(Triangle::new(p, q, q).is_degenerate());
+        assert!(!Triangle::new(p, q, Vector3::new(1., 0., 0.)).is_degenerate());
+        assert!(!Triangle::new(p, q * 1e-6, Vector3::new(1e-6, 0., 0.)).is_degenerate());
+    }
+
+    #[test]
+    fn unit_normal_degenerate() {
+        let p = Vector3::new(0., 0., 0.);
+        let q = Vector3::new(1., 1., 1.);
+        let r = Vector3::new(2., 2., 2.);
+        let t = Triangle::new(p, q, r);
+
+        assert_eq!(t.unit_normal(), Vector3::zeros());
+    }
+
+    #[test]
+    fn contains() {
+        let triangle = Triangle::new(
+            Vector3::new(0., 0., 0.),
+            Vector3::new(1., 0., 0.),
+            Vector3::new(0., 1., 0.),
+        );
+
+        assert!(triangle.contains(&Vector3::new(0.25, 0.25, 0.), EPSILON));
+        assert!(triangle.contains(&Vector3::new(0.5, 0., 0.), EPSILON));
+        assert!(triangle.contains(&Vector3::new(0.5, 0.5, 0.), EPSILON));
+        assert!(triangle.contains(&Vector3::new(0., 0., 0.), EPSILON));
+        assert!(!triangle.contains(&Vector3::new(0.5, -1e-6, 0.), EPSILON));
+        assert!(!triangle.contains(&Vector3::new(0.5, 0.5 + 1e-6, 0.), EPSILON));
+        assert!(!triangle.contains(&Vector3::new(0.25, 0.25, 1e-6), EPSILON));
+        assert!(triangle.contains(&Vector3::new(0.25, 0.25, 1e-6), 1e-5));
+        assert!(triangle.intersects(&Vector3::new(0.5, 0., 0.)));
+
+        let (p, q, r) = triangle.vertices();
+        let small = Triangle::new(p * 1e-6, q * 1e-6, r * 1e-6);
+        assert!(small.contains(&Vector3::new(0.25e-6, 0.25e-6, 0.), 1e-12));
+    }
+}
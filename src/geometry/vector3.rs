@@ -1,7 +1,8 @@
 use crate::geometry::collision;
-use crate::geometry::{Aabb, Sphere};
+use crate::geometry::{Aabb, Matrix4, Sphere};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector3 {
     x: f64,
     y: f64,
@@ -14,6 +15,11 @@ impl Vector3 {
         Vector3 { x, y, z }
     }
 
+    /// Construct a Vector3 from an array of its components
+    pub fn from_array(array: [f64; 3]) -> Vector3 {
+        Vector3::new(array[0], array[1], array[2])
+    }
+
     /// Construct a Vector3 of all zeros
     pub fn zeros() -> Vector3 {
         Vector3::new(0., 0., 0.)
@@ -52,11 +58,49 @@ impl Vector3 {
         self.z
     }
 
-    /// Compute the angle (in radians) between u and v
+    /// Get the components as an array
+    pub fn to_array(&self) -> [f64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    /// Compute the angle (in radians) between u and v. The angle is zero if
+    /// either vector is zero.
     pub fn angle(u: &Vector3, v: &Vector3) -> f64 {
-        (Vector3::dot(&u, &v) / (u.mag() * v.mag()))
-            .clamp(-1., 1.)
-            .acos()
+        match (u.try_unit(), v.try_unit()) {
+            (Some(u), Some(v)) => Vector3::dot(&u, &v).clamp(-1., 1.).acos(),
+            _ => 0.,
+        }
+    }
+
+    /// Compute the signed angle (in radians) from u to v in [-π, π]. The
+    /// angle is positive when rotating u toward v is counter-clockwise about
+    /// the axis. The angle is zero if either vector is zero.
+    pub fn angle_signed(u: &Vector3, v: &Vector3, axis: &Vector3) -> f64 {
+        match (u.try_unit(), v.try_unit()) {
+            (Some(u), Some(v)) => {
+                let sin = Vector3::dot(&Vector3::cross(&u, &v), &axis.unit());
+                sin.atan2(Vector3::dot(&u, &v))
+            }
+            _ => 0.,
+        }
+    }
+
+    /// Get two unit vectors orthogonal to the vector and to each other. The
+    /// first is found by crossing with the axis of the smallest component,
+    /// which is never close to parallel. The vector need not be a unit
+    /// vector, but a zero vector returns zeros.
+    pub fn orthonormal_basis(&self) -> (Vector3, Vector3) {
+        let n = self.unit();
+        let axis = match n.abs().min_index() {
+            0 => Vector3::new(1., 0., 0.),
+            1 => Vector3::new(0., 1., 0.),
+            _ => Vector3::new(0., 0., 1.),
+        };
+
+        let u = Vector3::cross(&n, &axis).unit();
+        let v = Vector3::cross(&n, &u);
+
+        (u, v)
     }
 
     /// Get the magnitude
@@ -64,9 +108,35 @@ impl Vector3 {
         Vector3::dot(self, self).sqrt()
     }
 
-    /// Get the unit (magnitude = 1)
+    /// Get the unit (magnitude = 1). A zero (or non-finite) vector has no
+    /// direction and returns zeros.
     pub fn unit(&self) -> Vector3 {
-        *self / self.mag()
+        self.try_unit().unwrap_or_else(Vector3::zeros)
+    }
+
+    /// Get the unit (magnitude = 1) or None for a zero (or non-finite)
+    /// vector. The components are scaled by the largest before normalizing
+    /// so tiny vectors do not underflow.
+    pub fn try_unit(&self) -> Option<Vector3> {
+        let scale = self.x.abs().max(self.y.abs()).max(self.z.abs());
+
+        if scale == 0. || !scale.is_finite() {
+            return None;
+        }
+
+        let v = *self / scale;
+        Some(v / v.mag())
+    }
+
+    /// Get the integer coordinates of the cubic grid cell of the given size
+    /// containing the vector. The coordinates are hashable and may be used to
+    /// bucket nearby points.
+    pub fn quantize(&self, cell: f64) -> (i64, i64, i64) {
+        (
+            (self.x / cell).floor() as i64,
+            (self.y / cell).floor() as i64,
+            (self.z / cell).floor() as i64,
+        )
     }
 
     /// Get the inverse
@@ -79,6 +149,29 @@ impl Vector3 {
         Vector3::new(self.x.abs(), self.y.abs(), self.z.abs())
     }
 
+    /// Get the componentwise minimum with another vector
+    pub fn min(&self, other: &Vector3) -> Vector3 {
+        Vector3::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Get the componentwise maximum with another vector
+    pub fn max(&self, other: &Vector3) -> Vector3 {
+        Vector3::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    /// Clamp each component between the components of lo and hi
+    pub fn clamp(&self, lo: &Vector3, hi: &Vector3) -> Vector3 {
+        self.max(lo).min(hi)
+    }
+
     /// Get the index of the minimal component
     pub fn min_index(&self) -> usize {
         let mut index = 0;
@@ -110,6 +203,30 @@ impl Vector3 {
     }
 }
 
+impl From<[f64; 3]> for Vector3 {
+    fn from(array: [f64; 3]) -> Self {
+        Vector3::from_array(array)
+    }
+}
+
+impl From<(f64, f64, f64)> for Vector3 {
+    fn from((x, y, z): (f64, f64, f64)) -> Self {
+        Vector3::new(x, y, z)
+    }
+}
+
+impl From<Vector3> for [f64; 3] {
+    fn from(v: Vector3) -> Self {
+        v.to_array()
+    }
+}
+
+impl From<Vector3> for (f64, f64, f64) {
+    fn from(v: Vector3) -> Self {
+        (v.x, v.y, v.z)
+    }
+}
+
 impl std::ops::Index<usize> for Vector3 {
     type Output = f64;
 
@@ -313,3 +430,139 @@ impl crate::geometry::Intersects<Sphere> for Vector3 {
         collision::intersects::intersects_sphere_vector3(other, self)
     }
 }
+
+impl crate::geometry::Bounded for Vector3 {
+    /// A point is bounded by a degenerate box
+    fn bounds(&self) -> Aabb {
+        Aabb::new(*self, Vector3::zeros())
+    }
+}
+
+impl crate::geometry::Transform for Vector3 {
+    fn transform(&self, m: &Matrix4) -> Vector3 {
+        let p = m * nalgebra::Vector4::new(self.x, self.y, self.z, 1.);
+        Vector3::new(p[0], p[1], p[2]) / p[3]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::geometry::EPSILON;
+
+    #[test]
+    fn array_round_trip() {
+        let v = Vector3::from([1., -2., 3.5]);
+        let array: [f64; 3] = v.into();
+
+        assert_eq!(v, Vector3::new(1., -2., 3.5));
+        assert_eq!(array, [1., -2., 3.5]);
+        assert_eq!(Vector3::from_array(v.to_array()), v);
+    }
+
+    #[test]
+    fn min_max() {
+        let u = Vector3::new(-1., 2., -3.);
+        let v = Vector3::new(1., -2., -4.);
+
+        assert_eq!(u.min(&v), Vector3::new(-1., -2., -4.));
+        assert_eq!(u.max(&v), Vector3::new(1., 2., -3.));
+    }
+
+    #[test]
+    fn clamp() {
+        let lo = Vector3::new(-1., -1., 0.);
+        let hi = Vector3::new(1., 1., 2.);
+        let v = Vector3::new(-3., 0.5, 5.);
+
+        assert_eq!(v.clamp(&lo, &hi), Vector3::new(-1., 0.5, 2.));
+    }
+
+    #[test]
+    fn tuple_round_trip() {
+        let v = Vector3::from((1., -2., 3.5));
+        let tuple: (f64, f64, f64) = v.into();
+
+        assert_eq!(v, Vector3::new(1., -2., 3.5));
+        assert_eq!(tuple, (1., -2., 3.5));
+    }
+
+    #[test]
+    fn unit_zero() {
+        let v = Vector3::zeros();
+
+        assert_eq!(v.unit(), Vector3::zeros());
+        assert!(v.try_unit().is_none());
+        assert_eq!(Vector3::angle(&v, &Vector3::ones()), 0.);
+        assert_eq!(
+            Vector3::new(0., 3., 4.).try_unit(),
+            Some(Vector3::new(0., 0.6, 0.8))
+        );
+    }
+
+    #[test]
+    fn unit_small() {
+        let u = Vector3::new(1e-9, 0., 0.);
+        let v = Vector3::new(0., 1e-9, 0.);
+
+        assert_eq!(u.unit(), Vector3::new(1., 0., 0.));
+        assert!((Vector3::new(3e-200, 4e-200, 0.).unit().mag() - 1.).abs() < EPSILON);
+        assert!((Vector3::angle(&u, &v) - std::f64::consts::FRAC_PI_2).abs() < EPSILON);
+        assert!(Vector3::new(f64::NAN, 0., 0.).try_unit().is_none());
+    }
+
+    #[test]
+    fn quantize() {
+        let u = Vector3::new(0.01, 0.99, -0.01);
+        let v = Vector3::new(0.49, 0.51, -0.49);
+        let w = Vector3::new(0.51, 0.51, -0.49);
+
+        assert_eq!(u.quantize(0.5), (0, 1, -1));
+        assert_eq!(u.quantize(0.5), v.quantize(0.5));
+        assert_ne!(v.quantize(0.5), w.quantize(0.5));
+    }
+
+    #[test]
+    fn angle_signed() {
+        let u = Vector3::new(1., 0., 0.);
+        let v = Vector3::new(0., 2., 0.);
+        let axis = Vector3::new(0., 0., 1.);
+        let half_pi = std::f64::consts::FRAC_PI_2;
+
+        assert!((Vector3::angle_signed(&u, &v, &axis) - half_pi).abs() < EPSILON);
+        assert!((Vector3::angle_signed(&v, &u, &axis) + half_pi).abs() < EPSILON);
+        assert!((Vector3::angle_signed(&u, &v, &-axis) + half_pi).abs() < EPSILON);
+        assert!(
+            (Vector3::angle_signed(&u, &-u, &axis).abs() - std::f64::consts::PI).abs() < EPSILON
+        );
+        assert_eq!(Vector3::angle_signed(&u, &Vector3::zeros(), &axis), 0.);
+    }
+
+    #[test]
+    fn orthonormal_basis() {
+        let normals = [
+            Vector3::new(1., 0., 0.),
+            Vector3::new(0., -1., 0.),
+            Vector3::new(0., 0., 3.),
+            Vector3::new(1., 2., 3.),
+            Vector3::new(-0.3, 1e-9, 0.7),
+        ];
+
+        for normal in normals {
+            let (u, v) = normal.orthonormal_basis();
+            let n = normal.unit();
+
+            assert!((u.mag() - 1.).abs() < EPSILON);
+            assert!((v.mag() - 1.).abs() < EPSILON);
+            assert!(Vector3::dot(&u, &n).abs() < EPSILON);
+            assert!(Vector3::dot(&v, &n).abs() < EPSILON);
+            assert!(Vector3::dot(&u, &v).abs() < EPSILON);
+            assert!((Vector3::dot(&Vector3::cross(&u, &v), &n) - 1.).abs() < EPSILON);
+        }
+
+        let (u, v) = Vector3::zeros().orthonormal_basis();
+        assert_eq!(u, Vector3::zeros());
+        assert_eq!(v, Vector3::zeros());
+    }
+}
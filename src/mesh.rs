@@ -1,8 +1,20 @@
 pub mod half_edge;
+pub mod handle;
+pub mod marching_cubes;
 pub mod polygon_soup;
 pub mod wavefront;
 
+// Private modules
+mod boolean;
+#[cfg(test)]
+mod fixtures;
+mod remesh;
+mod repair;
+
 // Re-exports
-pub use half_edge::HeMesh;
-pub use polygon_soup::PolygonSoupMesh;
-pub use wavefront::{ObjReader, ObjWriter};
+pub use half_edge::{HeMesh, HeMeshError, MeshFeature};
+pub use handle::{FaceHandle, HalfEdgeHandle, VertexHandle};
+pub use marching_cubes::marching_cubes;
+pub use polygon_soup::{PolygonSoupMesh, SoupError};
+pub use repair::{RepairOptions, RepairReport};
+pub use wavefront::{ObjReader, ObjWriter, UpAxis};
@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+
+use crate::geometry::{Aabb, Geometry, Intersection, Line, Triangle, Vector3, EPSILON};
+use crate::mesh::half_edge::weld_points;
+use crate::mesh::{HeMesh, HeMeshError, PolygonSoupMesh};
+use crate::spatial::{Octree, Query};
+
+impl HeMesh {
+    /// Compute the boolean union with another mesh. Both meshes must be
+    /// closed, oriented outward, and composed of triangles. Coplanar
+    /// overlapping faces are not supported and result in an error.
+    pub fn union(&self, other: &HeMesh) -> Result<HeMesh, HeMeshError> {
+        boolean(self, other, BooleanOperation::Union)
+    }
+
+    /// Compute the boolean difference with another mesh. Both meshes must be
+    /// closed, oriented outward, and composed of triangles. Coplanar
+    /// overlapping faces are not supported and result in an error.
+    pub fn difference(&self, other: &HeMesh) -> Result<HeMesh, HeMeshError> {
+        boolean(self, other, BooleanOperation::Difference)
+    }
+
+    /// Compute the boolean intersection with another mesh. Both meshes must
+    /// be closed, oriented outward, and composed of triangles. Coplanar
+    /// overlapping faces are not supported and result in an error.
+    pub fn intersection(&self, other: &HeMesh) -> Result<HeMesh, HeMeshError> {
+        boolean(self, other, BooleanOperation::Intersection)
+    }
+}
+
+/// Intersection segments indexed by the face they lie on
+type FaceSegments = HashMap<usize, Vec<Line>>;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum BooleanOperation {
+    Union,
+    Difference,
+    Intersection,
+}
+
+/// Compute a boolean operation between two meshes. The faces of each mesh
+/// are split along the intersection segments with the other mesh, each
+/// fragment is classified as inside or outside of the other mesh, and the
+/// retained fragments are reassembled into a new mesh.
+fn boolean(a: &HeMesh, b: &HeMesh, operation: BooleanOperation) -> Result<HeMesh, HeMeshError> {
+    validate(a)?;
+    validate(b)?;
+
+    let (segments_a, segments_b) = intersection_segments(a, b)?;
+    let mut patches: Vec<String> = a.patches().iter().map(|p| p.name().to_string()).collect();
+    let mut index_patches = HashMap::<usize, usize>::new();
+
+    for (i, patch) in b.patches().iter().enumerate() {
+        match patches.iter().position(|p| p == patch.name()) {
+            Some(j) => index_patches.insert(i, j),
+            None => {
+                patches.push(patch.name().to_string());
+                index_patches.insert(i, patches.len() - 1)
+            }
+        };
+    }
+
+    let mut triangles = vec![];
+
+    for (triangle, patch) in split_faces(a, &segments_a) {
+        let inside = b.contains_point(&triangle.center());
+
+        let keep = match operation {
+            BooleanOperation::Union | BooleanOperation::Difference => !inside,
+            BooleanOperation::Intersection => inside,
+        };
+
+        if keep {
+            triangles.push((triangle, patch));
+        }
+    }
+
+    for (triangle, patch) in split_faces(b, &segments_b) {
+        let inside = a.contains_point(&triangle.center());
+        let patch = patch.map(|p| index_patches[&p]);
+
+        match operation {
+            BooleanOperation::Union if !inside => triangles.push((triangle, patch)),
+            BooleanOperation::Intersection if inside => triangles.push((triangle, patch)),
+            BooleanOperation::Difference if inside => {
+                let flipped = Triangle::new(triangle[0], triangle[2], triangle[1]);
+                triangles.push((flipped, patch));
+            }
+            _ => (),
+        }
+    }
+
+    assemble(&triangles, &patches)
+}
+
+/// Check that a mesh is valid for a boolean operation
+fn validate(mesh: &HeMesh) -> Result<(), HeMeshError> {
+    if !mesh.is_closed() {
+        return Err(HeMeshError::Open);
+    }
+
+    if !mesh.is_consistent() {
+        return Err(HeMeshError::Inconsistent);
+    }
+
+    if !mesh.is_triangles() {
+        return Err(HeMeshError::NonTriangular);
+    }
+
+    Ok(())
+}
+
+/// Compute the intersection segments between the faces of two meshes. The
+/// segments are indexed by the face of each mesh they lie on.
+fn intersection_segments(
+    a: &HeMesh,
+    b: &HeMesh,
+) -> Result<(FaceSegments, FaceSegments), HeMeshError> {
+    let mut segments_a = FaceSegments::new();
+    let mut segments_b = FaceSegments::new();
+
+    for (i, j) in a.intersecting_faces(b) {
+        let ta = a.face_triangles(i)[0];
+        let tb = b.face_triangles(j)[0];
+        let normal = ta.unit_normal();
+
+        let coplanar = (0..3)
+            .map(|k| Vector3::dot(&normal, &(tb[k] - ta[0])))
+            .all(|d| d.abs() <= EPSILON);
+
+        if coplanar {
+            return Err(HeMeshError::Coplanar);
+        }
+
+        if let Some(Geometry::Line(line)) = ta.intersection(&tb) {
+            if line.length() > EPSILON {
+                segments_a.entry(i).or_default().push(line);
+                segments_b.entry(j).or_default().push(line);
+            }
+        }
+    }
+
+    Ok((segments_a, segments_b))
+}
+
+/// Split the faces of a mesh along the lines through each segment lying on
+/// the face. Splitting along the full line guarantees that no fragment
+/// straddles the intersection with the other mesh.
+fn split_faces(mesh: &HeMesh, segments: &FaceSegments) -> Vec<(Triangle, Option<usize>)> {
+    let mut fragments = vec![];
+
+    for face_id in 0..mesh.n_faces() {
+        let patch = mesh.face(face_id).patch();
+        let triangle = mesh.face_triangles(face_id)[0];
+        let normal = triangle.unit_normal();
+        let mut current = vec![triangle];
+
+        if let Some(lines) = segments.get(&face_id) {
+            for line in lines.iter() {
+                current = current
+                    .iter()
+                    .flat_map(|t| split_triangle(t, line, &normal))
+                    .collect();
+            }
+        }
+
+        fragments.extend(current.into_iter().map(|t| (t, patch)));
+    }
+
+    fragments
+}
+
+/// Split a triangle along the line through a segment lying in the plane of
+/// the triangle. The orientation of the triangle is preserved.
+fn split_triangle(triangle: &Triangle, line: &Line, normal: &Vector3) -> Vec<Triangle> {
+    let side = Vector3::cross(&line.direction(), normal).unit();
+
+    let d = [0, 1, 2].map(|i| {
+        let d = Vector3::dot(&side, &(triangle[i] - line.p()));
+        if d.abs() <= EPSILON {
+            0.
+        } else {
+            d
+        }
+    });
+
+    if d.iter().all(|&d| d >= 0.) || d.iter().all(|&d| d <= 0.) {
+        return vec![*triangle];
+    }
+
+    let mut positive = vec![];
+    let mut negative = vec![];
+
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+
+        if d[i] >= 0. {
+            positive.push(triangle[i]);
+        }
+
+        if d[i] <= 0. {
+            negative.push(triangle[i]);
+        }
+
+        if (d[i] < 0. && d[j] > 0.) || (d[i] > 0. && d[j] < 0.) {
+            let s = d[i] / (d[i] - d[j]);
+            let point = triangle[i] + (triangle[j] - triangle[i]) * s;
+            positive.push(point);
+            negative.push(point);
+        }
+    }
+
+    [positive, negative]
+        .iter()
+        .flat_map(|p| (1..p.len() - 1).map(|i| Triangle::new(p[0], p[i], p[i + 1])))
+        .collect()
+}
+
+/// Assemble a mesh from a set of triangles. Coincident vertices are welded
+/// and any vertex lying on the edge of a triangle (a T-junction) is inserted
+/// into the triangle so the resulting mesh is conforming.
+fn assemble(
+    triangles: &[(Triangle, Option<usize>)],
+    patches: &[String],
+) -> Result<HeMesh, HeMeshError> {
+    let points: Vec<Vector3> = triangles
+        .iter()
+        .flat_map(|(t, _)| [t[0], t[1], t[2]])
+        .collect();
+
+    let welded = weld_points(&points, EPSILON);
+    let mut vertices = vec![];
+    let mut index = vec![0; points.len()];
+
+    for (i, &j) in welded.iter().enumerate() {
+        if i == j {
+            index[i] = vertices.len();
+            vertices.push(points[i]);
+        }
+    }
+
+    let mut min = Vector3::ones() * f64::INFINITY;
+    let mut max = Vector3::ones() * f64::NEG_INFINITY;
+
+    for vertex in vertices.iter() {
+        for i in 0..3 {
+            min[i] = min[i].min(vertex[i]);
+            max[i] = max[i].max(vertex[i]);
+        }
+    }
+
+    let mut octree = Octree::<Vector3>::new(Aabb::from_bounds(min - EPSILON, max + EPSILON));
+
+    for vertex in vertices.iter() {
+        octree.insert(*vertex);
+    }
+
+    let mut soup = PolygonSoupMesh::new();
+
+    for patch in patches.iter() {
+        soup.insert_patch(patch);
+    }
+
+    let mut faces = vec![];
+
+    for (k, (_, patch)) in triangles.iter().enumerate() {
+        let face = [0, 1, 2].map(|i| index[welded[3 * k + i]]);
+
+        if face[0] == face[1] || face[1] == face[2] || face[2] == face[0] {
+            continue;
+        }
+
+        let mut polygon = vec![];
+
+        for i in 0..3 {
+            let p = vertices[face[i]];
+            let q = vertices[face[(i + 1) % 3]];
+            let direction = q - p;
+            let length = direction.mag();
+            let query = Aabb::from_bounds(p.min(&q) - EPSILON, p.max(&q) + EPSILON);
+
+            let mut inserted: Vec<(f64, usize)> = octree
+                .query(&query)
+                .into_iter()
+                .filter_map(|j| {
+                    let s = Vector3::dot(&(vertices[j] - p), &direction) / (length * length);
+                    let closest = p + direction * s;
+                    let on_edge = s * length > EPSILON && (1. - s) * length > EPSILON;
+
+                    if on_edge && (vertices[j] - closest).mag() <= EPSILON {
+                        Some((s, j))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            inserted.sort_by(|a, b| a.0.total_cmp(&b.0));
+            polygon.push(face[i]);
+            polygon.extend(inserted.into_iter().map(|(_, j)| j));
+        }
+
+        faces.push((polygon, *patch));
+    }
+
+    for vertex in vertices.iter() {
+        soup.insert_vertex(*vertex);
+    }
+
+    let mut n = vertices.len();
+
+    for (polygon, patch) in faces.iter() {
+        if polygon.len() == 3 {
+            soup.insert_face(polygon, *patch);
+            continue;
+        }
+
+        let center = polygon
+            .iter()
+            .fold(Vector3::zeros(), |c, &v| c + vertices[v])
+            / polygon.len() as f64;
+
+        soup.insert_vertex(center);
+
+        for i in 0..polygon.len() {
+            let j = (i + 1) % polygon.len();
+            soup.insert_face(&[polygon[i], polygon[j], n], *patch);
+        }
+
+        n += 1;
+    }
+
+    HeMesh::new(&soup)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mesh::ObjReader;
+
+    fn import_box(center: Vector3, scale: f64) -> HeMesh {
+        let path = "tests/fixtures/box.obj";
+        let soup = ObjReader::new(path).read().unwrap();
+        let mut other = PolygonSoupMesh::new();
+
+        for i in 0..soup.n_vertices() {
+            other.insert_vertex(soup.vertex(i) * scale + center);
+        }
+
+        for i in 0..soup.n_faces() {
+            let (vertices, patch) = soup.face(i);
+            other.insert_face(vertices, patch);
+        }
+
+        HeMesh::new(&other).unwrap()
+    }
+
+    #[test]
+    fn difference() {
+        let a = import_box(Vector3::zeros(), 2.);
+        let b = import_box(Vector3::new(1., 0.1, 0.2), 1.);
+        let mesh = a.difference(&b).unwrap();
+
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 7.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn union() {
+        let a = import_box(Vector3::zeros(), 2.);
+        let b = import_box(Vector3::new(1., 0.1, 0.2), 1.);
+        let mesh = a.union(&b).unwrap();
+
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 8.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn intersection() {
+        let a = import_box(Vector3::zeros(), 2.);
+        let b = import_box(Vector3::new(1., 0.1, 0.2), 1.);
+        let mesh = a.intersection(&b).unwrap();
+
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn difference_coplanar() {
+        let a = import_box(Vector3::zeros(), 2.);
+        let b = import_box(Vector3::new(0.5, 0.1, 0.2), 1.);
+        let result = a.difference(&b);
+
+        assert!(result.is_err_and(|e| e.to_string() == "coplanar faces"));
+    }
+}
@@ -0,0 +1,33 @@
+use crate::geometry::Vector3;
+use crate::mesh::{HeMesh, PolygonSoupMesh};
+
+/// Build a flat grid of n by n vertices in the xy-plane with the given
+/// spacing. Each cell is a quad or is split into two triangles along the
+/// diagonal from its lower-left corner. Vertices are ordered with x varying
+/// fastest.
+pub fn plane_grid(n: usize, spacing: f64, quads: bool) -> HeMesh {
+    let mut soup = PolygonSoupMesh::new();
+
+    for j in 0..n {
+        for i in 0..n {
+            let x = i as f64 * spacing;
+            let y = j as f64 * spacing;
+            soup.insert_vertex(Vector3::new(x, y, 0.));
+        }
+    }
+
+    for j in 0..n - 1 {
+        for i in 0..n - 1 {
+            let a = j * n + i;
+
+            if quads {
+                soup.insert_face(&[a, a + 1, a + n + 1, a + n], None);
+            } else {
+                soup.insert_face(&[a, a + 1, a + n + 1], None);
+                soup.insert_face(&[a, a + n + 1, a + n], None);
+            }
+        }
+    }
+
+    HeMesh::new(&soup).unwrap()
+}
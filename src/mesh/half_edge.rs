@@ -1,7 +1,23 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 
-use crate::geometry::{Aabb, Vector3};
-use crate::mesh::{ObjReader, ObjWriter, PolygonSoupMesh};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::geometry::{
+    Aabb, Distance, Line, Matrix4, Plane, Ray, Transform, Triangle, TriangleRegion, Vector3,
+    EPSILON,
+};
+use crate::mesh::{ObjReader, ObjWriter, PolygonSoupMesh, SoupError};
+use crate::spatial::{Octree, Query};
+
+/// Names of the vertex scalars holding the RGB components of vertex colors
+const COLOR_SCALARS: [&str; 3] = ["red", "green", "blue"];
+
+/// FNV-1a parameters of the content hash
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
 
 #[derive(Debug, Clone, Default)]
 pub struct HeMesh {
@@ -9,11 +25,15 @@ pub struct HeMesh {
     faces: Vec<HeFace>,
     half_edges: Vec<HeHalfEdge>,
     patches: Vec<HePatch>,
+    vertex_scalars: HashMap<String, Vec<f64>>,
+    face_scalars: HashMap<String, Vec<f64>>,
 }
 
 impl HeMesh {
     /// Construct a half edge mesh from a polygon soup mesh
     pub fn new(soup: &PolygonSoupMesh) -> Result<HeMesh, HeMeshError> {
+        soup.validate()?;
+
         let mut mesh = HeMesh::default();
 
         for i in 0..soup.n_patches() {
@@ -31,13 +51,79 @@ impl HeMesh {
             mesh.insert_face(vertices, patch);
         }
 
-        if let Err(error) = mesh.build_links() {
-            return Err(error);
+        mesh.build_links()?;
+
+        if soup.has_colors() {
+            let colors = (0..soup.n_vertices())
+                .filter_map(|i| soup.color(i))
+                .collect();
+            mesh.set_vertex_colors(colors)?;
+        }
+
+        Ok(mesh)
+    }
+
+    /// Construct a half edge mesh from arrays of vertices and faces. Faces
+    /// index into the vertices and may be assigned to patches by index, with
+    /// a patch named "patch{i}" created for each index up to the largest.
+    pub fn from_arrays(
+        vertices: &[Vector3],
+        faces: &[Vec<usize>],
+        patches: Option<&[usize]>,
+    ) -> Result<HeMesh, HeMeshError> {
+        if faces.iter().flatten().any(|&v| v >= vertices.len()) {
+            return Err(HeMeshError::IndexOutOfRange);
+        }
+
+        if patches.is_some_and(|patches| patches.len() != faces.len()) {
+            return Err(HeMeshError::AttributeLength);
+        }
+
+        let mut mesh = HeMesh::default();
+        let n_patches = patches.and_then(|p| p.iter().max()).map_or(0, |&p| p + 1);
+
+        for i in 0..n_patches {
+            mesh.insert_patch(&format!("patch{}", i));
+        }
+
+        for &origin in vertices.iter() {
+            mesh.insert_vertex(origin);
+        }
+
+        for (i, face) in faces.iter().enumerate() {
+            mesh.insert_face(face, patches.map(|p| p[i]));
         }
 
+        mesh.build_links()?;
+
         Ok(mesh)
     }
 
+    /// Convert into a polygon soup with the same vertices, faces and patches
+    /// in the same order. Vertex colors are carried over, but other scalar
+    /// attributes are not.
+    pub fn to_soup(&self) -> PolygonSoupMesh {
+        let mut soup = PolygonSoupMesh::new();
+
+        for patch in self.patches.iter() {
+            soup.insert_patch(patch.name());
+        }
+
+        for vertex in self.vertices.iter() {
+            soup.insert_vertex(vertex.origin);
+        }
+
+        for color in self.vertex_colors().unwrap_or_default() {
+            soup.insert_color(color);
+        }
+
+        for (face_id, face) in self.faces.iter().enumerate() {
+            soup.insert_face(&self.face_vertices(face_id), face.patch);
+        }
+
+        soup
+    }
+
     // Insert a vertex
     fn insert_vertex(&mut self, origin: Vector3) {
         let vertex = HeVertex {
@@ -55,7 +141,7 @@ impl HeMesh {
         let face_id = self.n_faces();
         let face = HeFace {
             half_edge: nh,
-            patch: patch,
+            patch,
         };
         self.faces.push(face);
 
@@ -118,7 +204,7 @@ impl HeMesh {
 
     /// Import a half edge mesh from an OBJ file
     pub fn import_obj(path: &str) -> std::io::Result<HeMesh> {
-        let soup = ObjReader::new(&path).read()?;
+        let soup = ObjReader::new(path).read()?;
         let result = HeMesh::new(&soup);
 
         match result {
@@ -127,26 +213,30 @@ impl HeMesh {
         }
     }
 
+    /// Import a half edge mesh from an OBJ file, skipping any invalid vertices
+    /// or faces. The messages for the skipped lines are returned alongside the
+    /// mesh.
+    pub fn import_obj_lenient(path: &str) -> std::io::Result<(HeMesh, Vec<String>)> {
+        let (soup, warnings) = ObjReader::new(path).read_lenient()?;
+        let mesh = HeMesh::new(&soup)?;
+        Ok((mesh, warnings))
+    }
+
     /// Export a half edge mesh to an OBJ file
     pub fn export_obj(&self, path: &str) -> std::io::Result<()> {
-        let vertices: Vec<Vector3> = self.vertices().iter().map(|v| v.origin).collect();
-
-        let faces: Vec<Vec<usize>> = (0..self.n_faces()).map(|f| self.face_vertices(f)).collect();
-
-        let face_groups: Vec<Option<usize>> = self.faces().iter().map(|f| f.patch).collect();
+        ObjWriter::from_mesh(self).write(path)
+    }
 
-        let groups: Vec<String> = self
-            .patches()
-            .iter()
-            .map(|p| p.name().to_string())
-            .collect();
+    /// Export the mesh vertices and the feature lines to an OBJ file. The
+    /// feature lines are written as line records indexing the mesh vertices.
+    pub fn export_feature_obj(&self, path: &str, threshold: f64) -> std::io::Result<()> {
+        ObjWriter::from_mesh_lines(self, self.feature_lines(threshold)).write(path)
+    }
 
-        let mut writer = ObjWriter::new();
-        writer.set_vertices(vertices);
-        writer.set_faces(faces);
-        writer.set_face_groups(face_groups);
-        writer.set_groups(groups);
-        writer.write(path)
+    /// Export the mesh vertices and the boundary loops to an OBJ file. The
+    /// boundary loops are written as line records indexing the mesh vertices.
+    pub fn export_boundary_obj(&self, path: &str) -> std::io::Result<()> {
+        ObjWriter::from_mesh_lines(self, self.boundary_lines()).write(path)
     }
 
     /// Get the number of vertices
@@ -174,6 +264,84 @@ impl HeMesh {
         HeVertexFaceIter::new(self, index).collect()
     }
 
+    /// Get the neighboring vertex indices to a vertex by index. Unlike
+    /// `vertex_neighbors`, this returns an error on an inconsistent mesh
+    /// rather than panicking.
+    pub fn try_vertex_neighbors(&self, index: usize) -> Result<Vec<usize>, HeMeshError> {
+        let mut iter = HeVertexOHalfEdgeIter::new(self, index);
+        let mut neighbors = vec![];
+
+        while let Some(curr) = iter.try_next()? {
+            let next = self.half_edges[curr].next;
+            neighbors.push(self.half_edges[next].origin);
+
+            let prev = self.half_edges[curr].prev;
+
+            if self.half_edges[prev].twin.is_none() {
+                neighbors.push(self.half_edges[prev].origin);
+            }
+        }
+
+        Ok(neighbors)
+    }
+
+    /// Get the faces using a vertex by index. Unlike `vertex_faces`, this
+    /// returns an error on an inconsistent mesh rather than panicking.
+    pub fn try_vertex_faces(&self, index: usize) -> Result<Vec<usize>, HeMeshError> {
+        let mut iter = HeVertexOHalfEdgeIter::new(self, index);
+        let mut faces = vec![];
+
+        while let Some(curr) = iter.try_next()? {
+            faces.push(self.half_edges[curr].face);
+        }
+
+        Ok(faces)
+    }
+
+    /// Check if the edge of a half edge can be collapsed without making the
+    /// mesh non-manifold. The neighbors shared by the endpoints must be
+    /// exactly the vertices opposite the edge (the link condition) and an
+    /// interior edge may not join two boundary vertices. The faces using
+    /// the edge must be triangles.
+    pub fn can_collapse_edge(&self, half_edge: usize) -> bool {
+        let current = self.half_edges[half_edge];
+        let a = current.origin;
+        let b = self.half_edges[current.next].origin;
+        let mut opposite = HashSet::new();
+
+        for h in std::iter::once(half_edge).chain(current.twin) {
+            let face = self.half_edges[h].face;
+
+            if self.face_vertices(face).len() != 3 {
+                return false;
+            }
+
+            let prev = self.half_edges[h].prev;
+            opposite.insert(self.half_edges[prev].origin);
+        }
+
+        let neighbors: HashSet<usize> = self.vertex_neighbors(a).into_iter().collect();
+        let shared: HashSet<usize> = self
+            .vertex_neighbors(b)
+            .into_iter()
+            .filter(|v| neighbors.contains(v))
+            .collect();
+
+        if shared != opposite {
+            return false;
+        }
+
+        current.twin.is_none() || !(self.is_boundary_vertex(a) && self.is_boundary_vertex(b))
+    }
+
+    // Check if a vertex is used by a boundary half edge
+    fn is_boundary_vertex(&self, index: usize) -> bool {
+        HeVertexOHalfEdgeIter::new(self, index).any(|h| {
+            let prev = self.half_edges[h].prev;
+            self.half_edges[h].twin.is_none() || self.half_edges[prev].twin.is_none()
+        })
+    }
+
     /// Get the number of faces
     pub fn n_faces(&self) -> usize {
         self.faces.len()
@@ -189,7 +357,7 @@ impl HeMesh {
         self.faces[index]
     }
 
-    /// Get the normal vector of a face
+    /// Get the unit normal vector of a face (zeros for a degenerate face)
     pub fn face_normal(&self, index: usize) -> Vector3 {
         let mut normal = Vector3::zeros();
         let vertices = self.face_vertices(index);
@@ -204,6 +372,50 @@ impl HeMesh {
         normal.unit()
     }
 
+    /// Get the unit normal vector of each vertex as the area-weighted
+    /// average of the normals of the faces using the vertex
+    pub fn vertex_normals(&self) -> Vec<Vector3> {
+        let mut normals = vec![Vector3::zeros(); self.n_vertices()];
+
+        for face_id in 0..self.n_faces() {
+            let normal = self.face_weighted_normal(face_id);
+
+            for vertex_id in HeFaceVertexIter::new(self, face_id) {
+                normals[vertex_id] += normal;
+            }
+        }
+
+        normals.iter().map(|n| n.unit()).collect()
+    }
+
+    /// Get the unit normal vector of a vertex by index as the area-weighted
+    /// average of the normals of the faces using the vertex. This matches
+    /// `vertex_normals` for a single vertex, including boundary vertices.
+    pub fn vertex_normal(&self, index: usize) -> Vector3 {
+        HeVertexFaceIter::new(self, index)
+            .fold(Vector3::zeros(), |normal, face_id| {
+                normal + self.face_weighted_normal(face_id)
+            })
+            .unit()
+    }
+
+    /// Get the normal of a face with a magnitude of twice its area
+    fn face_weighted_normal(&self, index: usize) -> Vector3 {
+        self.face_triangles(index)
+            .iter()
+            .fold(Vector3::zeros(), |normal, t| normal + t.normal())
+    }
+
+    /// Move each vertex along its vertex normal by the distance. Large
+    /// offsets may cause the surface to intersect itself.
+    pub fn offset(&mut self, distance: f64) {
+        let normals = self.vertex_normals();
+
+        for (vertex, normal) in self.vertices.iter_mut().zip(normals.iter()) {
+            vertex.origin += *normal * distance;
+        }
+    }
+
     /// Get the vertices used by a face by index
     pub fn face_vertices(&self, index: usize) -> Vec<usize> {
         HeFaceVertexIter::new(self, index).collect()
@@ -221,7 +433,7 @@ impl HeMesh {
 
     /// Flip a face by index. This reverses all half edges defining the boundary
     /// of the face to flip the orientation.
-    fn flip_face(&mut self, index: usize) {
+    pub fn flip_face(&mut self, index: usize) {
         let half_edge_ids = self.face_half_edges(index);
         let mut half_edges = Vec::<HeHalfEdge>::new();
 
@@ -230,7 +442,7 @@ impl HeMesh {
             let origin = self.half_edges[half_edge.next].origin;
 
             half_edges.push(HeHalfEdge {
-                origin: origin,
+                origin,
                 face: half_edge.face,
                 prev: half_edge.next,
                 next: half_edge.prev,
@@ -241,971 +453,5472 @@ impl HeMesh {
         for (i, &j) in half_edge_ids.iter().enumerate() {
             self.half_edges[j] = half_edges[i];
         }
-    }
 
-    /// Get the number of half edges
-    pub fn n_half_edges(&self) -> usize {
-        self.half_edges.len()
-    }
+        // Vertices referencing a flipped half edge must reference the half
+        // edge now originating at the vertex.
+        for &j in half_edge_ids.iter() {
+            let origin = self.half_edges[j].origin;
 
-    /// Get a borrowed reference to the half edges
-    pub fn half_edges(&self) -> &Vec<HeHalfEdge> {
-        &self.half_edges
+            if half_edge_ids.contains(&self.vertices[origin].half_edge) {
+                self.vertices[origin].half_edge = j;
+            }
+        }
     }
 
-    /// Get a the half edge by index
-    pub fn half_edge(&self, index: usize) -> HeHalfEdge {
-        self.half_edges[index]
+    /// Flip all faces assigned to a patch by name. This is a no-op if no
+    /// patch exists with the name.
+    pub fn flip_patch(&mut self, name: &str) {
+        if let Some(patch) = self.patches.iter().position(|p| p.name() == name) {
+            for face_id in 0..self.n_faces() {
+                if self.faces[face_id].patch == Some(patch) {
+                    self.flip_face(face_id);
+                }
+            }
+        }
     }
 
-    /// Get the number of patches
-    pub fn n_patches(&self) -> usize {
-        self.patches.len()
-    }
+    /// Flip all faces assigned to any of the patches by name in one pass.
+    /// Names without a matching patch are ignored.
+    pub fn flip_patches(&mut self, names: &[&str]) {
+        let patches: HashSet<usize> = self
+            .patches
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| names.contains(&p.name()))
+            .map(|(i, _)| i)
+            .collect();
 
-    /// Get a borrowed reference to the patches
-    pub fn patches(&self) -> &Vec<HePatch> {
-        &self.patches
+        for face_id in 0..self.n_faces() {
+            if self.faces[face_id]
+                .patch
+                .is_some_and(|patch| patches.contains(&patch))
+            {
+                self.flip_face(face_id);
+            }
+        }
     }
 
-    /// Get a patch by index
-    pub fn patch(&self, index: usize) -> HePatch {
-        self.patches[index].clone()
+    /// Translate the vertices used only by faces assigned to a patch by name.
+    /// Vertices shared with faces outside the patch are left in place. This
+    /// is a no-op if no patch exists with the name.
+    pub fn translate_patch(&mut self, name: &str, offset: Vector3) {
+        if let Some(patch) = self.patches.iter().position(|p| p.name() == name) {
+            let mut inside = HashSet::<usize>::new();
+            let mut outside = HashSet::<usize>::new();
+
+            for face_id in 0..self.n_faces() {
+                let vertices = HeFaceVertexIter::new(self, face_id);
+
+                if self.faces[face_id].patch == Some(patch) {
+                    inside.extend(vertices);
+                } else {
+                    outside.extend(vertices);
+                }
+            }
+
+            for vertex_id in inside.difference(&outside) {
+                self.vertices[*vertex_id].origin += offset;
+            }
+        }
     }
 
-    /// Check if the mesh is closed
-    pub fn is_closed(&self) -> bool {
-        self.half_edges.iter().find(|h| h.is_boundary()).is_none()
+    /// Remove faces by index and then any vertices no longer used by a face.
+    /// Patches and scalar attributes are preserved for the remaining faces
+    /// and vertices. Removing faces may leave a non-manifold vertex (e.g.
+    /// the shared vertex of a bowtie), which is kept as is and can be found
+    /// with `nonmanifold_vertices`.
+    pub fn remove_faces(&mut self, faces: &[usize]) -> Result<(), HeMeshError> {
+        let removed: HashSet<usize> = faces.iter().copied().collect();
+        let mut mesh = HeMesh {
+            vertices: self.vertices.clone(),
+            patches: self.patches.clone(),
+            vertex_scalars: self.vertex_scalars.clone(),
+            ..HeMesh::default()
+        };
+
+        for face_id in (0..self.n_faces()).filter(|f| !removed.contains(f)) {
+            mesh.insert_face(&self.face_vertices(face_id), self.faces[face_id].patch);
+        }
+
+        mesh.build_links()?;
+
+        for (name, values) in self.face_scalars.iter() {
+            let values = values
+                .iter()
+                .enumerate()
+                .filter(|(f, _)| !removed.contains(f))
+                .map(|(_, &value)| value)
+                .collect();
+
+            mesh.face_scalars.insert(name.clone(), values);
+        }
+
+        mesh.compact();
+        *self = mesh;
+
+        Ok(())
     }
 
-    /// Check if all contiguous faces are oriented consistently
-    pub fn is_consistent(&self) -> bool {
-        self.half_edges
+    /// Remove the faces assigned to the patches by name along with the
+    /// patches themselves. Unknown names are ignored.
+    pub fn remove_patch_names(&mut self, names: &[&str]) -> Result<(), HeMeshError> {
+        let removed: Vec<bool> = self
+            .patches
             .iter()
-            .filter(|h| !h.is_boundary())
-            .find(|h| self.half_edges[h.twin.unwrap()].origin == h.origin)
-            .is_none()
-    }
+            .map(|p| names.contains(&p.name()))
+            .collect();
 
-    /// Check if two faces are consistently oriented. If the two faces are
-    /// not neighbors, this returns false.
-    pub fn is_face_consistent(&self, i: usize, j: usize) -> bool {
-        let mut index = HashSet::new();
+        let faces: Vec<usize> = (0..self.n_faces())
+            .filter(|&f| self.faces[f].patch.is_some_and(|p| removed[p]))
+            .collect();
 
-        for k in self.face_half_edges(i) {
-            index.insert(k);
-        }
+        self.remove_faces(&faces)?;
 
-        for k in self.face_half_edges(j) {
-            let half_edge = self.half_edges[k];
+        let mut index = vec![None; self.n_patches()];
+        let mut patches = vec![];
 
-            if let Some(twin) = half_edge.twin {
-                if index.contains(&twin) {
-                    return self.half_edges[twin].origin != half_edge.origin;
-                }
+        for (i, patch) in self.patches.iter().enumerate() {
+            if !removed[i] {
+                index[i] = Some(patches.len());
+                patches.push(patch.clone());
             }
         }
 
-        false
-    }
+        for face in self.faces.iter_mut() {
+            face.patch = face.patch.and_then(|p| index[p]);
+        }
 
-    /// Check if the mesh is composed of strictly triangles
-    pub fn is_triangles(&self) -> bool {
-        (0..self.n_faces())
-            .find(|&f| self.face_vertices(f).len() != 3)
-            .is_none()
+        self.patches = patches;
+
+        Ok(())
     }
 
-    /// Get the axis-aligned bounding box
-    pub fn bounds(&self) -> Aabb {
-        let mut min = Vector3::ones() * f64::INFINITY;
-        let mut max = Vector3::ones() * f64::NEG_INFINITY;
+    /// Merge regions of adjacent faces in the same patch whose normals are
+    /// within the angle tolerance (in radians) of the region seed into single
+    /// polygonal faces. Regions whose boundary is not a single simple loop
+    /// are left as is. Face scalars are dropped. Returns the number of faces
+    /// removed.
+    pub fn merge_coplanar(&mut self, angle_tol: f64) -> Result<usize, HeMeshError> {
+        let mut region = vec![usize::MAX; self.n_faces()];
+        let mut regions: Vec<Vec<usize>> = vec![];
+
+        for seed in 0..self.n_faces() {
+            if region[seed] != usize::MAX {
+                continue;
+            }
 
-        for vertex in self.vertices.iter() {
-            for i in 0..3 {
-                if vertex.origin[i] < min[i] {
-                    min[i] = vertex.origin[i];
-                } else if vertex.origin[i] > max[i] {
-                    max[i] = vertex.origin[i]
+            let id = regions.len();
+            let mut faces = vec![seed];
+            let mut queue = vec![seed];
+            region[seed] = id;
+
+            while let Some(face) = queue.pop() {
+                for neighbor in self.face_neighbors(face) {
+                    if region[neighbor] == usize::MAX
+                        && self.faces[neighbor].patch == self.faces[seed].patch
+                        && self.dihedral_angle(seed, neighbor) <= angle_tol
+                    {
+                        region[neighbor] = id;
+                        faces.push(neighbor);
+                        queue.push(neighbor);
+                    }
                 }
             }
-        }
-
-        Aabb::from_bounds(min, max)
-    }
 
-    /// Get the contiguous faces as components
-    pub fn components(&self) -> Vec<Vec<usize>> {
-        let mut components = vec![];
-        let mut visited = vec![false; self.n_faces()];
+            regions.push(faces);
+        }
 
-        for next in 0..self.n_faces() {
-            if !visited[next] {
-                let mut component = vec![];
-                let mut queue = VecDeque::from([next]);
+        let mut mesh = HeMesh {
+            vertices: self.vertices.clone(),
+            patches: self.patches.clone(),
+            vertex_scalars: self.vertex_scalars.clone(),
+            ..HeMesh::default()
+        };
 
-                while let Some(current) = queue.pop_front() {
-                    if !visited[current] {
-                        visited[current] = true;
-                        component.push(current);
+        for (id, faces) in regions.iter().enumerate() {
+            let boundary = match faces.len() {
+                1 => None,
+                _ => self.region_boundary(faces, &region, id),
+            };
 
-                        for neighbor in HeFaceFaceIter::new(self, current) {
-                            if !visited[neighbor] {
-                                queue.push_back(neighbor);
-                            }
-                        }
+            match boundary {
+                Some(vertices) => mesh.insert_face(&vertices, self.faces[faces[0]].patch),
+                None => {
+                    for &face in faces.iter() {
+                        mesh.insert_face(&self.face_vertices(face), self.faces[face].patch);
                     }
                 }
-
-                components.push(component);
             }
         }
 
-        components
+        mesh.build_links()?;
+        mesh.compact();
+
+        let removed = self.n_faces() - mesh.n_faces();
+        *self = mesh;
+
+        Ok(removed)
     }
 
-    /// Get the indices of the vertices shared between two faces
-    pub fn shared_vertices(&self, i: usize, j: usize) -> Vec<usize> {
-        let mut index = HashSet::<usize>::new();
-        let mut vertices = vec![];
+    /// Get the vertices of the boundary loop of a region of faces, or None
+    /// if the boundary is not a single simple loop.
+    fn region_boundary(&self, faces: &[usize], region: &[usize], id: usize) -> Option<Vec<usize>> {
+        let mut outgoing: HashMap<usize, usize> = HashMap::new();
 
-        for vertex in self.face_vertices(i) {
-            index.insert(vertex);
+        for &face in faces.iter() {
+            for half_edge_id in self.face_half_edges(face) {
+                let half_edge = &self.half_edges[half_edge_id];
+                let inside = half_edge
+                    .twin
+                    .is_some_and(|twin| region[self.half_edges[twin].face] == id);
+
+                if !inside && outgoing.insert(half_edge.origin, half_edge_id).is_some() {
+                    return None;
+                }
+            }
         }
 
-        for vertex in self.face_vertices(j) {
-            if index.contains(&vertex) {
-                vertices.push(vertex);
+        let start = *outgoing.values().next()?;
+        let mut vertices = vec![];
+        let mut current = start;
+
+        loop {
+            vertices.push(self.half_edges[current].origin);
+            let next = self.half_edges[self.half_edges[current].next].origin;
+            current = *outgoing.get(&next)?;
+
+            if current == start || vertices.len() > outgoing.len() {
+                break;
             }
         }
 
-        vertices
+        (vertices.len() == outgoing.len()).then_some(vertices)
     }
 
-    /// Orient the mesh
-    pub fn orient(&mut self) {
-        let mut oriented = vec![false; self.n_faces()];
+    /// Remove the vertices not used by any face and renumber the remaining
+    /// vertices. Vertex scalar attributes are preserved.
+    pub fn compact(&mut self) {
+        let mut used = vec![false; self.n_vertices()];
 
-        for component in self.components() {
-            let mut queue = VecDeque::from([component[0]]);
+        for half_edge in self.half_edges.iter() {
+            used[half_edge.origin] = true;
+        }
 
-            while let Some(current) = queue.pop_front() {
-                if !oriented[current] {
-                    oriented[current] = true;
+        if used.iter().all(|&u| u) {
+            return;
+        }
 
-                    for neighbor in self.face_neighbors(current) {
-                        if !oriented[current] {
-                            queue.push_back(neighbor);
+        let mut index = vec![0; self.n_vertices()];
+        let mut vertices = vec![];
 
-                            if !self.is_face_consistent(current, neighbor) {
-                                self.flip_face(neighbor);
-                            }
-                        }
-                    }
-                }
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            if used[i] {
+                index[i] = vertices.len();
+                vertices.push(*vertex);
             }
         }
-    }
 
-    /// Zip any open edges. This may result in a non-manifold mesh.
-    pub fn zip_edges(&mut self) -> Result<(), HeMeshError> {
-        // TODO: implement
-        unimplemented!();
-    }
+        for half_edge in self.half_edges.iter_mut() {
+            half_edge.origin = index[half_edge.origin];
+        }
 
-    /// Get the half edge pairs whose incident faces form an angle greater
-    /// than the threshold (in radians)
-    pub fn feature_edges(&self, threshold: f64) -> Vec<(usize, usize)> {
-        let mut visited = vec![false; self.n_half_edges()];
-        let mut features = vec![];
+        for values in self.vertex_scalars.values_mut() {
+            let mut i = 0;
+            values.retain(|_| {
+                i += 1;
+                used[i - 1]
+            });
+        }
 
-        for (i, half_edge) in self.half_edges.iter().enumerate() {
-            if let Some(j) = half_edge.twin {
-                if !visited[i] && !visited[j] {
-                    visited[i] = true;
-                    visited[j] = true;
-                    let twin = self.half_edges[j];
+        self.vertices = vertices;
+    }
 
-                    let u = self.face_normal(half_edge.face);
-                    let v = self.face_normal(twin.face);
+    /// Get the triangles of a face by index. Quads are triangulated as a fan
+    /// about their first vertex and larger polygons by ear clipping, which
+    /// handles non-convex planar faces and avoids slivers.
+    pub fn face_triangles(&self, index: usize) -> Vec<Triangle> {
+        let points: Vec<Vector3> = HeFaceVertexIter::new(self, index)
+            .map(|v| self.vertices[v].origin)
+            .collect();
 
-                    if Vector3::angle(&u, &v) > threshold {
-                        features.push((i, j));
-                    }
-                }
-            }
-        }
+        triangulate_face(&points)
+            .into_iter()
+            .map(|[a, b, c]| Triangle::new(points[a], points[b], points[c]))
+            .collect()
+    }
 
-        features
+    /// Iterate over the faces as triangles. Polygonal faces are triangulated
+    /// as in `face_triangles`.
+    pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
+        (0..self.n_faces()).flat_map(move |face_id| self.face_triangles(face_id))
     }
 
-    /// Get the principal axes defining the dominant orthogonal coordinate
-    /// system local to the mesh vertices.
-    pub fn principal_axes(&self) -> Vec<Vector3> {
-        // TODO: implement
-        unimplemented!();
+    /// Iterate over the faces as triangles along with the index and patch of
+    /// the face each belongs to. Polygonal faces are triangulated as in
+    /// `face_triangles`.
+    pub fn faces_as_triangles(
+        &self,
+    ) -> impl Iterator<Item = (usize, Triangle, Option<usize>)> + '_ {
+        (0..self.n_faces()).flat_map(move |face_id| {
+            let patch = self.faces[face_id].patch;
+
+            self.face_triangles(face_id)
+                .into_iter()
+                .map(move |triangle| (face_id, triangle, patch))
+        })
     }
 
-    /// Merge naively with another mesh. The receiver mesh is updated in place
-    /// with the elements from the target mesh.
-    pub fn merge(&mut self, other: &HeMesh) {
-        let mut index_patches = HashMap::<String, usize>::new();
+    /// Get flat position and index buffers for rendering. Positions hold the
+    /// x, y and z of each vertex in order and indices hold three vertices per
+    /// triangle. Polygonal faces are triangulated as in `face_triangles`.
+    pub fn triangle_soup_arrays(&self) -> (Vec<f32>, Vec<u32>) {
+        let positions = self
+            .vertices
+            .iter()
+            .flat_map(|v| {
+                [
+                    v.origin.x() as f32,
+                    v.origin.y() as f32,
+                    v.origin.z() as f32,
+                ]
+            })
+            .collect();
 
-        for (i, patch) in self.patches.iter().enumerate() {
-            index_patches.insert(patch.name.to_string(), i);
+        let indices = (0..self.n_faces())
+            .flat_map(|face_id| {
+                let vertices = self.face_vertices(face_id);
+                let points: Vec<Vector3> =
+                    vertices.iter().map(|&v| self.vertices[v].origin).collect();
+
+                triangulate_face(&points)
+                    .into_iter()
+                    .flat_map(move |triangle| triangle.map(|i| vertices[i] as u32))
+            })
+            .collect();
+
+        (positions, indices)
+    }
+
+    /// Get the patch of each triangle in the order of `triangle_soup_arrays`.
+    /// Triangles of faces without a patch are assigned `u32::MAX`.
+    pub fn triangle_patch_ids(&self) -> Vec<u32> {
+        self.faces_as_triangles()
+            .map(|(_, _, patch)| patch.map_or(u32::MAX, |p| p as u32))
+            .collect()
+    }
+
+    /// Get a hash of the faces, patches and vertex positions rounded to the
+    /// nearest point of a grid with the given spacing. Meshes equal up to
+    /// the spacing hash identically (barring positions halfway between grid
+    /// points). The hash is FNV-1a and does not depend on the platform or
+    /// compiler version, so it may be stored as a cache key. The spacing
+    /// must be positive.
+    pub fn content_hash(&self, quantum: f64) -> Result<u64, HeMeshError> {
+        if quantum <= 0. || quantum.is_nan() {
+            return Err(HeMeshError::InvalidParameter);
         }
 
-        for patch in other.patches.iter() {
-            if !index_patches.contains_key(patch.name()) {
-                index_patches.insert(patch.name.clone(), self.patches.len());
-                self.patches.push(patch.clone());
+        let half = Vector3::new(quantum, quantum, quantum) * 0.5;
+        let mut hash = FNV_OFFSET;
+        let mut write = |value: u64| {
+            for byte in value.to_le_bytes() {
+                hash = (hash ^ byte as u64).wrapping_mul(FNV_PRIME);
             }
-        }
+        };
 
-        let offset_v = self.n_vertices();
-        let offset_f = self.n_faces();
-        let offset_h = self.n_half_edges();
+        write(self.n_vertices() as u64);
 
-        for vertex in other.vertices.iter() {
-            let mut vertex = *vertex;
-            vertex.half_edge += offset_h;
-            self.vertices.push(vertex);
+        for vertex in self.vertices.iter() {
+            let (i, j, k) = (vertex.origin + half).quantize(quantum);
+            write(i as u64);
+            write(j as u64);
+            write(k as u64);
         }
 
-        for face in other.faces.iter() {
-            let mut face = *face;
-            face.half_edge += offset_h;
-
-            if let Some(patch) = face.patch {
-                let name = other.patches[patch].name();
-                face.patch = Some(index_patches[name]);
-            }
+        write(self.n_faces() as u64);
 
-            self.faces.push(face);
+        for (face_id, face) in self.faces.iter().enumerate() {
+            let vertices = self.face_vertices(face_id);
+            write(vertices.len() as u64);
+            vertices.into_iter().for_each(|v| write(v as u64));
+            write(face.patch.map_or(u64::MAX, |p| p as u64));
         }
 
-        for half_edge in other.half_edges.iter() {
-            let mut half_edge = *half_edge;
-            half_edge.origin += offset_v;
-            half_edge.face += offset_f;
-            half_edge.prev += offset_h;
-            half_edge.next += offset_h;
+        Ok(hash)
+    }
 
-            if let Some(twin) = half_edge.twin {
-                half_edge.twin = Some(twin + offset_h);
-            }
+    /// Get the area of each face
+    pub fn face_areas(&self) -> Vec<f64> {
+        (0..self.n_faces())
+            .map(|f| self.face_triangles(f).iter().map(|t| t.area()).sum())
+            .collect()
+    }
 
-            self.half_edges.push(half_edge);
-        }
+    /// Get the number of half edges
+    pub fn n_half_edges(&self) -> usize {
+        self.half_edges.len()
     }
 
-    /// Extract the subset of faces into a new mesh. This is not efficient and should
-    /// only be used when explicitly necessary.
-    pub fn extract_faces(&self, faces: &[usize]) -> HeMesh {
-        let mut mesh = HeMesh::default();
-        let mut index_vertices = HashMap::<usize, usize>::new();
-        let mut index_patches = HashMap::<usize, usize>::new();
+    /// Get a borrowed reference to the half edges
+    pub fn half_edges(&self) -> &Vec<HeHalfEdge> {
+        &self.half_edges
+    }
 
-        for &face_id in faces.iter() {
-            let mut vertices = self.face_vertices(face_id);
-            let mut patch = None;
+    /// Get a the half edge by index
+    pub fn half_edge(&self, index: usize) -> HeHalfEdge {
+        self.half_edges[index]
+    }
 
-            for vertex_id in vertices.iter_mut() {
-                if !index_vertices.contains_key(vertex_id) {
-                    let origin = self.vertices[*vertex_id].origin;
-                    mesh.insert_vertex(origin);
-                    index_vertices.insert(*vertex_id, mesh.n_vertices() - 1);
-                }
+    /// Get the unique undirected edges as pairs of vertex indices ordered
+    /// from min to max
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        self.half_edges
+            .iter()
+            .enumerate()
+            .filter(|(i, h)| h.twin.is_none_or(|j| *i < j))
+            .map(|(_, h)| {
+                let a = h.origin;
+                let b = self.half_edges[h.next].origin;
+                (a.min(b), a.max(b))
+            })
+            .collect()
+    }
 
-                *vertex_id = index_vertices[vertex_id];
-            }
+    /// Get the number of unique undirected edges
+    pub fn n_edges(&self) -> usize {
+        self.half_edges
+            .iter()
+            .enumerate()
+            .filter(|(i, h)| h.twin.is_none_or(|j| *i < j))
+            .count()
+    }
 
-            if let Some(patch_id) = self.faces[face_id].patch {
-                if !index_patches.contains_key(&patch_id) {
-                    let name = self.patches[patch_id].name();
-                    mesh.insert_patch(name);
-                    index_patches.insert(patch_id, mesh.n_patches() - 1);
-                }
+    /// Get the length of each unique undirected edge in the order of `edges`
+    pub fn edge_lengths(&self) -> Vec<f64> {
+        self.edges()
+            .into_iter()
+            .map(|(a, b)| Line::new(self.vertices[a].origin, self.vertices[b].origin).length())
+            .collect()
+    }
 
-                patch = Some(index_patches[&patch_id]);
-            }
+    /// Get the min, max, mean and (population) standard deviation of the edge
+    /// lengths. These are all zero for a mesh without edges.
+    pub fn edge_length_stats(&self) -> (f64, f64, f64, f64) {
+        let lengths = self.edge_lengths();
 
-            mesh.insert_face(&vertices, patch);
+        if lengths.is_empty() {
+            return (0., 0., 0., 0.);
         }
 
-        mesh.build_links().unwrap();
+        let n = lengths.len() as f64;
+        let min = lengths.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = lengths.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mean = lengths.iter().sum::<f64>() / n;
+        let variance = lengths.iter().map(|l| (l - mean).powi(2)).sum::<f64>() / n;
 
-        mesh
+        (min, max, mean, variance.sqrt())
     }
 
-    /// Extract the subset of patches by index into a new mesh
-    pub fn extract_patches(&self, patches: &[usize]) -> HeMesh {
-        let mut index = HashSet::<usize>::new();
-        let mut faces = Vec::<usize>::new();
+    /// Get the number of patches
+    pub fn n_patches(&self) -> usize {
+        self.patches.len()
+    }
 
-        for patch in patches.iter() {
-            index.insert(*patch);
-        }
+    /// Get a borrowed reference to the patches
+    pub fn patches(&self) -> &Vec<HePatch> {
+        &self.patches
+    }
+
+    /// Get a patch by index
+    pub fn patch(&self, index: usize) -> HePatch {
+        self.patches[index].clone()
+    }
 
-        for (i, face) in self.faces.iter().enumerate() {
+    /// Get the total surface area of each patch
+    pub fn patch_areas(&self) -> Vec<f64> {
+        let mut areas = vec![0.; self.n_patches()];
+
+        for (face, area) in self.faces.iter().zip(self.face_areas()) {
             if let Some(patch) = face.patch {
-                if index.contains(&patch) {
-                    faces.push(i);
-                }
+                areas[patch] += area;
             }
         }
 
-        self.extract_faces(&faces)
+        areas
     }
 
-    /// Extract the subset of patches by name into a new mesh
-    pub fn extract_patch_names(&self, names: &[&str]) -> HeMesh {
-        let mut index = HashSet::<&str>::new();
-        let mut patches = Vec::<usize>::new();
+    /// Get the area-weighted centroid of the faces assigned to a patch by
+    /// index. The patch must have faces with a nonzero area.
+    pub fn patch_centroid(&self, patch: usize) -> Vector3 {
+        let mut centroid = Vector3::zeros();
+        let mut area = 0.;
 
-        for name in names.iter() {
-            index.insert(name);
+        for triangle in self.patch_faces(patch).flat_map(|f| self.face_triangles(f)) {
+            centroid += triangle.center() * triangle.area();
+            area += triangle.area();
         }
 
-        for (i, patch) in self.patches.iter().enumerate() {
-            if index.contains(patch.name()) {
-                patches.push(i);
-            }
+        centroid / area
+    }
+
+    /// Get the bounds of the faces assigned to a patch by index. The patch
+    /// must have faces.
+    pub fn patch_bounds(&self, patch: usize) -> Aabb {
+        let mut min = Vector3::ones() * f64::INFINITY;
+        let mut max = Vector3::ones() * f64::NEG_INFINITY;
+
+        for vertex in self
+            .patch_faces(patch)
+            .flat_map(|f| HeFaceVertexIter::new(self, f))
+        {
+            min = min.min(&self.vertices[vertex].origin);
+            max = max.max(&self.vertices[vertex].origin);
         }
 
-        self.extract_patches(&patches)
+        Aabb::from_bounds(min, max)
     }
-}
 
-#[derive(Debug, Copy, Clone)]
-pub struct HeVertex {
-    origin: Vector3,
-    half_edge: usize,
-}
+    // Get the faces assigned to a patch by index
+    fn patch_faces(&self, patch: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.n_faces()).filter(move |&f| self.faces[f].patch == Some(patch))
+    }
 
-impl HeVertex {
-    /// Get the origin
-    pub fn origin(&self) -> Vector3 {
-        self.origin
+    /// Get the name, number of faces, and total surface area of each patch.
+    /// Faces not assigned to a patch are summarized under a trailing
+    /// "unassigned" entry if any exist.
+    pub fn patch_summary(&self) -> Vec<(String, usize, f64)> {
+        let mut summary: Vec<(String, usize, f64)> = self
+            .patches
+            .iter()
+            .map(|p| (p.name().to_string(), 0, 0.))
+            .collect();
+
+        let mut unassigned = ("unassigned".to_string(), 0, 0.);
+
+        for (face, area) in self.faces.iter().zip(self.face_areas()) {
+            let entry = match face.patch {
+                Some(patch) => &mut summary[patch],
+                None => &mut unassigned,
+            };
+
+            entry.1 += 1;
+            entry.2 += area;
+        }
+
+        if unassigned.1 > 0 {
+            summary.push(unassigned);
+        }
+
+        summary
     }
 
-    /// Get the half edge originating at the vertex
-    pub fn half_edge(&self) -> usize {
-        self.half_edge
+    /// Rename a patch by index
+    pub fn rename_patch(&mut self, index: usize, name: &str) {
+        self.patches[index].name = name.to_string();
     }
-}
 
-#[derive(Debug, Copy, Clone)]
-pub struct HeFace {
-    half_edge: usize,
-    patch: Option<usize>,
-}
+    /// Merge patches by index into a single patch with a new name. The merged
+    /// patch takes the place of the lowest index, the other patches are
+    /// removed and the faces are reassigned to the compacted patch list.
+    pub fn merge_patches(&mut self, indices: &[usize], new_name: &str) {
+        let Some(&target) = indices.iter().min() else {
+            return;
+        };
 
-impl HeFace {
-    /// Get the starting half edge handle
-    pub fn half_edge(&self) -> usize {
-        self.half_edge
-    }
+        let mut index = vec![None; self.n_patches()];
+        let mut patches = vec![];
 
-    /// Get the patch handle
-    pub fn patch(&self) -> Option<usize> {
-        self.patch
+        for (i, patch) in self.patches.iter().enumerate() {
+            if i == target {
+                index[i] = Some(patches.len());
+                patches.push(HePatch {
+                    name: new_name.to_string(),
+                });
+            } else if !indices.contains(&i) {
+                index[i] = Some(patches.len());
+                patches.push(patch.clone());
+            }
+        }
+
+        for &i in indices.iter() {
+            index[i] = index[target];
+        }
+
+        for face in self.faces.iter_mut() {
+            face.patch = face.patch.and_then(|p| index[p]);
+        }
+
+        self.patches = patches;
     }
-}
 
-#[derive(Debug, Copy, Clone)]
-pub struct HeHalfEdge {
-    origin: usize,
-    face: usize,
-    prev: usize,
-    next: usize,
-    twin: Option<usize>,
-}
+    /// Assign each connected component to its own patch, replacing any
+    /// existing patches. The patches are named by the component index.
+    pub fn patches_from_components(&mut self) {
+        self.patches.clear();
 
-impl HeHalfEdge {
-    /// Get the origin vertex handle
-    pub fn origin(&self) -> usize {
-        self.origin
+        for (i, component) in self.components().into_iter().enumerate() {
+            self.insert_patch(&format!("patch{}", i));
+
+            for face in component {
+                self.faces[face].patch = Some(i);
+            }
+        }
     }
 
-    /// Get the incident face handle
-    pub fn face(&self) -> usize {
-        self.face
+    /// Remove all patches, leaving every face unassigned
+    pub fn clear_patches(&mut self) {
+        self.patches.clear();
+
+        for face in self.faces.iter_mut() {
+            face.patch = None;
+        }
     }
 
-    /// Get the previous half edge handle
-    pub fn prev(&self) -> usize {
-        self.prev
+    /// Set a named scalar attribute on the vertices. This returns an error if
+    /// the number of values does not match the number of vertices.
+    pub fn set_vertex_scalar(&mut self, name: &str, values: Vec<f64>) -> Result<(), HeMeshError> {
+        if values.len() != self.n_vertices() {
+            return Err(HeMeshError::AttributeLength);
+        }
+
+        self.vertex_scalars.insert(name.to_string(), values);
+        Ok(())
     }
 
-    /// Get the next half edge handle
-    pub fn next(&self) -> usize {
-        self.next
+    /// Get a named scalar attribute on the vertices (if it exists)
+    pub fn vertex_scalar(&self, name: &str) -> Option<&[f64]> {
+        self.vertex_scalars.get(name).map(|v| v.as_slice())
     }
 
-    /// Get the twin half edge handle (if it exists)
-    pub fn twin(&self) -> Option<usize> {
-        self.twin
+    /// Set the RGB color of each vertex. The colors are stored as the vertex
+    /// scalars "red", "green" and "blue", so they are carried over like any
+    /// other scalar attribute. This returns an error if the number of colors
+    /// does not match the number of vertices.
+    pub fn set_vertex_colors(&mut self, colors: Vec<Vector3>) -> Result<(), HeMeshError> {
+        if colors.len() != self.n_vertices() {
+            return Err(HeMeshError::AttributeLength);
+        }
+
+        for (i, name) in COLOR_SCALARS.iter().enumerate() {
+            let values = colors.iter().map(|c| c[i]).collect();
+            self.vertex_scalars.insert(name.to_string(), values);
+        }
+
+        Ok(())
     }
 
-    /// Check if the half edge is on a boundary
-    pub fn is_boundary(&self) -> bool {
-        self.twin.is_none()
+    /// Get the RGB color of each vertex (if set)
+    pub fn vertex_colors(&self) -> Option<Vec<Vector3>> {
+        let [r, g, b] = COLOR_SCALARS.map(|name| self.vertex_scalar(name));
+        let (r, g, b) = (r?, g?, b?);
+
+        Some(
+            (0..self.n_vertices())
+                .map(|i| Vector3::new(r[i], g[i], b[i]))
+                .collect(),
+        )
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct HePatch {
-    name: String,
-}
+    /// Set a named scalar attribute on the faces. This returns an error if
+    /// the number of values does not match the number of faces.
+    pub fn set_face_scalar(&mut self, name: &str, values: Vec<f64>) -> Result<(), HeMeshError> {
+        if values.len() != self.n_faces() {
+            return Err(HeMeshError::AttributeLength);
+        }
 
-impl HePatch {
-    /// Get a borrowed reference to the name
-    pub fn name(&self) -> &str {
-        &self.name
+        self.face_scalars.insert(name.to_string(), values);
+        Ok(())
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct HeVertexOHalfEdgeIter<'a> {
-    mesh: &'a HeMesh,
-    curr: usize,
-    init: usize,
-    count: usize,
-}
+    /// Get a named scalar attribute on the faces (if it exists)
+    pub fn face_scalar(&self, name: &str) -> Option<&[f64]> {
+        self.face_scalars.get(name).map(|v| v.as_slice())
+    }
 
-impl<'a> HeVertexOHalfEdgeIter<'a> {
-    pub fn new(mesh: &'a HeMesh, vertex: usize) -> HeVertexOHalfEdgeIter<'a> {
-        HeVertexOHalfEdgeIter {
-            mesh: mesh,
-            curr: mesh.vertices[vertex].half_edge,
-            init: mesh.vertices[vertex].half_edge,
-            count: 0,
-        }
+    /// Get the names of the scalar attributes on the vertices in sorted order
+    pub fn vertex_scalar_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.vertex_scalars.keys().map(|k| k.as_str()).collect();
+        names.sort();
+        names
     }
-}
 
-impl<'a> Iterator for HeVertexOHalfEdgeIter<'a> {
-    type Item = usize;
+    /// Get the names of the scalar attributes on the faces in sorted order
+    pub fn face_scalar_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.face_scalars.keys().map(|k| k.as_str()).collect();
+        names.sort();
+        names
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.count != 0 && self.curr == self.init {
-            return None;
+    // Carry the scalar attributes of a source mesh over to this mesh after it
+    // was rebuilt from the source. Each vertex interpolates between two source
+    // vertices given as (a, b, t) and each face copies its source face. Faces
+    // without a source are filled with NaN as in `merge`.
+    pub(crate) fn carry_scalars(
+        &mut self,
+        source: &HeMesh,
+        vertices: &[(usize, usize, f64)],
+        faces: &[Option<usize>],
+    ) {
+        for (name, values) in source.vertex_scalars.iter() {
+            let carried = vertices
+                .iter()
+                .map(|&(a, b, t)| values[a] + (values[b] - values[a]) * t)
+                .collect();
+
+            self.vertex_scalars.insert(name.clone(), carried);
         }
 
-        let curr = self.curr;
-        let prev = self.mesh.half_edges[curr].prev;
-
-        if let Some(twin) = self.mesh.half_edges[prev].twin {
-            if self.mesh.half_edges[twin].origin != self.mesh.half_edges[self.init].origin {
-                panic!("mesh must be oriented");
-            }
+        for (name, values) in source.face_scalars.iter() {
+            let carried = faces
+                .iter()
+                .map(|f| f.map_or(f64::NAN, |f| values[f]))
+                .collect();
 
-            self.curr = twin;
-            self.count += 1;
-            return Some(curr);
+            self.face_scalars.insert(name.clone(), carried);
         }
-
-        panic!("mesh must be closed");
     }
-}
+
+    /// Check if the mesh is closed
+    pub fn is_closed(&self) -> bool {
+        self.half_edges.iter().find(|h| h.is_boundary()).is_none()
+    }
+
+    /// Check if the mesh is closed, consistently oriented, and manifold at
+    /// every vertex. Every edge then has exactly two faces traversing it in
+    /// opposite directions and the faces about each vertex form a single fan.
+    pub fn is_watertight(&self) -> bool {
+        self.is_closed() && self.is_consistent() && self.nonmanifold_vertices().is_empty()
+    }
+
+    /// Get the vertices whose faces do not form a single fan connected by
+    /// the edges about the vertex (e.g. the shared vertex of a bowtie)
+    pub fn nonmanifold_vertices(&self) -> Vec<usize> {
+        self.outgoing_half_edges()
+            .iter()
+            .enumerate()
+            .filter(|(_, half_edges)| self.vertex_fans(half_edges).len() > 1)
+            .map(|(vertex_id, _)| vertex_id)
+            .collect()
+    }
+
+    /// Split each non-manifold vertex into one vertex per fan of faces so
+    /// the faces about every vertex form a single fan. This may introduce
+    /// new boundaries. Vertex scalar attributes are copied to the new
+    /// vertices.
+    pub fn split_nonmanifold_vertices(&mut self) -> Result<(), HeMeshError> {
+        let outgoing = self.outgoing_half_edges();
+
+        for (vertex_id, half_edges) in outgoing.iter().enumerate() {
+            for fan in self.vertex_fans(half_edges).iter().skip(1) {
+                let origin = self.vertices[vertex_id].origin;
+                self.insert_vertex(origin);
+                let new_id = self.n_vertices() - 1;
+
+                for &h in fan.iter() {
+                    self.half_edges[h].origin = new_id;
+                }
+
+                for values in self.vertex_scalars.values_mut() {
+                    values.push(values[vertex_id]);
+                }
+            }
+        }
+
+        for half_edge in self.half_edges.iter_mut() {
+            half_edge.twin = None;
+        }
+
+        self.build_links()
+    }
+
+    // Get the outgoing half edges of each vertex
+    fn outgoing_half_edges(&self) -> Vec<Vec<usize>> {
+        let mut outgoing = vec![vec![]; self.n_vertices()];
+
+        for (i, half_edge) in self.half_edges.iter().enumerate() {
+            outgoing[half_edge.origin].push(i);
+        }
+
+        outgoing
+    }
+
+    // Group the outgoing half edges of a vertex by the fans of their faces,
+    // which are joined by the edges leaving and entering the vertex
+    fn vertex_fans(&self, outgoing: &[usize]) -> Vec<Vec<usize>> {
+        let mut parent: HashMap<usize, usize> = outgoing
+            .iter()
+            .map(|&h| (self.half_edges[h].face, self.half_edges[h].face))
+            .collect();
+
+        for &h in outgoing.iter() {
+            let half_edge = self.half_edges[h];
+            let prev = self.half_edges[half_edge.prev];
+
+            for twin in [half_edge.twin, prev.twin].into_iter().flatten() {
+                let a = find_root(&mut parent, half_edge.face);
+                let b = find_root(&mut parent, self.half_edges[twin].face);
+                parent.insert(a, b);
+            }
+        }
+
+        let mut fans = Vec::<Vec<usize>>::new();
+        let mut index = HashMap::<usize, usize>::new();
+
+        for &h in outgoing.iter() {
+            let root = find_root(&mut parent, self.half_edges[h].face);
+            let fan = *index.entry(root).or_insert_with(|| {
+                fans.push(vec![]);
+                fans.len() - 1
+            });
+
+            fans[fan].push(h);
+        }
+
+        fans
+    }
+
+    /// Check if all contiguous faces are oriented consistently
+    pub fn is_consistent(&self) -> bool {
+        self.half_edges
+            .iter()
+            .filter(|h| !h.is_boundary())
+            .find(|h| self.half_edges[h.twin.unwrap()].origin == h.origin)
+            .is_none()
+    }
+
+    /// Get the faces sharing an edge with a neighbor whose half edge has the
+    /// same (rather than opposite) direction
+    pub fn inconsistent_faces(&self) -> Vec<usize> {
+        let mut faces = vec![];
+
+        for h in self.half_edges.iter().filter(|h| !h.is_boundary()) {
+            if self.half_edges[h.twin.unwrap()].origin == h.origin {
+                faces.push(h.face);
+            }
+        }
+
+        faces.sort();
+        faces.dedup();
+        faces
+    }
+
+    /// Check if two faces are consistently oriented. If the two faces are
+    /// not neighbors, this returns false.
+    pub fn is_face_consistent(&self, i: usize, j: usize) -> bool {
+        let mut index = HashSet::new();
+
+        for k in self.face_half_edges(i) {
+            index.insert(k);
+        }
+
+        for k in self.face_half_edges(j) {
+            let half_edge = self.half_edges[k];
+
+            if let Some(twin) = half_edge.twin {
+                if index.contains(&twin) {
+                    return self.half_edges[twin].origin != half_edge.origin;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Check if the mesh is composed of strictly triangles
+    pub fn is_triangles(&self) -> bool {
+        (0..self.n_faces())
+            .find(|&f| self.face_vertices(f).len() != 3)
+            .is_none()
+    }
+
+    /// Get the shape quality of each triangle as the ratio of the inscribed
+    /// to circumscribed radius, normalized to one for an equilateral triangle
+    /// and approaching zero for slivers. The mesh must be composed of
+    /// triangles.
+    pub fn triangle_qualities(&self) -> Result<Vec<f64>, HeMeshError> {
+        if !self.is_triangles() {
+            return Err(HeMeshError::NonTriangular);
+        }
+
+        let qualities = self.triangles().map(|t| t.quality()).collect();
+
+        Ok(qualities)
+    }
+
+    /// Get the minimum interior angle (in radians) of a face by index
+    pub fn min_angle(&self, index: usize) -> f64 {
+        let vertices = self.face_vertices(index);
+        let n = vertices.len();
+
+        (0..n)
+            .map(|i| {
+                let p = self.vertices[vertices[i]].origin;
+                let prev = self.vertices[vertices[(i + n - 1) % n]].origin;
+                let next = self.vertices[vertices[(i + 1) % n]].origin;
+                Vector3::angle(&(prev - p), &(next - p))
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Get the sum of the interior angles (in radians) of the faces at each
+    /// vertex
+    pub fn vertex_angle_sums(&self) -> Vec<f64> {
+        let mut sums = vec![0.; self.n_vertices()];
+
+        for face_id in 0..self.n_faces() {
+            let vertices = self.face_vertices(face_id);
+            let n = vertices.len();
+
+            for i in 0..n {
+                let p = self.vertices[vertices[i]].origin;
+                let prev = self.vertices[vertices[(i + n - 1) % n]].origin;
+                let next = self.vertices[vertices[(i + 1) % n]].origin;
+                sums[vertices[i]] += Vector3::angle(&(prev - p), &(next - p));
+            }
+        }
+
+        sums
+    }
+
+    /// Get the total angle defect, the sum over the vertices of 2π less the
+    /// angle sum at the vertex. By the Gauss-Bonnet theorem this is 2π times
+    /// the Euler characteristic for a closed mesh (e.g. 4π for a sphere).
+    pub fn total_angle_defect(&self) -> f64 {
+        self.vertex_angle_sums()
+            .iter()
+            .map(|sum| 2. * std::f64::consts::PI - sum)
+            .sum()
+    }
+
+    /// Get the axis-aligned bounding box
+    pub fn bounds(&self) -> Aabb {
+        let mut min = Vector3::ones() * f64::INFINITY;
+        let mut max = Vector3::ones() * f64::NEG_INFINITY;
+
+        for vertex in self.vertices.iter() {
+            for i in 0..3 {
+                if vertex.origin[i] < min[i] {
+                    min[i] = vertex.origin[i];
+                }
+
+                if vertex.origin[i] > max[i] {
+                    max[i] = vertex.origin[i];
+                }
+            }
+        }
+
+        Aabb::from_bounds(min, max)
+    }
+
+    /// Apply an affine transformation to every vertex. A mirroring
+    /// transformation (negative determinant) also flips every face to
+    /// preserve the outward orientation.
+    pub fn transform(&mut self, m: &Matrix4) {
+        for vertex in self.vertices.iter_mut() {
+            vertex.origin = vertex.origin.transform(m);
+        }
+
+        if m.fixed_view::<3, 3>(0, 0).determinant() < 0. {
+            for face in 0..self.faces.len() {
+                self.flip_face(face);
+            }
+        }
+    }
+
+    /// Get a conservative bounding box of the mesh after an affine
+    /// transformation without transforming the vertices
+    pub fn transformed_bounds(&self, m: &Matrix4) -> Aabb {
+        self.bounds().transformed(m)
+    }
+
+    /// Normalize the mesh to fit within the unit cube centered at the origin.
+    /// The bounds center is translated to the origin and the mesh is uniformly
+    /// scaled so the longest bounds dimension is one. The applied offset and
+    /// scale are returned such that `p' = (p + offset) * scale`.
+    pub fn normalize(&mut self) -> (Vector3, f64) {
+        let bounds = self.bounds();
+        let offset = -bounds.center();
+        let extent = 2. * bounds.halfsize()[bounds.halfsize().max_index()];
+        let scale = if extent > EPSILON { 1. / extent } else { 1. };
+
+        for vertex in self.vertices.iter_mut() {
+            vertex.origin = (vertex.origin + offset) * scale;
+        }
+
+        (offset, scale)
+    }
+
+    /// Get the signed volume enclosed by the mesh. This is only meaningful
+    /// for a closed and consistently oriented mesh and is positive when the
+    /// faces are oriented outward.
+    pub fn volume(&self) -> f64 {
+        self.triangles()
+            .map(|t| Vector3::dot(&t[0], &Vector3::cross(&t[1], &t[2])) / 6.)
+            .sum()
+    }
+
+    /// Get the generalized winding number of a point with respect to the mesh.
+    /// For a closed mesh oriented outward, this is 1 inside and 0 outside.
+    pub fn winding_number(&self, point: &Vector3) -> f64 {
+        let mut omega = 0.;
+
+        for triangle in self.triangles() {
+            let a = triangle[0] - *point;
+            let b = triangle[1] - *point;
+            let c = triangle[2] - *point;
+
+            let la = a.mag();
+            let lb = b.mag();
+            let lc = c.mag();
+
+            let numerator = Vector3::dot(&a, &Vector3::cross(&b, &c));
+            let denominator = la * lb * lc
+                + Vector3::dot(&a, &b) * lc
+                + Vector3::dot(&b, &c) * la
+                + Vector3::dot(&c, &a) * lb;
+
+            omega += 2. * numerator.atan2(denominator);
+        }
+
+        omega / (4. * std::f64::consts::PI)
+    }
+
+    /// Check if a point is contained within the mesh using the winding number.
+    /// This assumes a closed mesh oriented outward.
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        self.winding_number(point) > 0.5
+    }
+
+    /// Check if the mesh is convex. Every vertex must lie on or behind the
+    /// plane of each face, which assumes the faces are oriented outward. An
+    /// open mesh is never convex.
+    pub fn is_convex(&self) -> bool {
+        if !self.is_closed() {
+            return false;
+        }
+
+        let bounds = self.bounds();
+        let tolerance = EPSILON * (bounds.max() - bounds.min()).mag().max(1.);
+
+        (0..self.n_faces()).all(|face| {
+            let normal = self.face_normal(face);
+            let half_edge = self.half_edges[self.faces[face].half_edge];
+            let origin = self.vertices[half_edge.origin].origin;
+
+            self.vertices
+                .iter()
+                .all(|v| Vector3::dot(&normal, &(v.origin - origin)) <= tolerance)
+        })
+    }
+
+    /// Subdivide each triangle into four at its edge midpoints for the number
+    /// of iterations. Midpoints are shared between neighboring faces and the
+    /// surface is unchanged. The mesh must be composed of triangles. Vertex
+    /// scalars at the midpoints are the mean of the edge endpoints and face
+    /// scalars are copied to the four children.
+    pub fn subdivide_midpoint(&mut self, iterations: usize) -> Result<(), HeMeshError> {
+        self.subdivide_midpoint_with_cancel(iterations, || true)
+    }
+
+    /// Subdivide as in `subdivide_midpoint`, checking the callback before
+    /// each iteration and stopping when it returns false. The mesh keeps the
+    /// completed iterations.
+    pub fn subdivide_midpoint_with_cancel<C>(
+        &mut self,
+        iterations: usize,
+        should_continue: C,
+    ) -> Result<(), HeMeshError>
+    where
+        C: Fn() -> bool,
+    {
+        for _ in 0..iterations {
+            if !should_continue() {
+                break;
+            }
+
+            let faces: Vec<Vec<usize>> =
+                (0..self.n_faces()).map(|f| self.face_vertices(f)).collect();
+
+            if faces.iter().any(|f| f.len() != 3) {
+                return Err(HeMeshError::NonTriangular);
+            }
+
+            let mut mesh = HeMesh {
+                patches: self.patches.clone(),
+                ..HeMesh::default()
+            };
+
+            let mut sources: Vec<(usize, usize, f64)> = vec![];
+
+            for (i, vertex) in self.vertices.iter().enumerate() {
+                mesh.insert_vertex(vertex.origin);
+                sources.push((i, i, 0.));
+            }
+
+            let mut midpoints = HashMap::<(usize, usize), usize>::new();
+
+            for (face_id, face) in faces.iter().enumerate() {
+                let [p, q, r] = [0, 1, 2].map(|i| face[i]);
+                let mut midpoint = |a: usize, b: usize| {
+                    *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                        let origin = (self.vertices[a].origin + self.vertices[b].origin) * 0.5;
+                        mesh.insert_vertex(origin);
+                        sources.push((a, b, 0.5));
+                        mesh.n_vertices() - 1
+                    })
+                };
+
+                let pq = midpoint(p, q);
+                let qr = midpoint(q, r);
+                let rp = midpoint(r, p);
+                let patch = self.faces[face_id].patch;
+
+                mesh.insert_face(&[p, pq, rp], patch);
+                mesh.insert_face(&[pq, q, qr], patch);
+                mesh.insert_face(&[rp, qr, r], patch);
+                mesh.insert_face(&[pq, qr, rp], patch);
+            }
+
+            let face_sources: Vec<Option<usize>> =
+                (0..faces.len()).flat_map(|f| [Some(f); 4]).collect();
+
+            mesh.build_links()?;
+            mesh.carry_scalars(self, &sources, &face_sources);
+            *self = mesh;
+        }
+
+        Ok(())
+    }
+
+    /// Get the contiguous faces as components
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut components = vec![];
+        let mut visited = vec![false; self.n_faces()];
+
+        for next in 0..self.n_faces() {
+            if !visited[next] {
+                let mut component = vec![];
+                let mut queue = VecDeque::from([next]);
+
+                while let Some(current) = queue.pop_front() {
+                    if !visited[current] {
+                        visited[current] = true;
+                        component.push(current);
+
+                        for neighbor in HeFaceFaceIter::new(self, current) {
+                            if !visited[neighbor] {
+                                queue.push_back(neighbor);
+                            }
+                        }
+                    }
+                }
+
+                components.push(component);
+            }
+        }
+
+        components
+    }
+
+    /// Get the bounds of each component ordered like `components`
+    pub fn component_bounds(&self) -> Vec<Aabb> {
+        self.components()
+            .iter()
+            .map(|faces| {
+                let mut min = Vector3::ones() * f64::INFINITY;
+                let mut max = Vector3::ones() * f64::NEG_INFINITY;
+
+                for &face in faces.iter() {
+                    for vertex in HeFaceVertexIter::new(self, face) {
+                        min = min.min(&self.vertices[vertex].origin);
+                        max = max.max(&self.vertices[vertex].origin);
+                    }
+                }
+
+                Aabb::from_bounds(min, max)
+            })
+            .collect()
+    }
+
+    /// Get the enclosed volume of each component ordered like `components`.
+    /// Each component should be closed and oriented outward.
+    pub fn component_volumes(&self) -> Vec<f64> {
+        self.components()
+            .iter()
+            .map(|faces| self.faces_volume(faces))
+            .collect()
+    }
+
+    // Get the signed volume enclosed by a subset of faces
+    fn faces_volume(&self, faces: &[usize]) -> f64 {
+        faces
+            .iter()
+            .flat_map(|&face| self.face_triangles(face))
+            .map(|t| Vector3::dot(&t[0], &Vector3::cross(&t[1], &t[2])) / 6.)
+            .sum()
+    }
+
+    /// Get a human-readable summary of the mesh counts, topology flags,
+    /// bounds and number of components
+    pub fn summary(&self) -> String {
+        let bounds = self.bounds();
+        let (min, max) = (bounds.min(), bounds.max());
+
+        [
+            format!("vertices: {}", self.n_vertices()),
+            format!("faces: {}", self.n_faces()),
+            format!("half edges: {}", self.n_half_edges()),
+            format!("patches: {}", self.n_patches()),
+            format!("closed: {}", self.is_closed()),
+            format!("consistent: {}", self.is_consistent()),
+            format!("triangles: {}", self.is_triangles()),
+            format!(
+                "bounds: [{}, {}, {}] to [{}, {}, {}]",
+                min[0], min[1], min[2], max[0], max[1], max[2]
+            ),
+            format!("components: {}", self.components().len()),
+        ]
+        .join("\n")
+    }
+
+    /// Get the face adjacency as a compressed sparse row (CSR) structure. The
+    /// neighbors of face i are `neighbors[offsets[i]..offsets[i + 1]]`.
+    pub fn face_adjacency(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut offsets = Vec::with_capacity(self.n_faces() + 1);
+        let mut neighbors = Vec::with_capacity(self.n_half_edges());
+
+        offsets.push(0);
+
+        for i in 0..self.n_faces() {
+            neighbors.extend(HeFaceFaceIter::new(self, i));
+            offsets.push(neighbors.len());
+        }
+
+        (offsets, neighbors)
+    }
+
+    /// Compute the approximate geodesic distance from a source vertex to every
+    /// vertex using Dijkstra's algorithm over the edge graph. Unreachable
+    /// vertices have an infinite distance.
+    pub fn geodesic_from(&self, source: usize) -> Vec<f64> {
+        let mut distances = vec![f64::INFINITY; self.n_vertices()];
+        let mut adjacency = vec![vec![]; self.n_vertices()];
+
+        // Build the adjacency from the half edges directly so boundary edges
+        // are traversable in both directions on open meshes.
+        for half_edge in self.half_edges.iter() {
+            let i = half_edge.origin;
+            let j = self.half_edges[half_edge.next].origin;
+            let p = self.vertices[i].origin;
+            let q = self.vertices[j].origin;
+            let length = Line::new(p, q).length();
+
+            adjacency[i].push((j, length));
+
+            if half_edge.is_boundary() {
+                adjacency[j].push((i, length));
+            }
+        }
+
+        let mut queue = BinaryHeap::new();
+        distances[source] = 0.;
+        queue.push(GeodesicNode {
+            vertex: source,
+            distance: 0.,
+        });
+
+        while let Some(GeodesicNode { vertex, distance }) = queue.pop() {
+            if distance > distances[vertex] {
+                continue;
+            }
+
+            for &(neighbor, length) in adjacency[vertex].iter() {
+                let candidate = distance + length;
+
+                if candidate < distances[neighbor] {
+                    distances[neighbor] = candidate;
+                    queue.push(GeodesicNode {
+                        vertex: neighbor,
+                        distance: candidate,
+                    });
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Get the indices of the vertices shared between two faces
+    pub fn shared_vertices(&self, i: usize, j: usize) -> Vec<usize> {
+        let mut index = HashSet::<usize>::new();
+        let mut vertices = vec![];
+
+        for vertex in self.face_vertices(i) {
+            index.insert(vertex);
+        }
+
+        for vertex in self.face_vertices(j) {
+            if index.contains(&vertex) {
+                vertices.push(vertex);
+            }
+        }
+
+        vertices
+    }
+
+    /// Orient the mesh
+    pub fn orient(&mut self) {
+        let mut oriented = vec![false; self.n_faces()];
+
+        for component in self.components() {
+            self.orient_from(component[0], &mut oriented);
+        }
+    }
+
+    /// Orient the mesh so the faces of each closed component point outward.
+    /// Every component is made consistent and closed components enclosing a
+    /// negative signed volume are flipped. Open components have no inside so
+    /// they are only made consistent.
+    pub fn orient_outward(&mut self) {
+        let mut oriented = vec![false; self.n_faces()];
+
+        for component in self.components() {
+            self.orient_from(component[0], &mut oriented);
+
+            let closed = component.iter().all(|&face| {
+                self.face_half_edges(face)
+                    .iter()
+                    .all(|&h| self.half_edges[h].twin.is_some())
+            });
+
+            if closed && self.faces_volume(&component) < 0. {
+                for &face in component.iter() {
+                    self.flip_face(face);
+                }
+            }
+        }
+    }
+
+    /// Flip every face of a closed mesh if it encloses a negative signed
+    /// volume. Unlike orient_outward, the mesh is treated as a single
+    /// consistently oriented solid.
+    pub fn ensure_outward(&mut self) -> Result<(), HeMeshError> {
+        if !self.is_closed() {
+            return Err(HeMeshError::Open);
+        }
+
+        if self.volume() < 0. {
+            for face in 0..self.n_faces() {
+                self.flip_face(face);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Orient each component independently as in `orient_outward`
+    pub fn orient_all_outward(&mut self) {
+        self.orient_outward();
+    }
+
+    /// Orient each component consistently with its faces pointing away from
+    /// a point known to be outside of the mesh. A ray is cast from the point
+    /// through the largest triangle of each component and the component is
+    /// flipped if the first face hit faces away from the point. This does
+    /// not rely on the sign of the enclosed volume.
+    pub fn orient_toward(&mut self, outside_point: Vector3) {
+        let mut oriented = vec![false; self.n_faces()];
+
+        for component in self.components() {
+            self.orient_from(component[0], &mut oriented);
+
+            let target = component
+                .iter()
+                .flat_map(|&face| self.face_triangles(face))
+                .max_by(|a, b| a.area().total_cmp(&b.area()))
+                .map(|triangle| triangle.center());
+
+            let Some(target) = target else {
+                continue;
+            };
+
+            let ray = Ray::new(outside_point, target - outside_point);
+            let members: HashSet<usize> = component.iter().copied().collect();
+
+            let hit = self
+                .raycast_all(&ray)
+                .into_iter()
+                .find(|(face, _, _)| members.contains(face));
+
+            if let Some((face, _, _)) = hit {
+                if Vector3::dot(&self.face_normal(face), &ray.direction()) > 0. {
+                    for &face in component.iter() {
+                        self.flip_face(face);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Orient the component containing a face by index to match the current
+    /// winding of that face
+    pub fn orient_like(&mut self, reference_face: usize) {
+        let mut oriented = vec![false; self.n_faces()];
+        self.orient_from(reference_face, &mut oriented);
+    }
+
+    // Propagate the orientation of the seed face across its component
+    fn orient_from(&mut self, seed: usize, oriented: &mut [bool]) {
+        let mut queue = VecDeque::from([seed]);
+
+        while let Some(current) = queue.pop_front() {
+            if !oriented[current] {
+                oriented[current] = true;
+
+                for neighbor in self.face_neighbors(current) {
+                    if !oriented[neighbor] {
+                        queue.push_back(neighbor);
+
+                        if !self.is_face_consistent(current, neighbor) {
+                            self.flip_face(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Zip any open edges. This may result in a non-manifold mesh.
+    pub fn zip_edges(&mut self) -> Result<(), HeMeshError> {
+        // TODO: implement
+        unimplemented!();
+    }
+
+    /// Get the half edge pairs whose incident faces form an angle greater
+    /// than the threshold (in radians)
+    pub fn feature_edges(&self, threshold: f64) -> Vec<(usize, usize)> {
+        let mut visited = vec![false; self.n_half_edges()];
+        let mut features = vec![];
+
+        for (i, half_edge) in self.half_edges.iter().enumerate() {
+            if let Some(j) = half_edge.twin {
+                if !visited[i] && !visited[j] {
+                    visited[i] = true;
+                    visited[j] = true;
+                    let twin = self.half_edges[j];
+
+                    let u = self.face_normal(half_edge.face);
+                    let v = self.face_normal(twin.face);
+
+                    if Vector3::angle(&u, &v) > threshold {
+                        features.push((i, j));
+                    }
+                }
+            }
+        }
+
+        features
+    }
+
+    /// Assign the faces to new patches by flooding across the non-feature
+    /// edges. Each region bounded by feature edges (or the mesh boundary)
+    /// replaces any existing patches as a patch named by its index.
+    pub fn segment_by_features(&mut self, threshold: f64) {
+        let mut is_feature = vec![false; self.n_half_edges()];
+
+        for (i, j) in self.feature_edges(threshold) {
+            is_feature[i] = true;
+            is_feature[j] = true;
+        }
+
+        let mut patches = vec![None; self.n_faces()];
+        self.patches.clear();
+
+        for seed in 0..self.n_faces() {
+            if patches[seed].is_none() {
+                let patch = self.n_patches();
+                self.insert_patch(&format!("patch{}", patch));
+
+                patches[seed] = Some(patch);
+                let mut queue = VecDeque::from([seed]);
+
+                while let Some(current) = queue.pop_front() {
+                    for half_edge in self.face_half_edges(current) {
+                        if let Some(twin) = self.half_edges[half_edge].twin {
+                            let neighbor = self.half_edges[twin].face;
+
+                            if !is_feature[half_edge] && patches[neighbor].is_none() {
+                                patches[neighbor] = Some(patch);
+                                queue.push_back(neighbor);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for (face, patch) in self.faces.iter_mut().zip(patches) {
+            face.patch = patch;
+        }
+    }
+
+    /// Get the dihedral angle (in radians) between two faces as the angle
+    /// between their normals. Coplanar faces have an angle of zero.
+    pub fn dihedral_angle(&self, i: usize, j: usize) -> f64 {
+        Vector3::angle(&self.face_normal(i), &self.face_normal(j))
+    }
+
+    /// Grow a selection of faces from a seed face across the neighboring
+    /// faces whose dihedral angle is at most the threshold (in radians). The
+    /// selection stops at creases and is returned in sorted order.
+    pub fn flood_select(&self, seed: usize, max_dihedral: f64) -> Vec<usize> {
+        let mut selected = vec![false; self.n_faces()];
+        let mut queue = VecDeque::from([seed]);
+        selected[seed] = true;
+
+        while let Some(face) = queue.pop_front() {
+            for neighbor in self.face_neighbors(face) {
+                if !selected[neighbor] && self.dihedral_angle(face, neighbor) <= max_dihedral {
+                    selected[neighbor] = true;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        (0..self.n_faces()).filter(|&i| selected[i]).collect()
+    }
+
+    /// Get the feature edges chained into polylines of vertex indices. The
+    /// polylines are split at junctions where other than two feature edges
+    /// meet. Closed loops repeat the first vertex at the end.
+    pub fn feature_lines(&self, threshold: f64) -> Vec<Vec<usize>> {
+        let mut adjacency = HashMap::<usize, Vec<usize>>::new();
+
+        for (i, j) in self.feature_edges(threshold) {
+            let a = self.half_edges[i].origin;
+            let b = self.half_edges[j].origin;
+            adjacency.entry(a).or_default().push(b);
+            adjacency.entry(b).or_default().push(a);
+        }
+
+        for neighbors in adjacency.values_mut() {
+            neighbors.sort();
+        }
+
+        let mut vertices: Vec<usize> = adjacency.keys().copied().collect();
+        vertices.sort();
+
+        let mut visited = HashSet::<(usize, usize)>::new();
+        let mut lines = vec![];
+
+        let walk = |start: usize, next: usize, visited: &mut HashSet<(usize, usize)>| {
+            let mut line = vec![start];
+            let (mut prev, mut curr) = (start, next);
+
+            loop {
+                visited.insert((prev.min(curr), prev.max(curr)));
+                line.push(curr);
+
+                let neighbors = &adjacency[&curr];
+
+                if neighbors.len() != 2 || curr == start {
+                    break;
+                }
+
+                let next = if neighbors[0] == prev {
+                    neighbors[1]
+                } else {
+                    neighbors[0]
+                };
+                (prev, curr) = (curr, next);
+            }
+
+            line
+        };
+
+        // Open polylines start and end at junctions or endpoints
+        for &v in vertices.iter().filter(|&v| adjacency[v].len() != 2) {
+            for &u in adjacency[&v].iter() {
+                if !visited.contains(&(v.min(u), v.max(u))) {
+                    lines.push(walk(v, u, &mut visited));
+                }
+            }
+        }
+
+        // The remaining feature edges form closed loops
+        for &v in vertices.iter() {
+            let u = adjacency[&v][0];
+
+            if !visited.contains(&(v.min(u), v.max(u))) {
+                lines.push(walk(v, u, &mut visited));
+            }
+        }
+
+        lines
+    }
+
+    /// Slice the mesh with a plane. Each face crossing the plane yields a
+    /// single line segment. Faces touching the plane along an edge only
+    /// report the segment when the rest of the face lies on the positive
+    /// side of the plane so shared edges are not duplicated.
+    pub fn slice(&self, plane: &Plane) -> Vec<Line> {
+        let mut segments = vec![];
+
+        for face_id in 0..self.n_faces() {
+            let points: Vec<Vector3> = self
+                .face_vertices(face_id)
+                .iter()
+                .map(|&v| self.vertices[v].origin)
+                .collect();
+
+            let distances: Vec<f64> = points
+                .iter()
+                .map(|p| plane.signed_distance_eps(p, EPSILON))
+                .collect();
+
+            if !distances.iter().any(|&d| d > 0.) {
+                continue;
+            }
+
+            let n = points.len();
+            let mut crossings = vec![];
+
+            for i in 0..n {
+                let j = (i + 1) % n;
+                let di = distances[i];
+                let dj = distances[j];
+
+                if di == 0. {
+                    crossings.push(points[i]);
+                } else if dj != 0. && (di < 0.) != (dj < 0.) {
+                    let t = di / (di - dj);
+                    crossings.push(points[i] + (points[j] - points[i]) * t);
+                }
+            }
+
+            if crossings.len() == 2 {
+                segments.push(Line::new(crossings[0], crossings[1]));
+            }
+        }
+
+        segments
+    }
+
+    /// Slice the mesh with a plane and chain the segments into ordered
+    /// contours by matching shared endpoints. Closed loops do not repeat
+    /// their first point while open contours start and end on a boundary.
+    pub fn slice_contours(&self, plane: &Plane) -> Vec<Vec<Vector3>> {
+        let segments: Vec<Line> = self
+            .slice(plane)
+            .into_iter()
+            .filter(|s| s.length() > EPSILON)
+            .collect();
+
+        let mut contours = vec![];
+        let mut visited = vec![false; segments.len()];
+
+        for i in 0..segments.len() {
+            if visited[i] {
+                continue;
+            }
+
+            visited[i] = true;
+            let mut contour = VecDeque::from([segments[i].p(), segments[i].q()]);
+            let mut closed = false;
+
+            while let Some(point) =
+                next_contour_point(&segments, &mut visited, contour[contour.len() - 1])
+            {
+                if (point - contour[0]).mag() <= EPSILON {
+                    closed = true;
+                    break;
+                }
+
+                contour.push_back(point);
+            }
+
+            if !closed {
+                while let Some(point) = next_contour_point(&segments, &mut visited, contour[0]) {
+                    contour.push_front(point);
+                }
+            }
+
+            contours.push(contour.into_iter().collect());
+        }
+
+        contours
+    }
+
+    /// Split the mesh by a plane into the parts on its positive and negative
+    /// sides. Faces crossing the plane are clipped, sharing a new vertex along
+    /// each cut edge. When capped, the open section of each part is closed by
+    /// triangulating its contours into faces assigned to a new "cap" patch so
+    /// a closed mesh yields two closed parts. Sections with holes are not
+    /// supported when capping. Vertex scalars are interpolated at the cut
+    /// vertices, the clipped faces keep their face scalars, and the cap faces
+    /// have NaN face scalars.
+    pub fn split_by_plane(
+        &self,
+        plane: &Plane,
+        cap: bool,
+    ) -> Result<(HeMesh, HeMesh), HeMeshError> {
+        let mut points: Vec<Vector3> = self.vertices.iter().map(|v| v.origin).collect();
+        let mut distances: Vec<f64> = points.iter().map(|p| plane.signed_distance(p)).collect();
+        let mut sources: Vec<(usize, usize, f64)> = (0..points.len()).map(|v| (v, v, 0.)).collect();
+        let mut cuts = HashMap::<(usize, usize), usize>::new();
+        let mut positive = vec![];
+        let mut negative = vec![];
+
+        for face in 0..self.n_faces() {
+            let vertices = self.face_vertices(face);
+            let patch = self.faces[face].patch;
+            let n = vertices.len();
+            let mut polygon = vec![];
+
+            for i in 0..n {
+                let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+                polygon.push(a);
+
+                if distances[a].min(distances[b]) < -EPSILON
+                    && distances[a].max(distances[b]) > EPSILON
+                {
+                    let (p, q) = (a.min(b), a.max(b));
+
+                    let cut = *cuts.entry((p, q)).or_insert_with(|| {
+                        let t = distances[p] / (distances[p] - distances[q]);
+                        points.push(points[p] + (points[q] - points[p]) * t);
+                        distances.push(0.);
+                        sources.push((p, q, t));
+                        points.len() - 1
+                    });
+
+                    polygon.push(cut);
+                }
+            }
+
+            // A face lying on the plane closes the part on the side it faces
+            // away from
+            if polygon.iter().all(|&v| distances[v].abs() <= EPSILON) {
+                if Vector3::dot(&self.face_normal(face), &plane.normal()) < 0. {
+                    positive.push((polygon, patch, Some(face)));
+                } else {
+                    negative.push((polygon, patch, Some(face)));
+                }
+
+                continue;
+            }
+
+            if polygon.iter().any(|&v| distances[v] > EPSILON) {
+                let above = polygon
+                    .iter()
+                    .copied()
+                    .filter(|&v| distances[v] >= -EPSILON);
+                positive.push((above.collect(), patch, Some(face)));
+            }
+
+            if polygon.iter().any(|&v| distances[v] < -EPSILON) {
+                let below = polygon.iter().copied().filter(|&v| distances[v] <= EPSILON);
+                negative.push((below.collect(), patch, Some(face)));
+            }
+        }
+
+        let positive = self.split_part(&points, &distances, &sources, positive, cap)?;
+        let negative = self.split_part(&points, &distances, &sources, negative, cap)?;
+
+        Ok((positive, negative))
+    }
+
+    // Build one part of a plane split from its clipped faces (with their
+    // patch and source face), optionally capping the open edges lying on the
+    // plane
+    fn split_part(
+        &self,
+        points: &[Vector3],
+        distances: &[f64],
+        sources: &[(usize, usize, f64)],
+        mut faces: Vec<(Vec<usize>, Option<usize>, Option<usize>)>,
+        cap: bool,
+    ) -> Result<HeMesh, HeMeshError> {
+        let mut mesh = HeMesh {
+            patches: self.patches.clone(),
+            ..HeMesh::default()
+        };
+
+        faces.retain(|(vertices, _, _)| vertices.len() >= 3);
+
+        if cap {
+            let mut edges = HashSet::new();
+
+            for (vertices, _, _) in faces.iter() {
+                let n = vertices.len();
+                edges.extend((0..n).map(|i| (vertices[i], vertices[(i + 1) % n])));
+            }
+
+            // The cap runs against the open edges on the plane
+            let mut next: HashMap<usize, usize> = edges
+                .iter()
+                .filter(|&&(a, b)| !edges.contains(&(b, a)))
+                .filter(|&&(a, b)| distances[a].abs() <= EPSILON && distances[b].abs() <= EPSILON)
+                .map(|&(a, b)| (b, a))
+                .collect();
+
+            mesh.insert_patch("cap");
+            let patch = Some(mesh.n_patches() - 1);
+
+            while let Some(&start) = next.keys().min() {
+                let mut contour = vec![];
+                let mut current = start;
+
+                while let Some(v) = next.remove(&current) {
+                    contour.push(current);
+                    current = v;
+                }
+
+                if current == start && contour.len() >= 3 {
+                    let polygon: Vec<Vector3> = contour.iter().map(|&v| points[v]).collect();
+
+                    for [a, b, c] in triangulate_polygon(&polygon) {
+                        faces.push((vec![contour[a], contour[b], contour[c]], patch, None));
+                    }
+                }
+            }
+        }
+
+        let mut index = HashMap::<usize, usize>::new();
+        let mut vertex_sources = vec![];
+        let mut face_sources = vec![];
+
+        for (vertices, patch, source) in faces {
+            let vertices: Vec<usize> = vertices
+                .iter()
+                .map(|&v| {
+                    *index.entry(v).or_insert_with(|| {
+                        mesh.insert_vertex(points[v]);
+                        vertex_sources.push(sources[v]);
+                        mesh.n_vertices() - 1
+                    })
+                })
+                .collect();
+
+            mesh.insert_face(&vertices, patch);
+            face_sources.push(source);
+        }
+
+        mesh.build_links()?;
+        mesh.carry_scalars(self, &vertex_sources, &face_sources);
+        Ok(mesh)
+    }
+
+    /// Get the nearest face hit by a ray along with the parameter along the ray
+    /// at the hit. Faces facing away from the ray are optionally culled.
+    pub fn raycast_culled(&self, ray: &Ray, cull_backfaces: bool) -> Option<(usize, f64)> {
+        let mut nearest: Option<(usize, f64)> = None;
+
+        for face in 0..self.n_faces() {
+            for triangle in self.face_triangles(face) {
+                if let Some(t) = ray.hit_triangle(&triangle, cull_backfaces) {
+                    if nearest.is_none_or(|(_, nearest)| t < nearest) {
+                        nearest = Some((face, t));
+                    }
+                }
+            }
+        }
+
+        nearest
+    }
+
+    /// Get every face hit by a ray along with the parameter along the ray
+    /// and the point at each hit, sorted by ascending parameter. Faces are
+    /// hit from either side and each face is reported at most once.
+    pub fn raycast_all(&self, ray: &Ray) -> Vec<(usize, f64, Vector3)> {
+        let mut hits = vec![];
+
+        for face in 0..self.n_faces() {
+            let t = self
+                .face_triangles(face)
+                .iter()
+                .filter_map(|triangle| ray.hit_triangle(triangle, false))
+                .min_by(|a, b| a.total_cmp(b));
+
+            if let Some(t) = t {
+                hits.push((face, t, ray.origin() + ray.direction() * t));
+            }
+        }
+
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits
+    }
+
+    /// Check if the mesh spatially intersects another mesh
+    pub fn intersects(&self, other: &HeMesh) -> bool {
+        !self.intersecting_faces(other).is_empty()
+    }
+
+    /// Get the pairs of faces (self, other) that spatially intersect between
+    /// this mesh and another mesh.
+    pub fn intersecting_faces(&self, other: &HeMesh) -> Vec<(usize, usize)> {
+        let mut octree = Octree::<Triangle>::new(self.bounds());
+        let mut index = vec![];
+
+        for face_id in 0..self.n_faces() {
+            for triangle in self.face_triangles(face_id) {
+                octree.insert(triangle);
+                index.push(face_id);
+            }
+        }
+
+        let mut pairs = HashSet::<(usize, usize)>::new();
+
+        for face_id in 0..other.n_faces() {
+            for triangle in other.face_triangles(face_id) {
+                for item in octree.query(&triangle) {
+                    pairs.insert((index[item], face_id));
+                }
+            }
+        }
+
+        let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+        pairs.sort();
+        pairs
+    }
+
+    /// Get the pairs of faces (i, j) with i < j that spatially intersect
+    /// each other, sorted. Faces sharing a vertex always touch, so they are
+    /// not reported.
+    pub fn self_intersections(&self) -> Vec<(usize, usize)> {
+        let (octree, index) = self.face_octree();
+
+        (0..self.n_faces())
+            .into_par_iter()
+            .flat_map_iter(|i| {
+                self.self_intersecting_faces(&octree, &index, i)
+                    .into_iter()
+                    .map(move |j| (i, j))
+            })
+            .collect()
+    }
+
+    /// Lazily yield the pairs of faces (i, j) with i < j that spatially
+    /// intersect each other in the same order as `self_intersections`. Only
+    /// the octree is built up front, so stopping early (e.g. to check for
+    /// any self-intersection) avoids testing the remaining faces.
+    pub fn self_intersection_iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (octree, index) = self.face_octree();
+
+        (0..self.n_faces()).flat_map(move |i| {
+            self.self_intersecting_faces(&octree, &index, i)
+                .into_iter()
+                .map(move |j| (i, j))
+        })
+    }
+
+    // Get the sorted faces after a face intersecting it that do not share a
+    // vertex with it
+    fn self_intersecting_faces(
+        &self,
+        octree: &Octree<Triangle>,
+        index: &[usize],
+        face: usize,
+    ) -> Vec<usize> {
+        let vertices = self.face_vertices(face);
+
+        let mut faces: Vec<usize> = self
+            .face_triangles(face)
+            .iter()
+            .flat_map(|triangle| octree.query(triangle))
+            .map(|item| index[item])
+            .filter(|&other| other > face)
+            .filter(|&other| {
+                !self
+                    .face_vertices(other)
+                    .iter()
+                    .any(|v| vertices.contains(v))
+            })
+            .collect();
+
+        faces.sort();
+        faces.dedup();
+        faces
+    }
+
+    /// Get the closest point on the mesh surface to a point. This returns the
+    /// nearest face, the closest point on it, and the distance, or None if
+    /// the mesh has no faces. Candidate faces are found by querying an octree
+    /// with an expanding search box. The octree is built on each call, so use
+    /// `closest_points` to query many points.
+    pub fn closest_point(&self, point: Vector3) -> Option<(usize, Vector3, f64)> {
+        let (octree, index) = self.face_octree();
+        closest_point(&octree, &index, point)
+    }
+
+    /// Get the closest points on the mesh surface to many points in parallel
+    /// as in `closest_point`, building the octree once. This returns None if
+    /// the mesh has no faces.
+    pub fn closest_points(&self, points: &[Vector3]) -> Option<Vec<(usize, Vector3, f64)>> {
+        let (octree, index) = self.face_octree();
+
+        points
+            .par_iter()
+            .map(|&point| closest_point(&octree, &index, point))
+            .collect()
+    }
+
+    /// Get the feature of the mesh surface closest to a point along with the
+    /// nearest face. The feature is the face itself, one of its edges as the
+    /// vertex pair in face order, or one of its vertices. Diagonals added to
+    /// triangulate polygonal faces belong to the face. This returns None if
+    /// the mesh has no faces.
+    pub fn closest_feature(&self, point: Vector3) -> Option<(usize, MeshFeature)> {
+        let (face_id, _, _) = self.closest_point(point)?;
+        let vertices = self.face_vertices(face_id);
+        let n = vertices.len();
+        let points: Vec<Vector3> = vertices.iter().map(|&v| self.vertices[v].origin).collect();
+
+        let (triangle, region) = triangulate_face(&points)
+            .into_iter()
+            .map(|t| {
+                let triangle = Triangle::new(points[t[0]], points[t[1]], points[t[2]]);
+                let (closest, region) = triangle.closest_point_region(&point);
+                (t, region, (closest - point).mag())
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(t, region, _)| (t, region))
+            .expect("face has no triangles");
+
+        let feature = match region {
+            TriangleRegion::Vertex(i) => MeshFeature::Vertex(vertices[triangle[i]]),
+            TriangleRegion::Edge(i) => {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+
+                if (a + 1) % n == b {
+                    MeshFeature::Edge(vertices[a], vertices[b])
+                } else if (b + 1) % n == a {
+                    MeshFeature::Edge(vertices[b], vertices[a])
+                } else {
+                    MeshFeature::Face(face_id)
+                }
+            }
+            TriangleRegion::Face => MeshFeature::Face(face_id),
+        };
+
+        Some((face_id, feature))
+    }
+
+    /// Get the signed distance to the mesh surface, which is negative inside
+    /// and positive outside, or None if the mesh has no faces. This assumes
+    /// a closed mesh oriented outward.
+    pub fn signed_distance(&self, point: Vector3) -> Option<f64> {
+        let (_, _, distance) = self.closest_point(point)?;
+
+        if self.contains_point(&point) {
+            Some(-distance)
+        } else {
+            Some(distance)
+        }
+    }
+
+    /// Sample the signed distance on a regular grid with the resolution
+    /// number of points along each axis spanning the bounds. The samples are
+    /// ordered with x varying fastest, then y, then z. The distance is
+    /// infinite if the mesh has no faces.
+    pub fn sample_sdf(&self, bounds: Aabb, resolution: usize) -> Vec<f64> {
+        let (octree, index) = self.face_octree();
+        let min = bounds.min();
+        let step = if resolution > 1 {
+            (bounds.max() - min) / (resolution - 1) as f64
+        } else {
+            Vector3::zeros()
+        };
+
+        (0..resolution * resolution * resolution)
+            .into_par_iter()
+            .map(|n| {
+                let i = n % resolution;
+                let j = (n / resolution) % resolution;
+                let k = n / (resolution * resolution);
+                let offset = Vector3::new(i as f64, j as f64, k as f64) * step;
+                let point = min + offset;
+                let distance = closest_point(&octree, &index, point)
+                    .map_or(f64::INFINITY, |(_, _, distance)| distance);
+
+                if self.contains_point(&point) {
+                    -distance
+                } else {
+                    distance
+                }
+            })
+            .collect()
+    }
+
+    /// Estimate the symmetric Hausdorff distance to another mesh as the
+    /// largest distance from the points sampled on the surface of either
+    /// mesh to the surface of the other. The sampling uses a fixed seed, so
+    /// the estimate is reproducible and improves with the number of samples.
+    pub fn hausdorff_distance(&self, other: &HeMesh, samples: usize) -> f64 {
+        let one_sided = |from: &HeMesh, to: &HeMesh| {
+            let (octree, index) = to.face_octree();
+
+            from.sample_surface(samples, 0)
+                .into_par_iter()
+                .map(|point| {
+                    closest_point(&octree, &index, point)
+                        .map_or(f64::INFINITY, |(_, _, distance)| distance)
+                })
+                .reduce(|| 0., f64::max)
+        };
+
+        one_sided(self, other).max(one_sided(other, self))
+    }
+
+    // Build an octree of the face triangles along with the face index of
+    // each triangle
+    fn face_octree(&self) -> (Octree<Triangle>, Vec<usize>) {
+        let mut octree = Octree::<Triangle>::new(self.bounds());
+        let mut index = vec![];
+
+        for face_id in 0..self.n_faces() {
+            for triangle in self.face_triangles(face_id) {
+                octree.insert(triangle);
+                index.push(face_id);
+            }
+        }
+
+        (octree, index)
+    }
+
+    /// Sample points uniformly over the surface. Faces are selected with a
+    /// probability proportional to their area and points are placed within
+    /// them using uniform barycentric coordinates. The seed makes the
+    /// sampling reproducible.
+    pub fn sample_surface(&self, n: usize, seed: u64) -> Vec<Vector3> {
+        let triangles: Vec<Triangle> = self.triangles().collect();
+        let mut cumulative = Vec::with_capacity(triangles.len());
+        let mut total = 0.;
+
+        for triangle in triangles.iter() {
+            total += triangle.area();
+            cumulative.push(total);
+        }
+
+        if total <= 0. {
+            return vec![];
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..n)
+            .map(|_| {
+                let target = rng.gen::<f64>() * total;
+                let index = cumulative.partition_point(|&a| a < target);
+                let t = triangles[index.min(triangles.len() - 1)];
+
+                let (mut u, mut v) = (rng.gen::<f64>(), rng.gen::<f64>());
+
+                if u + v > 1. {
+                    (u, v) = (1. - u, 1. - v);
+                }
+
+                t[0] + (t[1] - t[0]) * u + (t[2] - t[0]) * v
+            })
+            .collect()
+    }
+
+    /// Get the principal axes defining the dominant orthogonal coordinate
+    /// system local to the mesh vertices. The unit axes are ordered by
+    /// decreasing variance of the vertices along them.
+    pub fn principal_axes(&self) -> Vec<Vector3> {
+        let points: Vec<Vector3> = self.vertices.iter().map(|v| v.origin).collect();
+        point_axes(&points).to_vec()
+    }
+
+    /// Get the 2D coordinates of each vertex projected onto a plane. The
+    /// coordinates are measured from the plane origin along the orthonormal
+    /// basis of the plane normal, so faces wound counter-clockwise about the
+    /// normal stay counter-clockwise in 2D.
+    pub fn project_to_plane(&self, plane: &Plane) -> Vec<(f64, f64)> {
+        let (u, v) = plane.normal().orthonormal_basis();
+
+        self.vertices
+            .iter()
+            .map(|vertex| {
+                let d = vertex.origin - plane.origin();
+                (Vector3::dot(&d, &u), Vector3::dot(&d, &v))
+            })
+            .collect()
+    }
+
+    /// Get a UV coordinate for each vertex by projecting the vertices of each
+    /// patch onto the plane of its two principal axes and normalizing the
+    /// coordinates to [0, 1]. Faces without a patch are projected together.
+    /// A vertex shared by several patches takes its coordinate from the last
+    /// of them and a vertex not used by any face is assigned (0, 0).
+    pub fn planar_uv(&self) -> Vec<(f64, f64)> {
+        let mut uv = vec![(0., 0.); self.n_vertices()];
+        let mut groups = vec![HashSet::<usize>::new(); self.n_patches() + 1];
+
+        for (face_id, face) in self.faces.iter().enumerate() {
+            let group = face.patch.unwrap_or(self.n_patches());
+            groups[group].extend(self.face_vertices(face_id));
+        }
+
+        for group in groups.iter().filter(|g| !g.is_empty()) {
+            let mut vertices: Vec<usize> = group.iter().copied().collect();
+            vertices.sort();
+
+            let points: Vec<Vector3> = vertices.iter().map(|&v| self.vertices[v].origin).collect();
+            let axes = point_axes(&points);
+
+            let projected: Vec<(f64, f64)> = points
+                .iter()
+                .map(|p| (Vector3::dot(p, &axes[0]), Vector3::dot(p, &axes[1])))
+                .collect();
+
+            let (u_min, u_max) = projected
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                    (lo.min(p.0), hi.max(p.0))
+                });
+            let (v_min, v_max) = projected
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                    (lo.min(p.1), hi.max(p.1))
+                });
+
+            let normalize = |x: f64, lo: f64, hi: f64| {
+                if hi - lo > EPSILON {
+                    (x - lo) / (hi - lo)
+                } else {
+                    0.
+                }
+            };
+
+            for (&v, &(u, w)) in vertices.iter().zip(projected.iter()) {
+                uv[v] = (normalize(u, u_min, u_max), normalize(w, v_min, v_max));
+            }
+        }
+
+        uv
+    }
+
+    /// Merge naively with another mesh. The receiver mesh is updated in place
+    /// with the elements from the target mesh. Scalar attributes defined on
+    /// only one of the meshes are filled with NaN for the other elements.
+    /// Patches of the target mesh are matched to the first receiver patch of
+    /// the same name and the rest are appended in their original order, so
+    /// the receiver patch ids are unchanged and the new ids are deterministic.
+    pub fn merge(&mut self, other: &HeMesh) {
+        let index_patches: Vec<usize> = other
+            .patches
+            .iter()
+            .map(|patch| {
+                self.patches
+                    .iter()
+                    .position(|p| p.name == patch.name)
+                    .unwrap_or_else(|| {
+                        self.patches.push(patch.clone());
+                        self.patches.len() - 1
+                    })
+            })
+            .collect();
+
+        let offset_v = self.n_vertices();
+        let offset_f = self.n_faces();
+        let offset_h = self.n_half_edges();
+
+        for vertex in other.vertices.iter() {
+            let mut vertex = *vertex;
+            vertex.half_edge += offset_h;
+            self.vertices.push(vertex);
+        }
+
+        for face in other.faces.iter() {
+            let mut face = *face;
+            face.half_edge += offset_h;
+
+            face.patch = face.patch.map(|patch| index_patches[patch]);
+
+            self.faces.push(face);
+        }
+
+        for half_edge in other.half_edges.iter() {
+            let mut half_edge = *half_edge;
+            half_edge.origin += offset_v;
+            half_edge.face += offset_f;
+            half_edge.prev += offset_h;
+            half_edge.next += offset_h;
+
+            if let Some(twin) = half_edge.twin {
+                half_edge.twin = Some(twin + offset_h);
+            }
+
+            self.half_edges.push(half_edge);
+        }
+
+        merge_scalars(
+            &mut self.vertex_scalars,
+            &other.vertex_scalars,
+            offset_v,
+            other.n_vertices(),
+        );
+
+        merge_scalars(
+            &mut self.face_scalars,
+            &other.face_scalars,
+            offset_f,
+            other.n_faces(),
+        );
+    }
+
+    /// Thicken the surface into a closed shell by offsetting a copy of the
+    /// surface along the (area-weighted) vertex normals by the distance and
+    /// bridging the boundaries of the two surfaces with quads. The faces are
+    /// oriented outward from the shell assuming a consistently oriented
+    /// surface. The offset copy shares the scalar attributes of the surface
+    /// and the bridging quads have NaN face scalars.
+    pub fn extrude_boundary(&mut self, distance: f64) -> Result<(), HeMeshError> {
+        let n = self.n_vertices();
+        let normals = self.vertex_normals();
+
+        let mut mesh = HeMesh {
+            patches: self.patches.clone(),
+            ..HeMesh::default()
+        };
+
+        for vertex in self.vertices.iter() {
+            mesh.insert_vertex(vertex.origin);
+        }
+
+        for (vertex, normal) in self.vertices.iter().zip(normals.iter()) {
+            mesh.insert_vertex(vertex.origin + *normal * distance);
+        }
+
+        // The surface facing the offset copy is reversed so both face away
+        // from the enclosed shell.
+        let flip_original = distance > 0.;
+
+        for face_id in 0..self.n_faces() {
+            let mut vertices = self.face_vertices(face_id);
+            let patch = self.faces[face_id].patch;
+            let mut offset: Vec<usize> = vertices.iter().map(|v| v + n).collect();
+
+            if flip_original {
+                vertices.reverse();
+            } else {
+                offset.reverse();
+            }
+
+            mesh.insert_face(&vertices, patch);
+            mesh.insert_face(&offset, patch);
+        }
+
+        let mut face_sources: Vec<Option<usize>> =
+            (0..self.n_faces()).flat_map(|f| [Some(f); 2]).collect();
+
+        for half_edge in self.half_edges.iter().filter(|h| h.is_boundary()) {
+            let a = half_edge.origin;
+            let b = self.half_edges[half_edge.next].origin;
+
+            if flip_original {
+                mesh.insert_face(&[a, b, b + n, a + n], None);
+            } else {
+                mesh.insert_face(&[b, a, a + n, b + n], None);
+            }
+
+            face_sources.push(None);
+        }
+
+        let sources: Vec<(usize, usize, f64)> = (0..2 * n).map(|v| (v % n, v % n, 0.)).collect();
+
+        mesh.build_links()?;
+        mesh.carry_scalars(self, &sources, &face_sources);
+        *self = mesh;
+
+        Ok(())
+    }
+
+    /// Merge with another mesh and weld coincident boundary vertices within
+    /// the tolerance. The links are rebuilt so touching meshes are joined into
+    /// a single component. This may result in a non-manifold mesh error.
+    pub fn merge_welded(&mut self, other: &HeMesh, tolerance: f64) -> Result<(), HeMeshError> {
+        self.merge(other);
+        self.weld_boundary_vertices(tolerance)
+    }
+
+    // Weld coincident boundary vertices within the tolerance and rebuild
+    // the mesh from the welded vertices.
+    fn weld_boundary_vertices(&mut self, tolerance: f64) -> Result<(), HeMeshError> {
+        let n = self.n_vertices();
+        let mut boundary = vec![false; n];
+        let mut welded: Vec<usize> = (0..n).collect();
+
+        for half_edge in self.half_edges.iter().filter(|h| h.is_boundary()) {
+            boundary[half_edge.origin] = true;
+            boundary[self.half_edges[half_edge.next].origin] = true;
+        }
+
+        let ids: Vec<usize> = (0..n).filter(|&v| boundary[v]).collect();
+        let points: Vec<Vector3> = ids.iter().map(|&v| self.vertices[v].origin).collect();
+
+        for (i, j) in weld_points(&points, tolerance).into_iter().enumerate() {
+            welded[ids[i]] = ids[j];
+        }
+
+        let mut mesh = HeMesh::default();
+        let mut index_vertices = vec![0; n];
+        let mut order_vertices = vec![];
+
+        for patch in self.patches.iter() {
+            mesh.insert_patch(patch.name());
+        }
+
+        for vertex_id in (0..n).filter(|&v| welded[v] == v) {
+            mesh.insert_vertex(self.vertices[vertex_id].origin);
+            index_vertices[vertex_id] = mesh.n_vertices() - 1;
+            order_vertices.push(vertex_id);
+        }
+
+        for face_id in 0..self.n_faces() {
+            let vertices: Vec<usize> = self
+                .face_vertices(face_id)
+                .iter()
+                .map(|&v| index_vertices[welded[v]])
+                .collect();
+
+            mesh.insert_face(&vertices, self.faces[face_id].patch);
+        }
+
+        mesh.build_links()?;
+
+        for (name, values) in self.vertex_scalars.iter() {
+            let values = order_vertices.iter().map(|&v| values[v]).collect();
+            mesh.vertex_scalars.insert(name.clone(), values);
+        }
+
+        mesh.face_scalars = std::mem::take(&mut self.face_scalars);
+        *self = mesh;
+
+        Ok(())
+    }
+
+    /// Extract the subset of faces into a new mesh. This is not efficient and should
+    /// only be used when explicitly necessary. Scalar attributes are carried over
+    /// to the extracted elements. Vertices are numbered in the order they are
+    /// first referenced by the faces, so extracting the same faces always
+    /// produces the same mesh. The referenced patches keep their relative
+    /// order, so their ids do not depend on the order of the faces.
+    pub fn extract_faces(&self, faces: &[usize]) -> HeMesh {
+        let mut mesh = HeMesh::default();
+        let mut index_vertices = vec![None; self.n_vertices()];
+        let mut order_vertices = Vec::<usize>::new();
+        let mut index_patches = vec![None; self.n_patches()];
+
+        for patch_id in faces.iter().filter_map(|&f| self.faces[f].patch) {
+            index_patches[patch_id] = Some(0);
+        }
+
+        for (patch_id, index) in index_patches.iter_mut().enumerate() {
+            if index.is_some() {
+                *index = Some(mesh.n_patches());
+                mesh.insert_patch(self.patches[patch_id].name());
+            }
+        }
+
+        for &face_id in faces.iter() {
+            let mut vertices = self.face_vertices(face_id);
+
+            for vertex_id in vertices.iter_mut() {
+                *vertex_id = *index_vertices[*vertex_id].get_or_insert_with(|| {
+                    mesh.insert_vertex(self.vertices[*vertex_id].origin);
+                    order_vertices.push(*vertex_id);
+                    mesh.n_vertices() - 1
+                });
+            }
+
+            let patch = self.faces[face_id].patch.and_then(|p| index_patches[p]);
+            mesh.insert_face(&vertices, patch);
+        }
+
+        mesh.build_links().unwrap();
+
+        for (name, values) in self.vertex_scalars.iter() {
+            let values = order_vertices.iter().map(|&v| values[v]).collect();
+            mesh.vertex_scalars.insert(name.clone(), values);
+        }
+
+        for (name, values) in self.face_scalars.iter() {
+            let values = faces.iter().map(|&f| values[f]).collect();
+            mesh.face_scalars.insert(name.clone(), values);
+        }
+
+        mesh
+    }
+
+    /// Extract the subset of patches by index into a new mesh
+    pub fn extract_patches(&self, patches: &[usize]) -> HeMesh {
+        let mut patches = patches.to_vec();
+        patches.sort();
+        patches.dedup();
+
+        let mut faces: Vec<usize> = patches.iter().flat_map(|&p| self.patch_faces(p)).collect();
+
+        faces.sort();
+        self.extract_faces(&faces)
+    }
+
+    /// Extract the subset of patches by name into a new mesh
+    pub fn extract_patch_names(&self, names: &[&str]) -> HeMesh {
+        let mut index = HashSet::<&str>::new();
+        let mut patches = Vec::<usize>::new();
+
+        for name in names.iter() {
+            index.insert(name);
+        }
+
+        for (i, patch) in self.patches.iter().enumerate() {
+            if index.contains(patch.name()) {
+                patches.push(i);
+            }
+        }
+
+        self.extract_patches(&patches)
+    }
+
+    /// Construct a closed tube by sweeping a circle of the radius along a
+    /// polyline. The circle is sampled with the number of segments and
+    /// oriented by a parallel-transport frame, which avoids twisting about
+    /// the path. The ends are capped with polygonal faces. The path must have
+    /// at least two points, the circle at least three segments, and the
+    /// radius must be positive.
+    pub fn sweep_circle(
+        path: &[Vector3],
+        radius: f64,
+        segments: usize,
+    ) -> Result<HeMesh, HeMeshError> {
+        if path.len() < 2 || segments < 3 || radius <= EPSILON {
+            return Err(HeMeshError::InvalidParameter);
+        }
+
+        let n = path.len();
+        let directions: Vec<Vector3> = path.windows(2).map(|w| (w[1] - w[0]).unit()).collect();
+        let tangents: Vec<Vector3> = (0..n)
+            .map(|i| match i {
+                0 => directions[0],
+                i if i == n - 1 => directions[n - 2],
+                i => (directions[i - 1] + directions[i]).unit(),
+            })
+            .collect();
+
+        let mut soup = PolygonSoupMesh::new();
+        let (mut u, _) = tangents[0].orthonormal_basis();
+
+        for (point, tangent) in path.iter().zip(tangents.iter()) {
+            // Transport the frame by removing the component along the tangent
+            u = (u - *tangent * Vector3::dot(&u, tangent)).unit();
+            let v = Vector3::cross(tangent, &u);
+
+            for k in 0..segments {
+                let theta = 2. * std::f64::consts::PI * k as f64 / segments as f64;
+                soup.insert_vertex(*point + (u * theta.cos() + v * theta.sin()) * radius);
+            }
+        }
+
+        for i in 0..n - 1 {
+            for k in 0..segments {
+                let a = i * segments + k;
+                let b = i * segments + (k + 1) % segments;
+                soup.insert_face(&[a, b, b + segments, a + segments], None);
+            }
+        }
+
+        let start: Vec<usize> = (0..segments).rev().collect();
+        let end: Vec<usize> = (0..segments).map(|k| (n - 1) * segments + k).collect();
+        soup.insert_face(&start, None);
+        soup.insert_face(&end, None);
+
+        HeMesh::new(&soup)
+    }
+
+    /// Construct the dual mesh. Each face becomes a vertex at its centroid
+    /// and each vertex becomes a face connecting the centroids of its
+    /// surrounding faces. This requires a closed mesh.
+    pub fn dual(&self) -> Result<HeMesh, HeMeshError> {
+        if !self.is_closed() {
+            return Err(HeMeshError::Open);
+        }
+
+        let mut mesh = HeMesh::default();
+
+        for face_id in 0..self.n_faces() {
+            let vertices = self.face_vertices(face_id);
+            let centroid = vertices
+                .iter()
+                .fold(Vector3::zeros(), |c, &v| c + self.vertices[v].origin);
+
+            mesh.insert_vertex(centroid / vertices.len() as f64);
+        }
+
+        for vertex_id in 0..self.n_vertices() {
+            let faces = self.try_vertex_faces(vertex_id)?;
+            mesh.insert_face(&faces, None);
+        }
+
+        mesh.build_links()?;
+        Ok(mesh)
+    }
+}
+
+/// Find the root of an item in a disjoint set forest, compressing the path
+fn find_root(parent: &mut HashMap<usize, usize>, item: usize) -> usize {
+    let p = parent[&item];
+
+    if p == item {
+        return item;
+    }
+
+    let root = find_root(parent, p);
+    parent.insert(item, root);
+    root
+}
+
+/// Weld coincident points within the tolerance. This returns the index of
+/// the representative point for each point, where a representative point
+/// references itself.
+pub(crate) fn weld_points(points: &[Vector3], tolerance: f64) -> Vec<usize> {
+    let size = tolerance.max(EPSILON);
+    let mut index = HashMap::<(i64, i64, i64), Vec<usize>>::new();
+    let mut welded: Vec<usize> = (0..points.len()).collect();
+
+    for (i, point) in points.iter().enumerate() {
+        let cell = point.quantize(size);
+
+        let mut found = None;
+
+        for di in -1..=1 {
+            for dj in -1..=1 {
+                for dk in -1..=1 {
+                    let key = (cell.0 + di, cell.1 + dj, cell.2 + dk);
+
+                    if found.is_some() {
+                        continue;
+                    }
+
+                    if let Some(candidates) = index.get(&key) {
+                        found = candidates
+                            .iter()
+                            .copied()
+                            .find(|&c| (points[c] - *point).mag() <= tolerance);
+                    }
+                }
+            }
+        }
+
+        match found {
+            Some(target) => welded[i] = target,
+            None => index.entry(cell).or_default().push(i),
+        }
+    }
+
+    welded
+}
+
+/// Merge the scalar attributes of a source mesh into a target mesh. Attributes
+/// missing from either mesh are filled with NaN.
+fn merge_scalars(
+    target: &mut HashMap<String, Vec<f64>>,
+    source: &HashMap<String, Vec<f64>>,
+    n_target: usize,
+    n_source: usize,
+) {
+    for name in source.keys() {
+        target
+            .entry(name.clone())
+            .or_insert_with(|| vec![f64::NAN; n_target]);
+    }
+
+    for (name, values) in target.iter_mut() {
+        match source.get(name) {
+            Some(other) => values.extend(other),
+            None => values.extend(std::iter::repeat_n(f64::NAN, n_source)),
+        }
+    }
+}
+
+// Get the closest point on the face triangles in an octree to a point, or
+// None if the octree is empty. The search box expands until it contains a
+// candidate face.
+fn closest_point(
+    octree: &Octree<Triangle>,
+    index: &[usize],
+    point: Vector3,
+) -> Option<(usize, Vector3, f64)> {
+    if octree.items().is_empty() {
+        return None;
+    }
+
+    let nearest = |items: Vec<usize>| {
+        items
+            .into_iter()
+            .map(|i| {
+                let closest = octree.items()[i].closest_point(&point);
+                (index[i], closest, (closest - point).mag())
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2).then(a.0.cmp(&b.0)))
+    };
+
+    let bounds = octree.node(1).bounds();
+    let outside = Aabb::new(point, Vector3::zeros()).distance(&bounds);
+    let mut radius = outside.max(bounds.halfsize().mag() * 1e-3).max(EPSILON);
+
+    let candidate = loop {
+        let query = Aabb::new(point, Vector3::ones() * radius);
+
+        if let Some(candidate) = nearest(octree.query_aabb(&query)) {
+            break candidate;
+        }
+
+        radius *= 2.;
+    };
+
+    // A closer face may lie outside the box but within the candidate
+    // distance, so query once more with that distance.
+    let query = Aabb::new(point, Vector3::ones() * (candidate.2 + EPSILON));
+    Some(nearest(octree.query_aabb(&query)).unwrap_or(candidate))
+}
+
+impl std::fmt::Display for HeMesh {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// Feature of the mesh surface nearest to a query point
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MeshFeature {
+    Face(usize),
+    Edge(usize, usize),
+    Vertex(usize),
+}
+
+// Priority queue entry for the geodesic distance computation. The ordering
+// is reversed to yield a min-heap from the standard max-heap.
+#[derive(Debug, Clone, Copy)]
+struct GeodesicNode {
+    vertex: usize,
+    distance: f64,
+}
+
+impl PartialEq for GeodesicNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for GeodesicNode {}
+
+impl PartialOrd for GeodesicNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GeodesicNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then_with(|| self.vertex.cmp(&other.vertex))
+    }
+}
+
+/// Get the principal axes of a set of points from the eigenvectors of their
+/// covariance, ordered by decreasing eigenvalue. Without points this is the
+/// standard basis.
+fn point_axes(points: &[Vector3]) -> [Vector3; 3] {
+    if points.is_empty() {
+        return [
+            Vector3::new(1., 0., 0.),
+            Vector3::new(0., 1., 0.),
+            Vector3::new(0., 0., 1.),
+        ];
+    }
+
+    let n = points.len() as f64;
+    let mean = points.iter().fold(Vector3::zeros(), |sum, &p| sum + p) / n;
+    let mut covariance = nalgebra::Matrix3::<f64>::zeros();
+
+    for p in points.iter() {
+        let d = nalgebra::Vector3::new(p[0] - mean[0], p[1] - mean[1], p[2] - mean[2]);
+        covariance += d * d.transpose() / n;
+    }
+
+    let eigen = covariance.symmetric_eigen();
+    let mut order = [0, 1, 2];
+    order.sort_by(|&i, &j| eigen.eigenvalues[j].total_cmp(&eigen.eigenvalues[i]));
+
+    order.map(|i| {
+        let axis = eigen.eigenvectors.column(i);
+        Vector3::new(axis[0], axis[1], axis[2])
+    })
+}
+
+/// Triangulate a face with the fast fan about its first vertex for triangles
+/// and quads and by ear clipping for larger polygons. The triangles index the
+/// face points and follow the face orientation.
+pub(crate) fn triangulate_face(points: &[Vector3]) -> Vec<[usize; 3]> {
+    if points.len() <= 4 {
+        (1..points.len() - 1).map(|i| [0, i, i + 1]).collect()
+    } else {
+        triangulate_polygon(points)
+    }
+}
+
+/// Triangulate a simple planar polygon by ear clipping. The ear of the best
+/// shape is clipped first to avoid slivers. The triangles index the polygon
+/// points and follow the polygon orientation.
+pub(crate) fn triangulate_polygon(points: &[Vector3]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    let normal = (0..n).fold(Vector3::zeros(), |normal, i| {
+        normal + Vector3::cross(&points[i], &points[(i + 1) % n])
+    });
+
+    let is_left = |a: usize, b: usize, p: &Vector3| {
+        let ab = points[b] - points[a];
+        Vector3::dot(&Vector3::cross(&ab, &(*p - points[a])), &normal) >= 0.
+    };
+
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut triangles = vec![];
+
+    while remaining.len() > 3 {
+        let m = remaining.len();
+
+        let ear = (0..m)
+            .filter(|&i| {
+                let (a, b, c) = (
+                    remaining[(i + m - 1) % m],
+                    remaining[i],
+                    remaining[(i + 1) % m],
+                );
+                let ab = points[b] - points[a];
+                let bc = points[c] - points[b];
+
+                Vector3::dot(&Vector3::cross(&ab, &bc), &normal) > 0.
+                    && remaining.iter().all(|&v| {
+                        v == a
+                            || v == b
+                            || v == c
+                            || !(is_left(a, b, &points[v])
+                                && is_left(b, c, &points[v])
+                                && is_left(c, a, &points[v]))
+                    })
+            })
+            .map(|i| {
+                let a = points[remaining[(i + m - 1) % m]];
+                let b = points[remaining[i]];
+                let c = points[remaining[(i + 1) % m]];
+                (i, Triangle::new(a, b, c).quality())
+            })
+            .fold(None, |best: Option<(usize, f64)>, (i, q)| match best {
+                Some((_, best_q)) if best_q >= q => best,
+                _ => Some((i, q)),
+            })
+            .map(|(i, _)| i);
+
+        // A degenerate polygon may have no ear, so clip the first vertex to
+        // guarantee progress
+        let i = ear.unwrap_or(0);
+        triangles.push([
+            remaining[(i + m - 1) % m],
+            remaining[i],
+            remaining[(i + 1) % m],
+        ]);
+        remaining.remove(i);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    triangles
+}
+
+/// Find the unvisited segment connected to a point and return its opposite
+/// endpoint. The segment is marked as visited.
+fn next_contour_point(segments: &[Line], visited: &mut [bool], point: Vector3) -> Option<Vector3> {
+    for (i, segment) in segments.iter().enumerate() {
+        if !visited[i] {
+            if (segment.p() - point).mag() <= EPSILON {
+                visited[i] = true;
+                return Some(segment.q());
+            }
+
+            if (segment.q() - point).mag() <= EPSILON {
+                visited[i] = true;
+                return Some(segment.p());
+            }
+        }
+    }
+
+    None
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct HeVertex {
+    origin: Vector3,
+    half_edge: usize,
+}
+
+impl HeVertex {
+    /// Get the origin
+    pub fn origin(&self) -> Vector3 {
+        self.origin
+    }
+
+    /// Get the half edge originating at the vertex
+    pub fn half_edge(&self) -> usize {
+        self.half_edge
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct HeFace {
+    half_edge: usize,
+    patch: Option<usize>,
+}
+
+impl HeFace {
+    /// Get the starting half edge handle
+    pub fn half_edge(&self) -> usize {
+        self.half_edge
+    }
+
+    /// Get the patch handle
+    pub fn patch(&self) -> Option<usize> {
+        self.patch
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct HeHalfEdge {
+    origin: usize,
+    face: usize,
+    prev: usize,
+    next: usize,
+    twin: Option<usize>,
+}
+
+impl HeHalfEdge {
+    /// Get the origin vertex handle
+    pub fn origin(&self) -> usize {
+        self.origin
+    }
+
+    /// Get the incident face handle
+    pub fn face(&self) -> usize {
+        self.face
+    }
+
+    /// Get the previous half edge handle
+    pub fn prev(&self) -> usize {
+        self.prev
+    }
+
+    /// Get the next half edge handle
+    pub fn next(&self) -> usize {
+        self.next
+    }
+
+    /// Get the twin half edge handle (if it exists)
+    pub fn twin(&self) -> Option<usize> {
+        self.twin
+    }
+
+    /// Check if the half edge is on a boundary
+    pub fn is_boundary(&self) -> bool {
+        self.twin.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HePatch {
+    name: String,
+}
+
+impl HePatch {
+    /// Get a borrowed reference to the name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HeVertexOHalfEdgeIter<'a> {
+    mesh: &'a HeMesh,
+    curr: Option<usize>,
+    init: usize,
+    reverse: bool,
+}
+
+impl<'a> HeVertexOHalfEdgeIter<'a> {
+    pub fn new(mesh: &'a HeMesh, vertex: usize) -> HeVertexOHalfEdgeIter<'a> {
+        HeVertexOHalfEdgeIter {
+            mesh,
+            curr: Some(mesh.vertices[vertex].half_edge),
+            init: mesh.vertices[vertex].half_edge,
+            reverse: false,
+        }
+    }
+
+    /// Get the next outgoing half edge without panicking on an inconsistent
+    /// mesh.
+    pub fn try_next(&mut self) -> Result<Option<usize>, HeMeshError> {
+        let Some(curr) = self.curr else {
+            return Ok(None);
+        };
+
+        if self.reverse {
+            self.curr = self.step_reverse(curr)?;
+            return Ok(Some(curr));
+        }
+
+        let prev = self.mesh.half_edges[curr].prev;
+
+        if let Some(twin) = self.mesh.half_edges[prev].twin {
+            if self.mesh.half_edges[twin].origin != self.mesh.half_edges[self.init].origin {
+                self.curr = None;
+                return Err(HeMeshError::Inconsistent);
+            }
+
+            self.curr = if twin == self.init { None } else { Some(twin) };
+            return Ok(Some(curr));
+        }
+
+        // A boundary was reached, so walk the rest of the fan from the seed
+        // half edge in the opposite direction.
+        self.reverse = true;
+        self.curr = self.step_reverse(self.init)?;
+        Ok(Some(curr))
+    }
+
+    // Step to the next outgoing half edge in the reverse direction. This is
+    // used once a boundary is reached in the forward direction.
+    fn step_reverse(&mut self, curr: usize) -> Result<Option<usize>, HeMeshError> {
+        let Some(twin) = self.mesh.half_edges[curr].twin else {
+            return Ok(None);
+        };
+
+        let next = self.mesh.half_edges[twin].next;
+
+        if self.mesh.half_edges[next].origin != self.mesh.half_edges[self.init].origin {
+            self.curr = None;
+            return Err(HeMeshError::Inconsistent);
+        }
+
+        Ok(Some(next))
+    }
+}
+
+impl<'a> Iterator for HeVertexOHalfEdgeIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.try_next() {
+            Ok(next) => next,
+            Err(_) => panic!("mesh must be oriented"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HeVertexIHalfEdgeIter<'a> {
+    mesh: &'a HeMesh,
+    iter: HeVertexOHalfEdgeIter<'a>,
+    pending: Option<usize>,
+}
+
+impl<'a> HeVertexIHalfEdgeIter<'a> {
+    pub fn new(mesh: &'a HeMesh, vertex: usize) -> HeVertexIHalfEdgeIter<'a> {
+        HeVertexIHalfEdgeIter {
+            mesh,
+            iter: HeVertexOHalfEdgeIter::new(mesh, vertex),
+            pending: None,
+        }
+    }
+}
+
+impl<'a> Iterator for HeVertexIHalfEdgeIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(pending) = self.pending.take() {
+            return Some(pending);
+        }
+
+        for curr in self.iter.by_ref() {
+            // The incoming boundary half edge has no twin and is only
+            // reachable as the previous half edge of its face.
+            let prev = self.mesh.half_edges[curr].prev;
+
+            if self.mesh.half_edges[prev].twin.is_none() {
+                self.pending = Some(prev);
+            }
+
+            if let Some(twin) = self.mesh.half_edges[curr].twin {
+                return Some(twin);
+            }
+
+            if let Some(pending) = self.pending.take() {
+                return Some(pending);
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HeVertexVertexIter<'a> {
+    mesh: &'a HeMesh,
+    iter: HeVertexOHalfEdgeIter<'a>,
+    pending: Option<usize>,
+}
+
+impl<'a> HeVertexVertexIter<'a> {
+    pub fn new(mesh: &'a HeMesh, vertex: usize) -> HeVertexVertexIter<'a> {
+        HeVertexVertexIter {
+            mesh,
+            iter: HeVertexOHalfEdgeIter::new(mesh, vertex),
+            pending: None,
+        }
+    }
+}
+
+impl<'a> Iterator for HeVertexVertexIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(pending) = self.pending.take() {
+            return Some(pending);
+        }
+
+        if let Some(curr) = self.iter.next() {
+            // On a boundary the neighbor across the incoming boundary half
+            // edge is not the destination of any outgoing half edge.
+            let prev = self.mesh.half_edges[curr].prev;
+
+            if self.mesh.half_edges[prev].twin.is_none() {
+                self.pending = Some(self.mesh.half_edges[prev].origin);
+            }
+
+            let next = self.mesh.half_edges[curr].next;
+            return Some(self.mesh.half_edges[next].origin);
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HeVertexFaceIter<'a> {
+    mesh: &'a HeMesh,
+    iter: HeVertexOHalfEdgeIter<'a>,
+}
+
+impl<'a> HeVertexFaceIter<'a> {
+    pub fn new(mesh: &'a HeMesh, vertex: usize) -> HeVertexFaceIter<'a> {
+        HeVertexFaceIter {
+            mesh,
+            iter: HeVertexOHalfEdgeIter::new(mesh, vertex),
+        }
+    }
+}
+
+impl<'a> Iterator for HeVertexFaceIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(curr) = self.iter.next() {
+            return Some(self.mesh.half_edges[curr].face);
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HeFaceHalfEdgeIter<'a> {
+    mesh: &'a HeMesh,
+    curr: usize,
+    init: usize,
+    count: usize,
+}
+
+impl<'a> HeFaceHalfEdgeIter<'a> {
+    pub fn new(mesh: &'a HeMesh, face: usize) -> HeFaceHalfEdgeIter<'a> {
+        HeFaceHalfEdgeIter {
+            mesh,
+            init: mesh.faces[face].half_edge,
+            curr: mesh.faces[face].half_edge,
+            count: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for HeFaceHalfEdgeIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count != 0 && self.curr == self.init {
+            return None;
+        }
+
+        let curr = self.curr;
+        self.curr = self.mesh.half_edges[self.curr].next;
+        self.count += 1;
+
+        Some(curr)
+    }
+}
 
 #[derive(Debug, Clone)]
-pub struct HeVertexIHalfEdgeIter<'a> {
+pub struct HeFaceVertexIter<'a> {
     mesh: &'a HeMesh,
-    iter: HeVertexOHalfEdgeIter<'a>,
+    iter: HeFaceHalfEdgeIter<'a>,
+}
+
+impl<'a> HeFaceVertexIter<'a> {
+    pub fn new(mesh: &'a HeMesh, face: usize) -> HeFaceVertexIter<'a> {
+        HeFaceVertexIter {
+            mesh,
+            iter: HeFaceHalfEdgeIter::new(mesh, face),
+        }
+    }
+}
+
+impl<'a> Iterator for HeFaceVertexIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(index) = self.iter.next() {
+            return Some(self.mesh.half_edges[index].origin);
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HeFaceFaceIter<'a> {
+    mesh: &'a HeMesh,
+    iter: HeFaceHalfEdgeIter<'a>,
+}
+
+impl<'a> HeFaceFaceIter<'a> {
+    pub fn new(mesh: &'a HeMesh, face: usize) -> HeFaceFaceIter<'a> {
+        HeFaceFaceIter {
+            mesh,
+            iter: HeFaceHalfEdgeIter::new(mesh, face),
+        }
+    }
+}
+
+impl<'a> Iterator for HeFaceFaceIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for curr in self.iter.by_ref() {
+            if let Some(twin) = self.mesh.half_edges[curr].twin {
+                return Some(self.mesh.half_edges[twin].face);
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum HeMeshError {
+    NonManifold,
+    AttributeLength,
+    Open,
+    Inconsistent,
+    NonTriangular,
+    Coplanar,
+    IndexOutOfRange,
+    InvalidParameter,
+    InvalidSoup(SoupError),
+}
+
+impl std::fmt::Display for HeMeshError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HeMeshError::NonManifold => write!(f, "non-manifold mesh"),
+            HeMeshError::AttributeLength => write!(f, "attribute length mismatch"),
+            HeMeshError::Open => write!(f, "open mesh"),
+            HeMeshError::Inconsistent => write!(f, "inconsistent mesh"),
+            HeMeshError::NonTriangular => write!(f, "non-triangular mesh"),
+            HeMeshError::Coplanar => write!(f, "coplanar faces"),
+            HeMeshError::IndexOutOfRange => write!(f, "index out of range"),
+            HeMeshError::InvalidParameter => write!(f, "invalid parameter"),
+            HeMeshError::InvalidSoup(e) => write!(f, "invalid polygon soup: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HeMeshError {}
+
+impl From<SoupError> for HeMeshError {
+    fn from(val: SoupError) -> Self {
+        HeMeshError::InvalidSoup(val)
+    }
+}
+
+impl From<HeMeshError> for std::io::Error {
+    fn from(val: HeMeshError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, val.to_string())
+    }
 }
 
-impl<'a> HeVertexIHalfEdgeIter<'a> {
-    pub fn new(mesh: &'a HeMesh, vertex: usize) -> HeVertexIHalfEdgeIter<'a> {
-        HeVertexIHalfEdgeIter {
-            mesh: mesh,
-            iter: HeVertexOHalfEdgeIter::new(mesh, vertex),
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::mesh::fixtures::plane_grid;
+
+    #[test]
+    fn from_arrays() {
+        let vertices = [
+            Vector3::new(-0.5, -0.5, -0.5),
+            Vector3::new(0.5, -0.5, -0.5),
+            Vector3::new(0.5, 0.5, -0.5),
+            Vector3::new(-0.5, 0.5, -0.5),
+            Vector3::new(-0.5, -0.5, 0.5),
+            Vector3::new(0.5, -0.5, 0.5),
+            Vector3::new(0.5, 0.5, 0.5),
+            Vector3::new(-0.5, 0.5, 0.5),
+        ];
+
+        let faces = vec![
+            vec![0, 3, 2, 1],
+            vec![4, 5, 6, 7],
+            vec![0, 1, 5, 4],
+            vec![2, 3, 7, 6],
+            vec![0, 4, 7, 3],
+            vec![1, 2, 6, 5],
+        ];
+
+        let patches = [0, 1, 0, 0, 0, 0];
+        let mesh = HeMesh::from_arrays(&vertices, &faces, Some(&patches)).unwrap();
+
+        assert_eq!(mesh.n_vertices(), 8);
+        assert_eq!(mesh.n_faces(), 6);
+        assert_eq!(mesh.n_patches(), 2);
+        assert_eq!(mesh.patches()[1].name(), "patch1");
+        assert_eq!(mesh.face(1).patch(), Some(1));
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 1.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn to_soup() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let soup = mesh.to_soup();
+
+        assert_eq!(soup.n_vertices(), mesh.n_vertices());
+        assert_eq!(soup.n_faces(), mesh.n_faces());
+        assert_eq!(soup.n_patches(), mesh.n_patches());
+        assert_eq!(soup.patch(1), "back");
+        assert_eq!(soup.face(2), (&[6, 7, 5][..], Some(1)));
+
+        let other = HeMesh::new(&soup).unwrap();
+
+        assert_eq!(other.n_vertices(), mesh.n_vertices());
+        assert_eq!(other.n_faces(), mesh.n_faces());
+        assert_eq!(other.n_half_edges(), mesh.n_half_edges());
+        assert_eq!(other.n_patches(), mesh.n_patches());
+    }
+
+    #[test]
+    fn from_arrays_invalid() {
+        let vertices = [Vector3::zeros(), Vector3::ones(), Vector3::new(1., 0., 0.)];
+        let faces = vec![vec![0, 1, 3]];
+
+        assert!(HeMesh::from_arrays(&vertices, &faces, None)
+            .is_err_and(|e| e.to_string() == "index out of range"));
+
+        let faces = vec![vec![0, 1, 2]];
+
+        assert!(HeMesh::from_arrays(&vertices, &faces, Some(&[0, 0]))
+            .is_err_and(|e| e.to_string() == "attribute length mismatch"));
+    }
+
+    #[test]
+    fn import_obj() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert_eq!(mesh.n_vertices(), 8);
+        assert_eq!(mesh.n_faces(), 12);
+        assert_eq!(mesh.n_half_edges(), 36);
+        assert_eq!(mesh.n_patches(), 0);
+    }
+
+    #[test]
+    fn import_obj_gzip() {
+        let path = "tests/fixtures/box.obj.gz";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert_eq!(mesh.n_vertices(), 8);
+        assert_eq!(mesh.n_faces(), 12);
+        assert_eq!(mesh.n_half_edges(), 36);
+        assert_eq!(mesh.n_patches(), 0);
+    }
+
+    #[test]
+    fn import_obj_patches() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert_eq!(mesh.n_patches(), 6);
+        assert_eq!(mesh.faces[0].patch, Some(0));
+        assert_eq!(mesh.faces[1].patch, Some(1));
+        assert_eq!(mesh.faces[2].patch, Some(1));
+        assert_eq!(mesh.faces[3].patch, Some(2));
+        assert_eq!(mesh.faces[4].patch, Some(3));
+        assert_eq!(mesh.faces[5].patch, Some(4));
+        assert_eq!(mesh.faces[6].patch, Some(5));
+    }
+
+    #[test]
+    fn import_obj_lenient() {
+        let path = "tests/fixtures/box.invalid.obj";
+        let (mesh, warnings) = HeMesh::import_obj_lenient(path).unwrap();
+
+        assert_eq!(mesh.n_vertices(), 8);
+        assert_eq!(mesh.n_faces(), 12);
+        assert!(mesh.is_closed());
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(warnings[1], "invalid face: 1 2");
+    }
+
+    #[test]
+    fn import_obj_nonmanifold() {
+        let path = "tests/fixtures/box.nonmanifold.obj";
+        let result = HeMesh::import_obj(path);
+
+        assert!(result.is_err_and(|e| e.to_string() == "non-manifold mesh"));
+    }
+
+    #[test]
+    fn face_half_edge_iter() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeFaceHalfEdgeIter::new(&mesh, 0);
+
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn face_vertex_iter() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeFaceVertexIter::new(&mesh, 0);
+
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn face_face_iter() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeFaceFaceIter::new(&mesh, 0);
+
+        assert_eq!(iter.next(), Some(4));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(8));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn face_adjacency() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let (offsets, neighbors) = mesh.face_adjacency();
+
+        assert_eq!(offsets.len(), mesh.n_faces() + 1);
+        assert_eq!(neighbors.len(), 36);
+
+        for i in 0..mesh.n_faces() {
+            let expected = mesh.face_neighbors(i);
+            assert_eq!(&neighbors[offsets[i]..offsets[i + 1]], &expected[..]);
+        }
+    }
+
+    #[test]
+    fn geodesic_from() {
+        let mut mesh = plane_grid(4, 1., false);
+
+        // Disconnected triangle
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(10., 0., 0.));
+        soup.insert_vertex(Vector3::new(11., 0., 0.));
+        soup.insert_vertex(Vector3::new(11., 1., 0.));
+        soup.insert_face(&[0, 1, 2], None);
+        mesh.merge(&HeMesh::new(&soup).unwrap());
+
+        let distances = mesh.geodesic_from(0);
+
+        assert_eq!(distances[0], 0.);
+        assert!((distances[3] - 3.).abs() < EPSILON);
+        assert!((distances[15] - 3. * 2f64.sqrt()).abs() < EPSILON);
+        assert!((distances[14] - (2. * 2f64.sqrt() + 1.)).abs() < EPSILON);
+        assert!(distances[16..].iter().all(|d| d.is_infinite()));
+    }
+
+    #[test]
+    fn vertex_outgoing_half_edge_iter() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeVertexOHalfEdgeIter::new(&mesh, 0);
+
+        assert_eq!(iter.next(), Some(24));
+        assert_eq!(iter.next(), Some(12));
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn vertex_outgoing_half_edge_iter_open() {
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut half_edges = HeVertexOHalfEdgeIter::new(&mesh, 3).collect::<Vec<usize>>();
+        half_edges.sort();
+
+        assert_eq!(half_edges.len(), 4);
+        assert!(half_edges.iter().all(|&i| mesh.half_edges[i].origin == 3));
+        half_edges.dedup();
+        assert_eq!(half_edges.len(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn vertex_outgoing_half_edge_iter_inconsistent() {
+        let path = "tests/fixtures/box.inconsistent.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeVertexOHalfEdgeIter::new(&mesh, 1);
+
+        iter.next();
+        iter.next();
+    }
+
+    #[test]
+    fn vertex_incoming_half_edge_iter() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeVertexIHalfEdgeIter::new(&mesh, 0);
+
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(26));
+        assert_eq!(iter.next(), Some(14));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn vertex_incoming_half_edge_iter_open() {
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut half_edges = HeVertexIHalfEdgeIter::new(&mesh, 3).collect::<Vec<usize>>();
+        half_edges.sort();
+
+        assert_eq!(half_edges.len(), 4);
+        assert!(half_edges.iter().all(|&i| {
+            let next = mesh.half_edges[i].next;
+            mesh.half_edges[next].origin == 3
+        }));
+        half_edges.dedup();
+        assert_eq!(half_edges.len(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn vertex_incoming_half_edge_iter_inconsistent() {
+        let path = "tests/fixtures/box.inconsistent.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeVertexOHalfEdgeIter::new(&mesh, 1);
+
+        iter.next();
+        iter.next();
+    }
+
+    #[test]
+    fn vertex_vertex_iter() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeVertexVertexIter::new(&mesh, 6);
+
+        assert_eq!(iter.next(), Some(4));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), Some(7));
+        assert_eq!(iter.next(), Some(5));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn vertex_vertex_iter_open() {
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut neighbors = HeVertexVertexIter::new(&mesh, 3).collect::<Vec<usize>>();
+        neighbors.sort();
+
+        assert_eq!(neighbors, vec![1, 2, 5, 6, 7]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn vertex_vertex_iter_inconsistent() {
+        let path = "tests/fixtures/box.inconsistent.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeVertexVertexIter::new(&mesh, 1);
+
+        iter.next();
+        iter.next();
+    }
+
+    #[test]
+    fn vertex_face_iter() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeVertexFaceIter::new(&mesh, 6);
+
+        assert_eq!(iter.next(), Some(9));
+        assert_eq!(iter.next(), Some(6));
+        assert_eq!(iter.next(), Some(7));
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn vertex_face_iter_open() {
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut faces = HeVertexFaceIter::new(&mesh, 3).collect::<Vec<usize>>();
+        faces.sort();
+
+        assert_eq!(faces, vec![1, 6, 9, 10]);
+    }
+
+    #[test]
+    fn vertex_neighbors_open_interior() {
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut neighbors = mesh.vertex_neighbors(0);
+        neighbors.sort();
+
+        assert_eq!(neighbors, vec![1, 2, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn vertex_face_iter_inconsistent() {
+        let path = "tests/fixtures/box.inconsistent.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut iter = HeVertexFaceIter::new(&mesh, 1);
+
+        iter.next();
+        iter.next();
+    }
+
+    #[test]
+    fn try_vertex_neighbors() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let neighbors = mesh.try_vertex_neighbors(6).unwrap();
+
+        assert_eq!(neighbors, mesh.vertex_neighbors(6));
+    }
+
+    #[test]
+    fn try_vertex_neighbors_open() {
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut neighbors = mesh.try_vertex_neighbors(3).unwrap();
+        neighbors.sort();
+
+        assert_eq!(neighbors, vec![1, 2, 5, 6, 7]);
+    }
+
+    #[test]
+    fn try_vertex_neighbors_inconsistent() {
+        let path = "tests/fixtures/box.inconsistent.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let result = mesh.try_vertex_neighbors(1);
+
+        assert!(result.is_err_and(|e| e.to_string() == "inconsistent mesh"));
+    }
+
+    #[test]
+    fn try_vertex_faces_inconsistent() {
+        let path = "tests/fixtures/box.inconsistent.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let result = mesh.try_vertex_faces(1);
+
+        assert!(result.is_err_and(|e| e.to_string() == "inconsistent mesh"));
+    }
+
+    #[test]
+    fn bounds_first_vertex_max() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(1., 1., 1.));
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(0.5, 0., 0.5));
+        soup.insert_face(&[0, 1, 2], None);
+
+        let mesh = HeMesh::new(&soup).unwrap();
+        let bounds = mesh.bounds();
+
+        assert_eq!(bounds.min(), Vector3::new(0., 0., 0.));
+        assert_eq!(bounds.max(), Vector3::new(1., 1., 1.));
+    }
+
+    #[test]
+    fn can_collapse_edge() {
+        let mesh = plane_grid(3, 1., false);
+        let find = |a: usize, b: usize| {
+            (0..mesh.n_half_edges())
+                .find(|&h| {
+                    let half_edge = mesh.half_edge(h);
+                    half_edge.origin() == a && mesh.half_edge(half_edge.next()).origin() == b
+                })
+                .unwrap()
+        };
+
+        // Interior edges from the center vertex
+        assert!(mesh.can_collapse_edge(find(4, 1)));
+        assert!(mesh.can_collapse_edge(find(0, 4)));
+
+        // Interior edge joining two boundary vertices
+        assert!(!mesh.can_collapse_edge(find(1, 5)));
+
+        // Boundary edge
+        assert!(mesh.can_collapse_edge(find(0, 1)));
+    }
+
+    #[test]
+    fn transformed_bounds() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let m = Matrix4::new_scaling(2.).append_translation(&[1., 0., 0.].into());
+        let bounds = mesh.transformed_bounds(&m);
+
+        assert_eq!(bounds.min(), Vector3::new(0., -1., -1.));
+        assert_eq!(bounds.max(), Vector3::new(2., 1., 1.));
+    }
+
+    #[test]
+    fn transform() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let volume = mesh.volume();
+        let m = Matrix4::new_scaling(2.).append_translation(&[1., 0., 0.].into());
+        mesh.transform(&m);
+
+        assert_eq!(mesh.bounds().min(), Vector3::new(0., -1., -1.));
+        assert_eq!(mesh.bounds().max(), Vector3::new(2., 1., 1.));
+        assert!((mesh.volume() - 8. * volume).abs() < 1e-12);
+    }
+
+    #[test]
+    fn transform_mirror() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let volume = mesh.volume();
+        let m = Matrix4::new_nonuniform_scaling(&[-1., 1., 1.].into());
+        mesh.transform(&m);
+
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - volume).abs() < 1e-12);
+    }
+
+    #[test]
+    fn summary() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let summary = mesh.summary();
+
+        assert!(summary.contains("vertices: 8"));
+        assert!(summary.contains("faces: 12"));
+        assert!(summary.contains("half edges: 36"));
+        assert!(summary.contains("closed: true"));
+        assert!(summary.contains("components: 1"));
+        assert_eq!(mesh.to_string(), summary);
+    }
+
+    #[test]
+    fn flood_select() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert_eq!(mesh.flood_select(0, 0.1), vec![0, 1]);
+        assert_eq!(mesh.flood_select(0, 2.).len(), 12);
+    }
+
+    #[test]
+    fn flood_select_quads() {
+        let vertices: Vec<Vector3> = (0..8)
+            .map(|i| Vector3::new((i >> 2) as f64, ((i >> 1) & 1) as f64, (i & 1) as f64))
+            .collect();
+        let faces = vec![
+            vec![0, 1, 3, 2],
+            vec![4, 6, 7, 5],
+            vec![0, 4, 5, 1],
+            vec![2, 3, 7, 6],
+            vec![0, 2, 6, 4],
+            vec![1, 5, 7, 3],
+        ];
+        let mesh = HeMesh::from_arrays(&vertices, &faces, None).unwrap();
+
+        for seed in 0..6 {
+            assert_eq!(mesh.flood_select(seed, 0.1), vec![seed]);
+        }
+    }
+
+    #[test]
+    fn dihedral_angle() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!(mesh.dihedral_angle(0, 1).abs() < EPSILON);
+        assert!((mesh.dihedral_angle(0, 4) - std::f64::consts::FRAC_PI_2).abs() < EPSILON);
+    }
+
+    #[test]
+    fn split_by_plane() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let plane = Plane::new(Vector3::new(0., 0., 0.1), Vector3::new(0., 0., 1.));
+        let (positive, negative) = mesh.split_by_plane(&plane, false).unwrap();
+
+        assert!(!positive.is_closed());
+        assert!(!negative.is_closed());
+        assert!((positive.bounds().min()[2] - 0.1).abs() < EPSILON);
+        assert!((negative.bounds().max()[2] - 0.1).abs() < EPSILON);
+    }
+
+    #[test]
+    fn split_by_plane_capped() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let plane = Plane::new(Vector3::new(0., 0., 0.1), Vector3::new(1., 0., 2.));
+        let (positive, negative) = mesh.split_by_plane(&plane, true).unwrap();
+
+        for part in [&positive, &negative] {
+            assert!(part.is_closed());
+            assert!(part.is_consistent());
+            assert!(part.volume() > 0.);
+            assert_eq!(part.patches().last().unwrap().name(), "cap");
+        }
+
+        assert!((positive.volume() + negative.volume() - mesh.volume()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn split_by_plane_scalars() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let z = mesh.vertices.iter().map(|v| v.origin.z()).collect();
+        mesh.set_vertex_scalar("z", z).unwrap();
+        mesh.set_face_scalar("id", vec![1.; 12]).unwrap();
+
+        let plane = Plane::new(Vector3::new(0., 0., 0.1), Vector3::new(1., 0., 2.));
+        let (positive, _) = mesh.split_by_plane(&plane, true).unwrap();
+        let z = positive.vertex_scalar("z").unwrap();
+        let ids = positive.face_scalar("id").unwrap();
+        let cap = positive.n_patches() - 1;
+
+        for (v, vertex) in positive.vertices().iter().enumerate() {
+            assert!((z[v] - vertex.origin().z()).abs() < 1e-12);
+        }
+
+        for (f, face) in positive.faces().iter().enumerate() {
+            assert_eq!(face.patch() == Some(cap), ids[f].is_nan());
+        }
+    }
+
+    #[test]
+    fn triangulate_polygon_concave() {
+        let points = vec![
+            Vector3::new(0., 0., 0.),
+            Vector3::new(2., 0., 0.),
+            Vector3::new(2., 2., 0.),
+            Vector3::new(1., 1., 0.),
+            Vector3::new(0., 2., 0.),
+        ];
+        let triangles = triangulate_polygon(&points);
+        let area: f64 = triangles
+            .iter()
+            .map(|&[a, b, c]| Triangle::new(points[a], points[b], points[c]).area())
+            .sum();
+
+        assert_eq!(triangles.len(), 3);
+        assert!((area - 3.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn triangulate_polygon_quality() {
+        let points: Vec<Vector3> = (0..8)
+            .map(|i| {
+                let t = std::f64::consts::PI * i as f64 / 4.;
+                Vector3::new(10. * t.cos(), t.sin(), 0.)
+            })
+            .collect();
+
+        let worst = |triangles: &[[usize; 3]]| {
+            triangles
+                .iter()
+                .map(|&[a, b, c]| Triangle::new(points[a], points[b], points[c]).quality())
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        let fan: Vec<[usize; 3]> = (1..7).map(|i| [0, i, i + 1]).collect();
+        let triangles = triangulate_polygon(&points);
+
+        assert_eq!(triangles.len(), 6);
+        assert!(worst(&triangles) > worst(&fan));
+    }
+
+    #[test]
+    fn face_triangles_nonconvex() {
+        // An L-shaped face starting at a vertex that cannot see the whole
+        // polygon, so a fan about it would invert a triangle
+        let points = vec![
+            Vector3::new(2., 1., 0.),
+            Vector3::new(1., 1., 0.),
+            Vector3::new(1., 2., 0.),
+            Vector3::new(0., 2., 0.),
+            Vector3::new(0., 0., 0.),
+            Vector3::new(2., 0., 0.),
+        ];
+
+        let faces = vec![(0..6).collect()];
+        let mesh = HeMesh::from_arrays(&points, &faces, None).unwrap();
+        let triangles = mesh.face_triangles(0);
+        let area: f64 = triangles.iter().map(|t| t.area()).sum();
+
+        assert_eq!(triangles.len(), 4);
+        assert!((area - 3.).abs() < EPSILON);
+        assert!(triangles.iter().all(|t| t.normal()[2] > 0.));
+    }
+
+    #[test]
+    fn orient_all_outward() {
+        let mut mesh = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+        let mut inverted = HeMesh::import_obj("tests/fixtures/box.inverted.obj").unwrap();
+        let mut open = HeMesh::import_obj("tests/fixtures/box.open.obj").unwrap();
+        inverted.transform(&Matrix4::new_translation(&[2., 0., 0.].into()));
+        open.transform(&Matrix4::new_translation(&[4., 0., 0.].into()));
+        open.flip_face(0);
+
+        mesh.merge(&inverted);
+        mesh.merge(&open);
+        mesh.orient_all_outward();
+
+        let volumes = mesh.component_volumes();
+
+        assert!(mesh.is_consistent());
+        assert_eq!(volumes.len(), 3);
+        assert!((volumes[0] - 1.).abs() < 1e-12);
+        assert!((volumes[1] - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn new_invalid_soup() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 0., 0.));
+        soup.insert_vertex(Vector3::new(0., 1., 0.));
+        soup.insert_face(&[0, 1, 5], None);
+
+        assert!(HeMesh::new(&soup).is_err_and(
+            |e| e.to_string() == "invalid polygon soup: face 0 vertex index out of range"
+        ));
+    }
+
+    #[test]
+    fn faces_as_triangles() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let items: Vec<(usize, Triangle, Option<usize>)> = mesh.faces_as_triangles().collect();
+
+        let faces: Vec<usize> = items.iter().map(|(f, _, _)| *f).collect();
+        let patches: Vec<Option<usize>> = items.iter().map(|(_, _, p)| *p).collect();
+
+        assert_eq!(faces, vec![0, 0, 1, 2, 3, 3, 4, 4, 5, 5, 6, 6]);
+        assert_eq!(
+            patches,
+            [0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5].map(Some).to_vec()
+        );
+        assert!(items
+            .iter()
+            .zip(mesh.triangles())
+            .all(|((_, t, _), u)| t.vertices() == u.vertices()));
+    }
+
+    #[test]
+    fn rename_patch() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.rename_patch(0, "inlet");
+
+        assert_eq!(mesh.patch(0).name(), "inlet");
+        assert_eq!(mesh.patch_faces(0).count(), 1);
+    }
+
+    #[test]
+    fn merge_patches() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.merge_patches(&[4, 1], "walls");
+
+        let names: Vec<&str> = mesh.patches().iter().map(|p| p.name()).collect();
+        let counts: Vec<usize> = (0..mesh.n_patches())
+            .map(|p| mesh.patch_faces(p).count())
+            .collect();
+
+        assert_eq!(names, vec!["front", "walls", "left", "right", "bottom"]);
+        assert_eq!(counts, vec![1, 3, 1, 1, 1]);
+        assert!(mesh.faces.iter().all(|f| f.patch.is_some()));
+    }
+
+    #[test]
+    fn remove_patch_names() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.remove_patch_names(&["back", "top", "unknown"])
+            .unwrap();
+
+        let names: Vec<&str> = mesh.patches().iter().map(|p| p.name()).collect();
+
+        assert_eq!(names, vec!["front", "left", "right", "bottom"]);
+        assert_eq!(mesh.n_faces(), 4);
+        assert_eq!(mesh.patch_faces(3).count(), 1);
+    }
+
+    #[test]
+    fn orient_toward() {
+        let path = "tests/fixtures/box.inverted.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.orient();
+
+        assert!(mesh.volume() < 0.);
+
+        mesh.orient_toward(Vector3::new(3., 2., 1.));
+
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 1.).abs() < 1e-12);
+
+        for face in 0..mesh.n_faces() {
+            let normal = mesh.face_normal(face);
+            let center = mesh.face_triangles(face)[0].center();
+
+            assert!(Vector3::dot(&normal, &center) > 0.);
+        }
+
+        mesh.orient_toward(Vector3::new(-2., 5., 0.5));
+
+        assert!((mesh.volume() - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn patches_from_components() {
+        let mut mesh = HeMesh::import_obj("tests/fixtures/box.groups.obj").unwrap();
+        let mut other = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+        other.transform(&Matrix4::new_translation(&[2., 0., 0.].into()));
+        mesh.merge(&other);
+        mesh.patches_from_components();
+
+        let names: Vec<&str> = mesh.patches().iter().map(|p| p.name()).collect();
+
+        assert_eq!(names, vec!["patch0", "patch1"]);
+        assert_eq!(mesh.patch_faces(0).count(), 7);
+        assert_eq!(mesh.patch_faces(1).count(), 12);
+    }
+
+    #[test]
+    fn clear_patches() {
+        let mut mesh = HeMesh::import_obj("tests/fixtures/box.groups.obj").unwrap();
+        mesh.clear_patches();
+
+        assert_eq!(mesh.n_patches(), 0);
+        assert!(mesh.faces.iter().all(|f| f.patch.is_none()));
+    }
+
+    #[test]
+    fn principal_axes() {
+        let vertices = vec![
+            Vector3::new(-2., 0., 0.),
+            Vector3::new(2., 0., 0.),
+            Vector3::new(0., 1., 0.),
+            Vector3::new(0., -1., 0.),
+        ];
+        let faces = vec![vec![0, 3, 1], vec![0, 1, 2]];
+        let mesh = HeMesh::from_arrays(&vertices, &faces, None).unwrap();
+        let axes = mesh.principal_axes();
+
+        assert_eq!(axes.len(), 3);
+        assert!((axes[0][0].abs() - 1.).abs() < EPSILON);
+        assert!((axes[1][1].abs() - 1.).abs() < EPSILON);
+        assert!((axes[2][2].abs() - 1.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn project_to_plane() {
+        let normal = Vector3::new(1., 2., 2.);
+        let origin = Vector3::new(1., 0., 0.);
+        let (a, b) = normal.orthonormal_basis();
+
+        // A flat patch in a tilted plane offset from the plane origin
+        let points: Vec<Vector3> = [(0., 0.), (2., 0.), (2., 1.), (0., 3.)]
+            .iter()
+            .map(|&(x, y)| origin + normal * 0.1 + a * x + b * y)
+            .collect();
+
+        let faces = vec![vec![0, 1, 2], vec![0, 2, 3]];
+        let mesh = HeMesh::from_arrays(&points, &faces, None).unwrap();
+        let plane = Plane::new(origin, normal);
+        let uv = mesh.project_to_plane(&plane);
+
+        let area: f64 = (0..mesh.n_faces())
+            .map(|f| {
+                let [p, q, r] = [0, 1, 2].map(|i| uv[mesh.face_vertices(f)[i]]);
+                0.5 * ((q.0 - p.0) * (r.1 - p.1) - (r.0 - p.0) * (q.1 - p.1))
+            })
+            .sum();
+
+        assert!((area - mesh.face_areas().iter().sum::<f64>()).abs() < 1e-12);
+        assert!((area - 4.).abs() < 1e-12);
+        assert!((uv[2].0 - 2.).abs() < 1e-12);
+        assert!((uv[2].1 - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn planar_uv() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let front = mesh.extract_patch_names(&["front"]);
+        let uv = front.planar_uv();
+
+        assert_eq!(uv.len(), 4);
+
+        for (u, v) in uv.iter() {
+            assert!((u.abs() < EPSILON || (u - 1.).abs() < EPSILON));
+            assert!((v.abs() < EPSILON || (v - 1.).abs() < EPSILON));
+        }
+
+        let corners: HashSet<(i64, i64)> = uv
+            .iter()
+            .map(|(u, v)| (u.round() as i64, v.round() as i64))
+            .collect();
+
+        assert_eq!(corners.len(), 4);
+        assert_eq!(mesh.planar_uv().len(), mesh.n_vertices());
+    }
+
+    #[test]
+    fn face_normal_degenerate() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 1., 1.));
+        soup.insert_vertex(Vector3::new(2., 2., 2.));
+        soup.insert_face(&[0, 1, 2], None);
+
+        let mesh = HeMesh::new(&soup).unwrap();
+
+        assert_eq!(mesh.face_normal(0), Vector3::zeros());
+    }
+
+    #[test]
+    fn face_normal_small() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.transform(&Matrix4::new_scaling(1e-4));
+
+        for i in 0..mesh.n_faces() {
+            assert!((mesh.face_normal(i).mag() - 1.).abs() < EPSILON);
+        }
+
+        assert_eq!(mesh.feature_edges(0.5).len(), 12);
+    }
+
+    #[test]
+    fn triangle_qualities() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 0., 0.));
+        soup.insert_vertex(Vector3::new(0.5, 3f64.sqrt() / 2., 0.));
+        soup.insert_vertex(Vector3::new(0.5, -1e-3, 0.));
+        soup.insert_face(&[0, 1, 2], None);
+        soup.insert_face(&[0, 3, 1], None);
+
+        let mesh = HeMesh::new(&soup).unwrap();
+        let qualities = mesh.triangle_qualities().unwrap();
+
+        assert!((qualities[0] - 1.).abs() < EPSILON);
+        assert!(qualities[1] < 1e-2);
+        assert!((mesh.min_angle(0) - std::f64::consts::FRAC_PI_3).abs() < 1e-6);
+        assert!(mesh.min_angle(1) < 1e-2);
+    }
+
+    #[test]
+    fn triangle_qualities_small() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(0.005, 0., 0.));
+        soup.insert_vertex(Vector3::new(0.0025, 0.005 * 3f64.sqrt() / 2., 0.));
+        soup.insert_face(&[0, 1, 2], None);
+
+        let mesh = HeMesh::new(&soup).unwrap();
+        let qualities = mesh.triangle_qualities().unwrap();
+
+        assert!((qualities[0] - 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn normalize() {
+        let mut mesh = import_box_offset(Vector3::new(3., -2., 5.));
+        mesh.vertices[0].origin[0] -= 1.;
+        let original = mesh.vertices.clone();
+
+        let (offset, scale) = mesh.normalize();
+        let bounds = mesh.bounds();
+
+        assert!(bounds.center().mag() < EPSILON);
+        assert!((bounds.halfsize().x() - 0.5).abs() < EPSILON);
+        assert!(bounds.min().x() >= -0.5 - EPSILON);
+        assert!(bounds.max().x() <= 0.5 + EPSILON);
+        assert!(bounds.halfsize().y() <= 0.5);
+        assert!(bounds.halfsize().z() <= 0.5);
+
+        for (vertex, expected) in mesh.vertices.iter().zip(original.iter()) {
+            let restored = vertex.origin / scale - offset;
+            assert!((restored - expected.origin).mag() < EPSILON);
+        }
+    }
+
+    #[test]
+    fn raycast_culled() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let origin = Vector3::new(0.1, 0.2, 0.);
+        let ray = Ray::new(origin, Vector3::new(1., 0., 0.));
+
+        // From inside, the outward facing faces are backfaces
+        assert!(mesh.raycast_culled(&ray, true).is_none());
+
+        let (face, t) = mesh.raycast_culled(&ray, false).unwrap();
+        assert!((t - 0.4).abs() < EPSILON);
+        assert!(mesh.face_normal(face).x() > 0.);
+
+        // From outside, the nearest front face is hit with culling
+        let origin = Vector3::new(-2., 0.2, 0.1);
+        let ray = Ray::new(origin, Vector3::new(1., 0., 0.));
+        let (face, t) = mesh.raycast_culled(&ray, true).unwrap();
+
+        assert!((t - 1.5).abs() < EPSILON);
+        assert!(mesh.face_normal(face).x() < 0.);
+    }
+
+    #[test]
+    fn raycast_all() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let origin = Vector3::new(-2., 0.2, 0.1);
+        let ray = Ray::new(origin, Vector3::new(1., 0., 0.));
+        let hits = mesh.raycast_all(&ray);
+
+        assert_eq!(hits.len(), 2);
+        assert!((hits[0].1 - 1.5).abs() < EPSILON);
+        assert!((hits[1].1 - 2.5).abs() < EPSILON);
+        assert!((hits[0].2 - Vector3::new(-0.5, 0.2, 0.1)).mag() < EPSILON);
+        assert!((hits[1].2 - Vector3::new(0.5, 0.2, 0.1)).mag() < EPSILON);
+        assert!(mesh.face_normal(hits[0].0).x() < 0.);
+        assert!(mesh.face_normal(hits[1].0).x() > 0.);
+
+        let ray = Ray::new(origin, Vector3::new(-1., 0., 0.));
+        assert!(mesh.raycast_all(&ray).is_empty());
+    }
+
+    #[test]
+    fn feature_lines() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let lines = mesh.feature_lines(std::f64::consts::FRAC_PI_4);
+
+        // Each corner joins three feature edges so every edge is split into
+        // its own polyline.
+        let mut edges: Vec<(usize, usize)> = lines
+            .iter()
+            .map(|line| {
+                assert_eq!(line.len(), 2);
+                (line[0].min(line[1]), line[0].max(line[1]))
+            })
+            .collect();
+
+        edges.sort();
+        edges.dedup();
+
+        assert_eq!(edges.len(), 12);
+
+        for (a, b) in edges {
+            let p = mesh.vertex(a).origin();
+            let q = mesh.vertex(b).origin();
+            assert!(((q - p).mag() - 1.).abs() < EPSILON);
         }
     }
-}
 
-impl<'a> Iterator for HeVertexIHalfEdgeIter<'a> {
-    type Item = usize;
+    #[test]
+    fn export_feature_obj() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        let path = std::env::temp_dir().join("meshr.export_feature_obj.obj");
+        let path = path.to_str().unwrap();
+        mesh.export_feature_obj(path, std::f64::consts::FRAC_PI_4)
+            .unwrap();
+
+        let data = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let n_vertices = data.lines().filter(|l| l.starts_with("v ")).count();
+        let lines: Vec<Vec<usize>> = data
+            .lines()
+            .filter_map(|l| l.strip_prefix("l "))
+            .map(|l| {
+                l.split_whitespace()
+                    .map(|v| v.parse::<usize>().unwrap() - 1)
+                    .collect()
+            })
+            .collect();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(curr) = self.iter.next() {
-            return self.mesh.half_edges[curr].twin;
+        assert_eq!(n_vertices, mesh.n_vertices());
+        assert!(!data.lines().any(|l| l.starts_with("f ")));
+        assert_eq!(lines, mesh.feature_lines(std::f64::consts::FRAC_PI_4));
+    }
+
+    #[test]
+    fn export_boundary_obj() {
+        let mesh = HeMesh::import_obj("tests/fixtures/box.open.obj").unwrap();
+
+        let path = std::env::temp_dir().join("meshr.export_boundary_obj.obj");
+        let path = path.to_str().unwrap();
+        mesh.export_boundary_obj(path).unwrap();
+
+        let data = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let lines: Vec<Vec<usize>> = data
+            .lines()
+            .filter_map(|l| l.strip_prefix("l "))
+            .map(|l| {
+                l.split_whitespace()
+                    .map(|v| v.parse::<usize>().unwrap() - 1)
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(lines, mesh.boundary_lines());
+        assert_eq!(lines.len(), 1);
+        assert!(!data.lines().any(|l| l.starts_with("f ")));
+    }
+
+    #[test]
+    fn feature_lines_loop() {
+        let n = 8;
+        let mut soup = PolygonSoupMesh::new();
+
+        for i in 0..n {
+            let t = 2. * std::f64::consts::PI * i as f64 / n as f64;
+            soup.insert_vertex(Vector3::new(t.cos(), t.sin(), 0.));
+            soup.insert_vertex(Vector3::new(t.cos(), t.sin(), 1.));
         }
 
-        None
+        for i in 0..n {
+            let j = (i + 1) % n;
+            soup.insert_face(&[2 * i, 2 * j, 2 * j + 1, 2 * i + 1], None);
+        }
+
+        let bottom: Vec<usize> = (0..n).rev().map(|i| 2 * i).collect();
+        let top: Vec<usize> = (0..n).map(|i| 2 * i + 1).collect();
+        soup.insert_face(&bottom, None);
+        soup.insert_face(&top, None);
+
+        // Only the rims of the octagonal prism exceed the threshold
+        let mesh = HeMesh::new(&soup).unwrap();
+        let lines = mesh.feature_lines(1.);
+
+        assert_eq!(lines.len(), 2);
+
+        for line in lines.iter() {
+            assert_eq!(line.len(), n + 1);
+            assert_eq!(line.first(), line.last());
+        }
+
+        assert!(lines[0].iter().all(|v| v % 2 == 0));
+        assert!(lines[1].iter().all(|v| v % 2 == 1));
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct HeVertexVertexIter<'a> {
-    mesh: &'a HeMesh,
-    iter: HeVertexOHalfEdgeIter<'a>,
-}
+    #[test]
+    fn inconsistent_faces() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        assert!(mesh.inconsistent_faces().is_empty());
 
-impl<'a> HeVertexVertexIter<'a> {
-    pub fn new(mesh: &'a HeMesh, vertex: usize) -> HeVertexVertexIter<'a> {
-        HeVertexVertexIter {
-            mesh: mesh,
-            iter: HeVertexOHalfEdgeIter::new(mesh, vertex),
+        mesh.flip_face(0);
+        let mut expected = mesh.face_neighbors(0);
+        expected.push(0);
+        expected.sort();
+
+        assert_eq!(mesh.inconsistent_faces(), expected);
+    }
+
+    #[test]
+    fn closest_point() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        let (face, closest, distance) = mesh.closest_point(Vector3::new(2., 0.1, -0.2)).unwrap();
+        assert!((closest - Vector3::new(0.5, 0.1, -0.2)).mag() < EPSILON);
+        assert!((distance - 1.5).abs() < EPSILON);
+        assert!((mesh.face_normal(face) - Vector3::new(1., 0., 0.)).mag() < EPSILON);
+
+        let (_, closest, distance) = mesh.closest_point(Vector3::new(0.1, 0.2, 0.3)).unwrap();
+        assert!((closest - Vector3::new(0.1, 0.2, 0.5)).mag() < EPSILON);
+        assert!((distance - 0.2).abs() < EPSILON);
+    }
+
+    #[test]
+    fn closest_points() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let points = mesh.sample_surface(50, 7);
+        let points: Vec<Vector3> = points.iter().map(|p| *p * 1.5).collect();
+        let closest = mesh.closest_points(&points).unwrap();
+
+        assert_eq!(closest.len(), points.len());
+
+        for (point, closest) in points.iter().zip(closest) {
+            assert_eq!(Some(closest), mesh.closest_point(*point));
+        }
+    }
+
+    #[test]
+    fn closest_point_empty() {
+        let mesh = HeMesh::new(&PolygonSoupMesh::new()).unwrap();
+        let point = Vector3::new(1., 2., 3.);
+
+        assert_eq!(mesh.closest_point(point), None);
+        assert_eq!(mesh.closest_points(&[point]), None);
+        assert_eq!(mesh.closest_feature(point), None);
+        assert_eq!(mesh.signed_distance(point), None);
+        assert_eq!(mesh.sample_sdf(Aabb::unit(), 2), vec![f64::INFINITY; 8]);
+    }
+
+    #[test]
+    fn signed_distance() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!((mesh.signed_distance(Vector3::zeros()).unwrap() + 0.5).abs() < EPSILON);
+        assert!((mesh.signed_distance(Vector3::new(2., 0., 0.)).unwrap() - 1.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn sample_sdf() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let bounds = Aabb::new(Vector3::zeros(), Vector3::ones());
+        let samples = mesh.sample_sdf(bounds, 3);
+
+        assert_eq!(samples.len(), 27);
+        assert!((samples[13] + 0.5).abs() < EPSILON);
+        assert!((samples[0] - 0.75f64.sqrt()).abs() < EPSILON);
+        assert!((samples[14] - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn flip_face() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+
+        let vertices = mesh.face_vertices(0);
+        assert_eq!(vertices[0], 0);
+        assert_eq!(vertices[1], 1);
+        assert_eq!(vertices[2], 2);
+
+        mesh.flip_face(0);
+        assert!(mesh.is_closed());
+        assert!(!mesh.is_consistent());
+
+        let vertices = mesh.face_vertices(0);
+        assert_eq!(vertices[0], 1);
+        assert_eq!(vertices[1], 0);
+        assert_eq!(vertices[2], 2);
+    }
+
+    #[test]
+    fn flip_patch() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+
+        let normals: Vec<Vector3> = (0..mesh.n_faces()).map(|f| mesh.face_normal(f)).collect();
+        mesh.flip_patch("back");
+
+        for (face_id, normal) in normals.iter().enumerate() {
+            if mesh.faces[face_id].patch == Some(1) {
+                assert_eq!(mesh.face_normal(face_id), -*normal);
+            } else {
+                assert_eq!(mesh.face_normal(face_id), *normal);
+            }
+        }
+    }
+
+    #[test]
+    fn is_face_consistent() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!(mesh.is_face_consistent(0, 1));
+        assert!(mesh.is_face_consistent(1, 0));
+
+        mesh.flip_face(1);
+
+        assert!(!mesh.is_face_consistent(0, 1));
+        assert!(!mesh.is_face_consistent(1, 0));
+    }
+
+    #[test]
+    fn orient() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.flip_face(3);
+        mesh.flip_face(7);
+
+        assert!(!mesh.is_consistent());
+
+        mesh.orient();
+
+        assert!(mesh.is_consistent());
+    }
+
+    #[test]
+    fn edges() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let edges = mesh.edges();
+        let unique: HashSet<(usize, usize)> = edges.iter().copied().collect();
+
+        assert_eq!(mesh.n_edges(), 18);
+        assert_eq!(edges.len(), 18);
+        assert_eq!(unique.len(), 18);
+        assert!(edges.iter().all(|(a, b)| a < b));
+    }
+
+    #[test]
+    fn edges_open() {
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert_eq!(mesh.n_edges(), mesh.edges().len());
+        assert_eq!(mesh.n_vertices() + mesh.n_faces() - mesh.n_edges(), 1);
+    }
+
+    #[test]
+    fn patch_summary() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let areas = mesh.patch_areas();
+        let summary = mesh.patch_summary();
+
+        assert_eq!(areas.len(), 6);
+        assert!(areas.iter().all(|a| (a - 1.).abs() < EPSILON));
+        assert_eq!(summary.len(), 6);
+        assert_eq!(summary[1].0, "back");
+        assert_eq!(summary[1].1, 2);
+        assert!((summary[1].2 - 1.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn patch_summary_unassigned() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let summary = mesh.patch_summary();
+
+        assert!(mesh.patch_areas().is_empty());
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].0, "unassigned");
+        assert_eq!(summary[0].1, 12);
+        assert!((summary[0].2 - 6.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn segment_by_features() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.segment_by_features(30. * std::f64::consts::PI / 180.);
+
+        assert_eq!(mesh.n_patches(), 6);
+
+        for patch in 0..mesh.n_patches() {
+            let faces: Vec<usize> = (0..mesh.n_faces())
+                .filter(|&f| mesh.face(f).patch() == Some(patch))
+                .collect();
+
+            assert_eq!(faces.len(), 2);
+            assert!(
+                Vector3::angle(&mesh.face_normal(faces[0]), &mesh.face_normal(faces[1])) < EPSILON
+            );
+        }
+
+        let extracted = mesh.extract_patch_names(&["patch0"]);
+        assert_eq!(extracted.n_faces(), 2);
+    }
+
+    #[test]
+    fn is_convex() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!(mesh.is_convex());
+
+        // Push a corner into the box to dent it
+        let mut soup = PolygonSoupMesh::new();
+
+        for (i, vertex) in mesh.vertices().iter().enumerate() {
+            if i == 7 {
+                soup.insert_vertex(Vector3::new(0.1, 0.1, 0.1));
+            } else {
+                soup.insert_vertex(vertex.origin());
+            }
+        }
+
+        for face in 0..mesh.n_faces() {
+            soup.insert_face(&mesh.face_vertices(face), None);
+        }
+
+        let dented = HeMesh::new(&soup).unwrap();
+
+        assert!(dented.volume() > 0.);
+        assert!(!dented.is_convex());
+    }
+
+    #[test]
+    fn is_convex_open() {
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!(!mesh.is_convex());
+    }
+
+    #[test]
+    fn sample_surface() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let points = mesh.sample_surface(500, 42);
+
+        assert_eq!(points.len(), 500);
+        assert_eq!(points, mesh.sample_surface(500, 42));
+
+        for point in points.iter() {
+            let (_, _, distance) = mesh.closest_point(*point).unwrap();
+            assert!(distance < EPSILON);
+        }
+    }
+
+    #[test]
+    fn sample_surface_area() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 0., 0.));
+        soup.insert_vertex(Vector3::new(0., 1., 0.));
+        soup.insert_vertex(Vector3::new(0., 0., 1.));
+        soup.insert_vertex(Vector3::new(3., 0., 1.));
+        soup.insert_vertex(Vector3::new(0., 1., 1.));
+        soup.insert_face(&[0, 1, 2], None);
+        soup.insert_face(&[3, 4, 5], None);
+
+        let mesh = HeMesh::new(&soup).unwrap();
+        let points = mesh.sample_surface(10000, 7);
+        let lower = points.iter().filter(|p| p[2] < 0.5).count();
+
+        // The lower face has a quarter of the total area
+        assert!((lower as f64 / 10000. - 0.25).abs() < 0.02);
+    }
+
+    #[test]
+    fn sweep_circle() {
+        let path = [Vector3::new(0., 0., 0.), Vector3::new(0., 0., 2.)];
+        let mesh = HeMesh::sweep_circle(&path, 0.5, 32).unwrap();
+        let area = 16. * 0.25 * (2. * std::f64::consts::PI / 32.).sin();
+
+        assert_eq!(mesh.n_vertices(), 64);
+        assert_eq!(mesh.n_faces(), 34);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 2. * area).abs() < 1e-12);
+
+        for vertex in mesh.vertices() {
+            let origin = vertex.origin();
+            assert!((origin.x().hypot(origin.y()) - 0.5).abs() < 1e-12);
+        }
+
+        // Bends keep the tube closed and outward
+        let path = [
+            Vector3::new(0., 0., 0.),
+            Vector3::new(1., 0., 0.),
+            Vector3::new(1., 1., 0.),
+            Vector3::new(1., 1., 1.),
+        ];
+
+        let mesh = HeMesh::sweep_circle(&path, 0.1, 12).unwrap();
+
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!(mesh.volume() > 0.);
+    }
+
+    #[test]
+    fn sweep_circle_invalid() {
+        let path = [Vector3::new(0., 0., 0.), Vector3::new(0., 0., 2.)];
+
+        for result in [
+            HeMesh::sweep_circle(&path[..1], 0.5, 32),
+            HeMesh::sweep_circle(&path, 0.5, 2),
+            HeMesh::sweep_circle(&path, 0., 32),
+        ] {
+            assert!(matches!(result, Err(HeMeshError::InvalidParameter)));
+        }
+    }
+
+    #[test]
+    fn dual() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let dual = mesh.dual().unwrap();
+
+        assert_eq!(dual.n_vertices(), 12);
+        assert_eq!(dual.n_faces(), 8);
+        assert!(dual.is_closed());
+        assert!(dual.is_consistent());
+        assert!(dual.volume() > 0.);
+    }
+
+    #[test]
+    fn dual_open() {
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        let result = mesh.dual();
+
+        assert!(result.is_err_and(|e| e.to_string() == "open mesh"));
+    }
+
+    #[test]
+    fn orient_outward() {
+        let path = "tests/fixtures/box.inverted.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.flip_face(5);
+
+        assert!(mesh.volume() < 0.);
+
+        mesh.orient_outward();
+
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 1.).abs() < 1e-12);
+
+        for face in 0..mesh.n_faces() {
+            let normal = mesh.face_normal(face);
+
+            for vertex in mesh.face_vertices(face) {
+                let corner = mesh.vertex(vertex).origin();
+                assert!(Vector3::dot(&normal, &corner) > 0.);
+            }
+        }
+    }
+
+    #[test]
+    fn ensure_outward() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+
+        for face in 0..mesh.n_faces() {
+            mesh.flip_face(face);
+        }
+
+        assert!(mesh.volume() < 0.);
+
+        mesh.ensure_outward().unwrap();
+
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 1.).abs() < 1e-12);
+
+        let path = "tests/fixtures/box.open.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!(matches!(mesh.ensure_outward(), Err(HeMeshError::Open)));
+    }
+
+    #[test]
+    fn orient_like() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let volume = mesh.volume();
+        mesh.flip_face(4);
+        mesh.orient_like(4);
+
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() + volume).abs() < 1e-12);
+
+        for face in 0..mesh.n_faces() {
+            let normal = mesh.face_normal(face);
+            let vertex = mesh.face_vertices(face)[0];
+            let center = mesh.vertex(vertex).origin();
+            assert!(Vector3::dot(&normal, &center) < 0.);
         }
     }
-}
 
-impl<'a> Iterator for HeVertexVertexIter<'a> {
-    type Item = usize;
+    #[test]
+    fn test_feature_edges() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(curr) = self.iter.next() {
-            let next = self.mesh.half_edges[curr].next;
-            return Some(self.mesh.half_edges[next].origin);
-        }
+        let features = mesh.feature_edges(30. * std::f64::consts::PI / 180.);
 
-        None
+        assert_eq!(features.len(), 12);
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct HeVertexFaceIter<'a> {
-    mesh: &'a HeMesh,
-    iter: HeVertexOHalfEdgeIter<'a>,
-}
+    #[test]
+    fn test_components_single() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
 
-impl<'a> HeVertexFaceIter<'a> {
-    pub fn new(mesh: &'a HeMesh, vertex: usize) -> HeVertexFaceIter<'a> {
-        HeVertexFaceIter {
-            mesh: mesh,
-            iter: HeVertexOHalfEdgeIter::new(mesh, vertex),
-        }
+        let components = mesh.components();
+
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), mesh.n_faces());
     }
-}
 
-impl<'a> Iterator for HeVertexFaceIter<'a> {
-    type Item = usize;
+    #[test]
+    fn test_components_multiple() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(curr) = self.iter.next() {
-            return Some(self.mesh.half_edges[curr].face);
-        }
+        let path = "tests/fixtures/box.obj";
+        let other = HeMesh::import_obj(path).unwrap();
+        mesh.merge(&other);
 
-        None
+        let components = mesh.components();
+
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].len(), 12);
+        assert_eq!(components[1].len(), 12);
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct HeFaceHalfEdgeIter<'a> {
-    mesh: &'a HeMesh,
-    curr: usize,
-    init: usize,
-    count: usize,
-}
+    #[test]
+    fn component_bounds_volumes() {
+        let mut mesh = import_box_offset(Vector3::zeros());
+        mesh.merge(&import_box_offset(Vector3::new(2., 0., 0.)));
 
-impl<'a> HeFaceHalfEdgeIter<'a> {
-    pub fn new(mesh: &'a HeMesh, face: usize) -> HeFaceHalfEdgeIter {
-        HeFaceHalfEdgeIter {
-            mesh: mesh,
-            init: mesh.faces[face].half_edge,
-            curr: mesh.faces[face].half_edge,
-            count: 0,
-        }
+        let bounds = mesh.component_bounds();
+        let volumes = mesh.component_volumes();
+
+        assert_eq!(bounds.len(), 2);
+        assert_eq!(bounds[0].center(), Vector3::zeros());
+        assert_eq!(bounds[1].center(), Vector3::new(2., 0., 0.));
+        assert_eq!(bounds[0].halfsize(), bounds[1].halfsize());
+
+        assert_eq!(volumes.len(), 2);
+        assert!((volumes[0] - 1.).abs() < EPSILON);
+        assert!((volumes[1] - 1.).abs() < EPSILON);
     }
-}
 
-impl<'a> Iterator for HeFaceHalfEdgeIter<'a> {
-    type Item = usize;
+    #[test]
+    fn translate_patch() {
+        let mut mesh = import_box_offset(Vector3::zeros());
+        let mut other = import_box_offset(Vector3::new(2., 0., 0.));
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.count != 0 && self.curr == self.init {
-            return None;
+        mesh.insert_patch("left");
+        other.insert_patch("right");
+
+        for face in mesh.faces.iter_mut() {
+            face.patch = Some(0);
         }
 
-        let curr = self.curr;
-        self.curr = self.mesh.half_edges[self.curr].next;
-        self.count += 1;
+        for face in other.faces.iter_mut() {
+            face.patch = Some(0);
+        }
 
-        Some(curr)
+        mesh.merge(&other);
+        let offset = Vector3::new(0., 0., 1.);
+        mesh.translate_patch("right", offset);
+
+        let bounds = mesh.component_bounds();
+        assert_eq!(bounds[0].center(), Vector3::zeros());
+        assert_eq!(bounds[1].center(), Vector3::new(2., 0., 1.));
+
+        // Vertices shared with other patches are left in place
+        let path = "tests/fixtures/box.groups.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let before = mesh.bounds();
+        mesh.translate_patch("front", offset);
+
+        assert_eq!(mesh.bounds().min(), before.min());
+        assert_eq!(mesh.bounds().max(), before.max());
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct HeFaceVertexIter<'a> {
-    mesh: &'a HeMesh,
-    iter: HeFaceHalfEdgeIter<'a>,
-}
+    #[test]
+    fn triangle_soup_arrays() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let (positions, indices) = mesh.triangle_soup_arrays();
+        let n_triangles = mesh.triangles().count();
 
-impl<'a> HeFaceVertexIter<'a> {
-    pub fn new(mesh: &'a HeMesh, face: usize) -> HeFaceVertexIter<'a> {
-        HeFaceVertexIter {
-            mesh: mesh,
-            iter: HeFaceHalfEdgeIter::new(mesh, face),
-        }
+        assert_eq!(positions.len(), 3 * mesh.n_vertices());
+        assert_eq!(indices.len(), 3 * n_triangles);
+        assert!(indices.iter().all(|&i| (i as usize) < mesh.n_vertices()));
+
+        let patches = mesh.triangle_patch_ids();
+
+        assert_eq!(patches.len(), n_triangles);
+        assert!(patches.iter().all(|&p| (p as usize) < mesh.n_patches()));
+
+        let a = indices[0] as usize;
+        let origin = mesh.vertex(a).origin();
+        assert_eq!(positions[3 * a], origin.x() as f32);
+        assert_eq!(positions[3 * a + 2], origin.z() as f32);
     }
-}
 
-impl<'a> Iterator for HeFaceVertexIter<'a> {
-    type Item = usize;
+    #[test]
+    fn content_hash() {
+        let path = "tests/fixtures/sphere.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let hash = mesh.content_hash(1e-6).unwrap();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(index) = self.iter.next() {
-            return Some(self.mesh.half_edges[index].origin);
-        }
+        let mut perturbed = mesh.clone();
+        perturbed.transform(&Matrix4::new_translation(&[1e-9, -1e-9, 1e-9].into()));
 
-        None
+        let mut scaled = mesh.clone();
+        scaled.transform(&Matrix4::new_scaling(1.01));
+
+        assert_eq!(mesh.content_hash(1e-6).unwrap(), hash);
+        assert_eq!(perturbed.content_hash(1e-6).unwrap(), hash);
+        assert_ne!(scaled.content_hash(1e-6).unwrap(), hash);
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct HeFaceFaceIter<'a> {
-    mesh: &'a HeMesh,
-    iter: HeFaceHalfEdgeIter<'a>,
-}
+    #[test]
+    fn content_hash_invalid() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
 
-impl<'a> HeFaceFaceIter<'a> {
-    pub fn new(mesh: &'a HeMesh, face: usize) -> HeFaceFaceIter<'a> {
-        HeFaceFaceIter {
-            mesh: mesh,
-            iter: HeFaceHalfEdgeIter::new(mesh, face),
+        for quantum in [0., -1e-6, f64::NAN] {
+            let result = mesh.content_hash(quantum);
+            assert!(matches!(result, Err(HeMeshError::InvalidParameter)));
         }
     }
-}
 
-impl<'a> Iterator for HeFaceFaceIter<'a> {
-    type Item = usize;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        while let Some(curr) = self.iter.next() {
-            if let Some(twin) = self.mesh.half_edges[curr].twin {
-                return Some(self.mesh.half_edges[twin].face);
+    #[test]
+    fn relax_tangential() {
+        // Shift the interior vertices within the plane
+        let mut mesh = plane_grid(11, 0.1, false);
+
+        for v in 0..mesh.n_vertices() {
+            if !mesh.is_boundary_vertex(v) {
+                let p = mesh.vertices[v].origin;
+                let offset =
+                    Vector3::new(0.04 * (17. * p.y()).sin(), 0.03 * (13. * p.x()).cos(), 0.);
+                mesh.vertices[v].origin = p + offset;
             }
         }
 
-        None
-    }
-}
+        let variance = |mesh: &HeMesh| {
+            let areas = mesh.face_areas();
+            let mean = areas.iter().sum::<f64>() / areas.len() as f64;
+            areas.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / areas.len() as f64
+        };
 
-#[derive(Debug, Clone)]
-pub enum HeMeshError {
-    NonManifold,
-}
+        let boundary = |mesh: &HeMesh| {
+            let mut points: Vec<[f64; 3]> = (0..mesh.n_vertices())
+                .filter(|&v| mesh.is_boundary_vertex(v))
+                .map(|v| mesh.vertex(v).origin().into())
+                .collect();
 
-impl std::fmt::Display for HeMeshError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            HeMeshError::NonManifold => write!(f, "non-manifold mesh"),
-        }
-    }
-}
+            points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            points
+        };
 
-impl std::error::Error for HeMeshError {}
+        let before = variance(&mesh);
+        let original = boundary(&mesh);
+        mesh.relax_tangential(10).unwrap();
 
-impl Into<std::io::Error> for HeMeshError {
-    fn into(self) -> std::io::Error {
-        std::io::Error::new(std::io::ErrorKind::InvalidData, self.to_string())
-    }
-}
+        assert!(variance(&mesh) < 0.5 * before);
+        assert!(mesh
+            .vertices()
+            .iter()
+            .all(|v| v.origin().z().abs() < EPSILON));
+        assert_eq!(boundary(&mesh), original);
+
+        // Vertices stay near the curved surface
+        let path = "tests/fixtures/sphere.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let radii = |mesh: &HeMesh| -> Vec<f64> {
+            mesh.vertices().iter().map(|v| v.origin().mag()).collect()
+        };
 
-#[cfg(test)]
-mod test {
-    use super::*;
+        let before = radii(&mesh);
+        mesh.relax_tangential(5).unwrap();
 
-    #[test]
-    fn import_obj() {
-        let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+        for (r, r0) in radii(&mesh).into_iter().zip(before) {
+            assert!((r - r0).abs() < 0.01 * r0);
+        }
 
-        assert_eq!(mesh.n_vertices(), 8);
-        assert_eq!(mesh.n_faces(), 12);
-        assert_eq!(mesh.n_half_edges(), 36);
-        assert_eq!(mesh.n_patches(), 0);
+        assert!(mesh.is_consistent());
     }
 
     #[test]
-    fn import_obj_gzip() {
-        let path = "tests/fixtures/box.obj.gz";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+    fn closest_feature() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let position = |v: usize| mesh.vertex(v).origin();
 
-        assert_eq!(mesh.n_vertices(), 8);
-        assert_eq!(mesh.n_faces(), 12);
-        assert_eq!(mesh.n_half_edges(), 36);
-        assert_eq!(mesh.n_patches(), 0);
-    }
+        let (_, feature) = mesh.closest_feature(Vector3::new(2., 2., 2.)).unwrap();
+        let MeshFeature::Vertex(v) = feature else {
+            panic!("expected a vertex: {:?}", feature);
+        };
 
-    #[test]
-    fn import_obj_patches() {
-        let path = "tests/fixtures/box.groups.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+        assert_eq!(position(v), Vector3::new(0.5, 0.5, 0.5));
 
-        assert_eq!(mesh.n_patches(), 6);
-        assert_eq!(mesh.faces[0].patch, Some(0));
-        assert_eq!(mesh.faces[1].patch, Some(1));
-        assert_eq!(mesh.faces[2].patch, Some(1));
-        assert_eq!(mesh.faces[3].patch, Some(2));
-        assert_eq!(mesh.faces[4].patch, Some(3));
-        assert_eq!(mesh.faces[5].patch, Some(4));
-        assert_eq!(mesh.faces[6].patch, Some(5));
-    }
+        let (face_id, feature) = mesh.closest_feature(Vector3::new(2., 2., 0.1)).unwrap();
+        let MeshFeature::Edge(a, b) = feature else {
+            panic!("expected an edge: {:?}", feature);
+        };
 
-    #[test]
-    fn import_obj_nonmanifold() {
-        let path = "tests/fixtures/box.nonmanifold.obj";
-        let result = HeMesh::import_obj(&path);
+        let vertices = mesh.face_vertices(face_id);
+        let i = vertices.iter().position(|&v| v == a).unwrap();
+        assert_eq!(vertices[(i + 1) % vertices.len()], b);
+        assert!([a, b]
+            .iter()
+            .all(|&v| position(v).x() == 0.5 && position(v).y() == 0.5));
 
-        assert!(result.is_err_and(|e| e.to_string() == "non-manifold mesh"));
+        let (face_id, feature) = mesh.closest_feature(Vector3::new(2., 0.2, -0.1)).unwrap();
+        assert_eq!(feature, MeshFeature::Face(face_id));
+        assert!(mesh.face_normal(face_id).x() > 0.5);
+
+        // Triangulation diagonals of polygonal faces belong to the face
+        mesh.merge_coplanar(EPSILON).unwrap();
+
+        let (face_id, feature) = mesh.closest_feature(Vector3::new(2., 0., 0.)).unwrap();
+        assert_eq!(feature, MeshFeature::Face(face_id));
     }
 
     #[test]
-    fn face_half_edge_iter() {
+    fn edge_length_stats() {
+        let n = 6;
+        let mesh = plane_grid(n, 0.2, true);
+        let lengths = mesh.edge_lengths();
+        let (min, max, mean, stddev) = mesh.edge_length_stats();
+
+        assert_eq!(lengths.len(), 2 * n * (n - 1));
+        assert!((min - 0.2).abs() < EPSILON);
+        assert!((max - min).abs() < EPSILON);
+        assert!((mean - 0.2).abs() < EPSILON);
+        assert!(stddev < EPSILON);
+
+        // Diagonals of the triangulated box spread the lengths
         let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeFaceHalfEdgeIter::new(&mesh, 0);
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let (min, max, _, stddev) = mesh.edge_length_stats();
 
-        assert_eq!(iter.next(), Some(0));
-        assert_eq!(iter.next(), Some(1));
-        assert_eq!(iter.next(), Some(2));
-        assert_eq!(iter.next(), None);
+        assert!((min - 1.).abs() < EPSILON);
+        assert!((max - 2_f64.sqrt()).abs() < EPSILON);
+        assert!(stddev > 0.1);
+        assert_eq!(HeMesh::default().edge_length_stats(), (0., 0., 0., 0.));
     }
 
     #[test]
-    fn face_vertex_iter() {
+    fn remove_faces() {
         let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeFaceVertexIter::new(&mesh, 0);
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let values: Vec<f64> = (0..mesh.n_faces()).map(|f| f as f64).collect();
+        mesh.set_face_scalar("id", values).unwrap();
 
-        assert_eq!(iter.next(), Some(0));
-        assert_eq!(iter.next(), Some(1));
-        assert_eq!(iter.next(), Some(2));
-        assert_eq!(iter.next(), None);
+        // Vertices are still used by the remaining faces
+        let faces: Vec<usize> = (0..mesh.n_faces())
+            .filter(|&f| mesh.face_normal(f).x() > 0.5 || mesh.face_normal(f).y() > 0.5)
+            .collect();
+
+        mesh.remove_faces(&faces).unwrap();
+
+        assert_eq!(mesh.n_faces(), 8);
+        assert_eq!(mesh.n_vertices(), 8);
+        assert!(mesh.is_consistent());
+
+        let ids = mesh.face_scalar("id").unwrap();
+        assert_eq!(ids.len(), 8);
+        assert!(faces.iter().all(|f| !ids.contains(&(*f as f64))));
+
+        // Keep only the top, dropping the bottom vertices
+        let faces: Vec<usize> = (0..mesh.n_faces())
+            .filter(|&f| mesh.face_normal(f).z() < 0.5)
+            .collect();
+
+        mesh.remove_faces(&faces).unwrap();
+
+        assert_eq!(mesh.n_faces(), 2);
+        assert_eq!(mesh.n_vertices(), 4);
+        assert!(mesh.vertices().iter().all(|v| v.origin().z() == 0.5));
+        assert!(mesh.is_consistent());
     }
 
     #[test]
-    fn face_face_iter() {
-        let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeFaceFaceIter::new(&mesh, 0);
+    fn remove_faces_bowtie() {
+        // Keep two diagonal quads sharing only the center vertex
+        let mut mesh = plane_grid(3, 1., true);
+        mesh.remove_faces(&[1, 2]).unwrap();
 
-        assert_eq!(iter.next(), Some(4));
-        assert_eq!(iter.next(), Some(1));
-        assert_eq!(iter.next(), Some(8));
-        assert_eq!(iter.next(), None);
+        assert_eq!(mesh.n_faces(), 2);
+        assert_eq!(mesh.n_vertices(), 7);
+
+        let vertices = mesh.nonmanifold_vertices();
+
+        assert_eq!(vertices.len(), 1);
+        assert_eq!(mesh.vertex(vertices[0]).origin(), Vector3::new(1., 1., 0.));
     }
 
     #[test]
-    fn vertex_outgoing_half_edge_iter() {
+    fn merge_coplanar() {
         let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexOHalfEdgeIter::new(&mesh, 0);
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let volume = mesh.volume();
 
-        assert_eq!(iter.next(), Some(24));
-        assert_eq!(iter.next(), Some(12));
-        assert_eq!(iter.next(), Some(0));
-        assert_eq!(iter.next(), None);
+        assert_eq!(mesh.merge_coplanar(1e-6).unwrap(), 6);
+        assert_eq!(mesh.n_faces(), 6);
+        assert_eq!(mesh.n_vertices(), 8);
+        assert!((0..mesh.n_faces()).all(|f| mesh.face_vertices(f).len() == 4));
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - volume).abs() < 1e-12);
+
+        // Only flat pairs on the curved surface are merged
+        let path = "tests/fixtures/sphere.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let n_faces = mesh.n_faces();
+        let removed = mesh.merge_coplanar(1e-6).unwrap();
+
+        assert!(removed < n_faces / 2);
+        assert_eq!(mesh.n_faces(), n_faces - removed);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
     }
 
     #[test]
-    #[should_panic]
-    fn vertex_outgoing_half_edge_iter_open() {
-        let path = "tests/fixtures/box.open.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexOHalfEdgeIter::new(&mesh, 3);
-
-        iter.next();
-        iter.next();
+    fn compact() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(5., 5., 5.));
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 0., 0.));
+        soup.insert_vertex(Vector3::new(0., 1., 0.));
+        soup.insert_face(&[1, 2, 3], None);
+
+        let mut mesh = HeMesh::new(&soup).unwrap();
+        mesh.set_vertex_scalar("id", vec![0., 1., 2., 3.]).unwrap();
+        mesh.compact();
+
+        assert_eq!(mesh.n_vertices(), 3);
+        assert_eq!(mesh.face_vertices(0), vec![0, 1, 2]);
+        assert_eq!(mesh.vertex(0).origin(), Vector3::zeros());
+        assert_eq!(mesh.vertex_scalar("id").unwrap(), &[1., 2., 3.]);
     }
 
     #[test]
-    #[should_panic]
-    fn vertex_outgoing_half_edge_iter_inconsistent() {
-        let path = "tests/fixtures/box.inconsistent.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexOHalfEdgeIter::new(&mesh, 1);
+    fn is_watertight() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!(mesh.is_watertight());
+
+        // A second box touching the first at a single (pinched) vertex
+        let mut vertices: Vec<Vector3> = mesh.vertices().iter().map(|v| v.origin()).collect();
+        let mut faces: Vec<Vec<usize>> =
+            (0..mesh.n_faces()).map(|f| mesh.face_vertices(f)).collect();
+        let corner = Vector3::new(0.5, 0.5, 0.5);
+        let pinch = vertices.iter().position(|&v| v == corner).unwrap();
+        let offset = Vector3::ones();
+        let n = vertices.len();
+
+        let index: Vec<usize> = (0..n)
+            .map(|i| {
+                if mesh.vertex(i).origin() + offset == corner {
+                    pinch
+                } else {
+                    vertices.push(mesh.vertex(i).origin() + offset);
+                    vertices.len() - 1
+                }
+            })
+            .collect();
 
-        iter.next();
-        iter.next();
-    }
+        for f in 0..mesh.n_faces() {
+            faces.push(mesh.face_vertices(f).iter().map(|&v| index[v]).collect());
+        }
 
-    #[test]
-    fn vertex_incoming_half_edge_iter() {
-        let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexIHalfEdgeIter::new(&mesh, 0);
+        let pinched = HeMesh::from_arrays(&vertices, &faces, None).unwrap();
 
-        assert_eq!(iter.next(), Some(2));
-        assert_eq!(iter.next(), Some(26));
-        assert_eq!(iter.next(), Some(14));
-        assert_eq!(iter.next(), None);
-    }
+        assert!(pinched.is_closed());
+        assert!(pinched.is_consistent());
+        assert!(!pinched.is_watertight());
 
-    #[test]
-    #[should_panic]
-    fn vertex_incoming_half_edge_iter_open() {
         let path = "tests/fixtures/box.open.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexIHalfEdgeIter::new(&mesh, 3);
+        let open = HeMesh::import_obj(path).unwrap();
 
-        iter.next();
-        iter.next();
+        assert!(!open.is_watertight());
     }
 
     #[test]
-    #[should_panic]
-    fn vertex_incoming_half_edge_iter_inconsistent() {
-        let path = "tests/fixtures/box.inconsistent.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexOHalfEdgeIter::new(&mesh, 1);
-
-        iter.next();
-        iter.next();
+    fn extrude_boundary() {
+        let mesh = plane_grid(3, 0.5, true);
+
+        for distance in [0.25, -0.25] {
+            let mut shell = mesh.clone();
+            shell.extrude_boundary(distance).unwrap();
+
+            assert_eq!(shell.n_vertices(), 18);
+            assert_eq!(shell.n_faces(), 16);
+            assert!(shell.is_closed());
+            assert!(shell.is_consistent());
+            assert!((shell.volume() - 0.25).abs() < EPSILON);
+        }
     }
 
     #[test]
-    fn vertex_vertex_iter() {
-        let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexVertexIter::new(&mesh, 6);
+    fn vertex_normals() {
+        let path = "tests/fixtures/sphere.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
 
-        assert_eq!(iter.next(), Some(4));
-        assert_eq!(iter.next(), Some(2));
-        assert_eq!(iter.next(), Some(3));
-        assert_eq!(iter.next(), Some(7));
-        assert_eq!(iter.next(), Some(5));
-        assert_eq!(iter.next(), None);
+        for (vertex, normal) in mesh.vertices().iter().zip(mesh.vertex_normals()) {
+            assert!((normal.mag() - 1.).abs() < EPSILON);
+            assert!(Vector3::dot(&normal, &vertex.origin().unit()) > 0.99);
+        }
     }
 
     #[test]
-    #[should_panic]
-    fn vertex_vertex_iter_open() {
-        let path = "tests/fixtures/box.open.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexVertexIter::new(&mesh, 3);
+    fn vertex_normal() {
+        for path in ["tests/fixtures/sphere.obj", "tests/fixtures/box.open.obj"] {
+            let mesh = HeMesh::import_obj(path).unwrap();
+            let normals = mesh.vertex_normals();
 
-        iter.next();
-        iter.next();
+            for (i, normal) in normals.iter().enumerate() {
+                assert!((mesh.vertex_normal(i) - *normal).mag() < EPSILON);
+            }
+        }
     }
 
     #[test]
-    #[should_panic]
-    fn vertex_vertex_iter_inconsistent() {
-        let path = "tests/fixtures/box.inconsistent.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexVertexIter::new(&mesh, 1);
+    fn offset() {
+        let path = "tests/fixtures/sphere.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.offset(0.1);
 
-        iter.next();
-        iter.next();
+        for vertex in mesh.vertices().iter() {
+            assert!((vertex.origin().mag() - 0.6).abs() < 1e-3);
+        }
+
+        mesh.offset(-0.2);
+
+        for vertex in mesh.vertices().iter() {
+            assert!((vertex.origin().mag() - 0.4).abs() < 1e-3);
+        }
     }
 
     #[test]
-    fn vertex_face_iter() {
-        let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexFaceIter::new(&mesh, 6);
+    fn patch_centroid_bounds() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let patch = mesh
+            .patches()
+            .iter()
+            .position(|p| p.name() == "right")
+            .unwrap();
 
-        assert_eq!(iter.next(), Some(9));
-        assert_eq!(iter.next(), Some(6));
-        assert_eq!(iter.next(), Some(7));
-        assert_eq!(iter.next(), Some(3));
-        assert_eq!(iter.next(), Some(2));
-        assert_eq!(iter.next(), None);
+        let centroid = mesh.patch_centroid(patch);
+        assert!((centroid - Vector3::new(0.5, 1., 0.5)).mag() < EPSILON);
+
+        let bounds = mesh.patch_bounds(patch);
+        assert_eq!(bounds.min(), Vector3::new(0., 1., 0.));
+        assert_eq!(bounds.max(), Vector3::new(1., 1., 1.));
     }
 
     #[test]
-    #[should_panic]
-    fn vertex_face_iter_open() {
-        let path = "tests/fixtures/box.open.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexFaceIter::new(&mesh, 3);
+    fn hausdorff_distance() {
+        let path = "tests/fixtures/sphere.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
 
-        iter.next();
-        iter.next();
+        assert!(mesh.hausdorff_distance(&mesh, 500) < 1e-9);
+
+        let mut scaled = mesh.clone();
+
+        for vertex in scaled.vertices.iter_mut() {
+            vertex.origin *= 1.2;
+        }
+
+        let distance = mesh.hausdorff_distance(&scaled, 500);
+        assert!((distance - 0.1).abs() < 0.01);
+        assert_eq!(distance, scaled.hausdorff_distance(&mesh, 500));
     }
 
     #[test]
-    #[should_panic]
-    fn vertex_face_iter_inconsistent() {
-        let path = "tests/fixtures/box.inconsistent.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
-        let mut iter = HeVertexFaceIter::new(&mesh, 1);
+    fn subdivide_midpoint_with_cancel() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let iterations = std::cell::Cell::new(0);
 
-        iter.next();
-        iter.next();
+        mesh.subdivide_midpoint_with_cancel(3, || {
+            iterations.set(iterations.get() + 1);
+            iterations.get() <= 1
+        })
+        .unwrap();
+
+        assert_eq!(mesh.n_faces(), 12 * 4);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
     }
 
     #[test]
-    fn flip_face() {
+    fn subdivide_midpoint() {
         let path = "tests/fixtures/box.obj";
-        let mut mesh = HeMesh::import_obj(&path).unwrap();
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.subdivide_midpoint(2).unwrap();
+
+        assert_eq!(mesh.n_faces(), 12 * 16);
+        assert_eq!(mesh.n_vertices() + mesh.n_faces() - mesh.n_edges(), 2);
         assert!(mesh.is_closed());
         assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 1.).abs() < EPSILON);
 
-        let vertices = mesh.face_vertices(0);
-        assert_eq!(vertices[0], 0);
-        assert_eq!(vertices[1], 1);
-        assert_eq!(vertices[2], 2);
+        let path = "tests/fixtures/box.groups.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
 
-        mesh.flip_face(0);
-        assert!(mesh.is_closed());
-        assert!(!mesh.is_consistent());
+        assert!(mesh
+            .subdivide_midpoint(1)
+            .is_err_and(|e| e.to_string() == "non-triangular mesh"));
 
-        let vertices = mesh.face_vertices(0);
-        assert_eq!(vertices[0], 1);
-        assert_eq!(vertices[1], 0);
-        assert_eq!(vertices[2], 2);
+        // Scalars are interpolated at the midpoints and copied to the children
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let x = mesh.vertices.iter().map(|v| v.origin.x()).collect();
+        let ids = (0..12).map(|f| f as f64).collect();
+        mesh.set_vertex_scalar("x", x).unwrap();
+        mesh.set_face_scalar("id", ids).unwrap();
+        mesh.subdivide_midpoint(1).unwrap();
+
+        let x = mesh.vertex_scalar("x").unwrap();
+        let ids = mesh.face_scalar("id").unwrap();
+
+        for (v, vertex) in mesh.vertices().iter().enumerate() {
+            assert_eq!(x[v], vertex.origin().x());
+        }
+
+        for (f, &id) in ids.iter().enumerate() {
+            assert_eq!(id, (f / 4) as f64);
+        }
     }
 
     #[test]
-    fn is_face_consistent() {
-        let path = "tests/fixtures/box.obj";
-        let mut mesh = HeMesh::import_obj(&path).unwrap();
+    fn flip_patches() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let before: Vec<Vector3> = (0..mesh.n_faces()).map(|f| mesh.face_normal(f)).collect();
 
-        assert!(mesh.is_face_consistent(0, 1));
-        assert!(mesh.is_face_consistent(1, 0));
+        mesh.flip_patches(&["top", "left", "missing"]);
 
-        mesh.flip_face(1);
+        for (face_id, normal) in before.iter().enumerate() {
+            let patch = mesh.face(face_id).patch().unwrap();
+            let name = mesh.patches()[patch].name();
 
-        assert!(!mesh.is_face_consistent(0, 1));
-        assert!(!mesh.is_face_consistent(1, 0));
+            if name == "top" || name == "left" {
+                assert_eq!(mesh.face_normal(face_id), -*normal);
+            } else {
+                assert_eq!(mesh.face_normal(face_id), *normal);
+            }
+        }
     }
 
     #[test]
-    fn test_feature_edges() {
+    fn nonmanifold_vertices() {
         let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+        let mesh = HeMesh::import_obj(path).unwrap();
+        assert!(mesh.nonmanifold_vertices().is_empty());
 
-        let features = mesh.feature_edges(30. * std::f64::consts::PI / 180.);
+        let path = "tests/fixtures/bowtie.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        assert_eq!(mesh.nonmanifold_vertices(), vec![0]);
 
-        assert_eq!(features.len(), 12);
+        let path = "tests/fixtures/box.open.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        assert!(mesh.nonmanifold_vertices().is_empty());
     }
 
     #[test]
-    fn test_components_single() {
-        let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+    fn split_nonmanifold_vertices() {
+        let path = "tests/fixtures/bowtie.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.set_vertex_scalar("id", vec![0., 1., 2., 3., 4.])
+            .unwrap();
+        mesh.split_nonmanifold_vertices().unwrap();
+
+        assert!(mesh.nonmanifold_vertices().is_empty());
+        assert_eq!(mesh.n_vertices(), 6);
+        assert_eq!(mesh.vertex(5).origin(), mesh.vertex(0).origin());
+        assert_eq!(mesh.vertex_scalar("id").unwrap()[5], 0.);
+        assert_eq!(mesh.face_vertices(0), vec![0, 1, 2]);
+        assert_eq!(mesh.face_vertices(1), vec![5, 3, 4]);
+        assert_eq!(mesh.components().len(), 2);
 
-        let components = mesh.components();
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.split_nonmanifold_vertices().unwrap();
 
-        assert_eq!(components.len(), 1);
-        assert_eq!(components[0].len(), mesh.n_faces());
+        assert_eq!(mesh.n_vertices(), 8);
+        assert!(mesh.is_watertight());
     }
 
     #[test]
-    fn test_components_multiple() {
+    fn total_angle_defect() {
         let path = "tests/fixtures/box.obj";
-        let mut mesh = HeMesh::import_obj(&path).unwrap();
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let pi = std::f64::consts::PI;
 
-        let path = "tests/fixtures/box.obj";
-        let other = HeMesh::import_obj(&path).unwrap();
-        mesh.merge(&other);
+        for sum in mesh.vertex_angle_sums() {
+            assert!((sum - 1.5 * pi).abs() < EPSILON);
+        }
 
-        let components = mesh.components();
+        assert!((mesh.total_angle_defect() - 4. * pi).abs() < EPSILON);
 
-        assert_eq!(components.len(), 2);
-        assert_eq!(components[0].len(), 12);
-        assert_eq!(components[1].len(), 12);
+        let path = "tests/fixtures/sphere.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!((mesh.total_angle_defect() - 4. * pi).abs() < 1e-6);
     }
 
     #[test]
     fn test_shared_vertices() {
         let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+        let mesh = HeMesh::import_obj(path).unwrap();
 
         let shared = mesh.shared_vertices(0, 1);
 
@@ -1217,7 +5930,7 @@ mod test {
     #[test]
     fn test_shared_vertices_none() {
         let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+        let mesh = HeMesh::import_obj(path).unwrap();
 
         let shared = mesh.shared_vertices(0, 7);
 
@@ -1227,7 +5940,7 @@ mod test {
     #[test]
     fn test_extract_faces() {
         let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+        let mesh = HeMesh::import_obj(path).unwrap();
 
         let faces = vec![3, 5, 6];
         let subset = mesh.extract_faces(&faces);
@@ -1240,7 +5953,7 @@ mod test {
     #[test]
     fn test_extract_faces_all_reversed() {
         let path = "tests/fixtures/box.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+        let mesh = HeMesh::import_obj(path).unwrap();
 
         let faces: Vec<usize> = (0..mesh.n_faces()).rev().collect();
         let subset = mesh.extract_faces(&faces);
@@ -1252,10 +5965,28 @@ mod test {
         assert!(subset.is_consistent());
     }
 
+    #[test]
+    fn test_extract_faces_deterministic() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let faces = vec![5, 2, 0, 3];
+
+        let export = |subset: &HeMesh| {
+            let mut buffer = Vec::<u8>::new();
+            ObjWriter::from_mesh(subset).write_to(&mut buffer).unwrap();
+            buffer
+        };
+
+        let a = export(&mesh.extract_faces(&faces));
+        let b = export(&mesh.extract_faces(&faces));
+
+        assert_eq!(a, b);
+    }
+
     #[test]
     fn test_extract_patch_names() {
         let path = "tests/fixtures/box.groups.obj";
-        let mesh = HeMesh::import_obj(&path).unwrap();
+        let mesh = HeMesh::import_obj(path).unwrap();
 
         let patch = mesh.patch(1);
         let names = vec![patch.name()];
@@ -1266,4 +5997,285 @@ mod test {
         assert_eq!(subset.n_half_edges(), 6);
         assert_eq!(subset.n_patches(), 1);
     }
+
+    #[test]
+    fn test_extract_faces_patch_order() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut faces: Vec<usize> = (0..mesh.n_faces()).collect();
+
+        let names = |subset: &HeMesh| -> Vec<String> {
+            subset
+                .patches()
+                .iter()
+                .map(|p| p.name().to_string())
+                .collect()
+        };
+
+        let forward = mesh.extract_faces(&faces);
+        faces.reverse();
+        let reversed = mesh.extract_faces(&faces);
+
+        assert_eq!(names(&forward), names(&mesh));
+        assert_eq!(names(&reversed), names(&mesh));
+
+        let subset = mesh.extract_patches(&[4, 1, 4]);
+        assert_eq!(names(&subset), vec!["back", "top"]);
+    }
+
+    #[test]
+    fn test_merge_patch_ids() {
+        let path = "tests/fixtures/box.groups.obj";
+        let source = HeMesh::import_obj(path).unwrap();
+        let a = source.extract_patch_names(&["top", "left"]);
+        let b = source.extract_patch_names(&["bottom", "top", "front"]);
+
+        let merged = |a: &HeMesh, b: &HeMesh| {
+            let mut mesh = a.clone();
+            mesh.merge(b);
+            mesh
+        };
+
+        let mesh = merged(&a, &b);
+        let names: Vec<&str> = mesh.patches().iter().map(|p| p.name()).collect();
+
+        assert_eq!(names, vec!["left", "top", "front", "bottom"]);
+
+        // Faces keep their patch names through the merge
+        for (face_id, face) in b.faces().iter().enumerate() {
+            let name = b.patch(face.patch().unwrap()).name().to_string();
+            let merged_face = mesh.face(a.n_faces() + face_id);
+            assert_eq!(mesh.patch(merged_face.patch().unwrap()).name(), name);
+        }
+
+        // Repeated merges assign the same ids
+        let other = merged(&a, &b);
+        let ids = |mesh: &HeMesh| -> Vec<Option<usize>> {
+            mesh.faces().iter().map(|f| f.patch()).collect()
+        };
+
+        assert_eq!(ids(&mesh), ids(&other));
+    }
+
+    #[test]
+    fn test_slice() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        let origin = Vector3::zeros();
+        let normal = Vector3::new(0., 0., 1.);
+        let plane = Plane::new(origin, normal);
+        let segments = mesh.slice(&plane);
+
+        assert_eq!(segments.len(), 8);
+
+        for segment in segments.iter() {
+            assert!(segment.p()[2].abs() < EPSILON);
+            assert!(segment.q()[2].abs() < EPSILON);
+
+            let shared = segments
+                .iter()
+                .flat_map(|s| [s.p(), s.q()])
+                .filter(|p| (*p - segment.p()).mag() < EPSILON)
+                .count();
+
+            assert_eq!(shared, 2);
+        }
+    }
+
+    #[test]
+    fn test_slice_contours() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        let origin = Vector3::new(0., 0., -0.5);
+        let normal = Vector3::new(0., 0., 1.);
+        let plane = Plane::new(origin, normal);
+        let contours = mesh.slice_contours(&plane);
+
+        assert_eq!(contours.len(), 1);
+        assert_eq!(contours[0].len(), 4);
+
+        let origin = Vector3::zeros();
+        let plane = Plane::new(origin, normal);
+        let contours = mesh.slice_contours(&plane);
+
+        assert_eq!(contours.len(), 1);
+        assert_eq!(contours[0].len(), 8);
+    }
+
+    #[test]
+    fn test_vertex_scalar_extract_faces() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+
+        let values: Vec<f64> = (0..mesh.n_vertices()).map(|v| v as f64).collect();
+        mesh.set_vertex_scalar("index", values).unwrap();
+
+        let values: Vec<f64> = (0..mesh.n_faces()).map(|f| f as f64).collect();
+        mesh.set_face_scalar("index", values).unwrap();
+
+        let faces = vec![3, 5, 6];
+        let subset = mesh.extract_faces(&faces);
+        let vertex_scalar = subset.vertex_scalar("index").unwrap();
+        let face_scalar = subset.face_scalar("index").unwrap();
+
+        assert_eq!(vertex_scalar.len(), subset.n_vertices());
+        assert_eq!(face_scalar, &[3., 5., 6.]);
+
+        for (face_id, &original_id) in faces.iter().enumerate() {
+            let original = mesh.face_vertices(original_id);
+            let extracted = subset.face_vertices(face_id);
+
+            for (&i, &j) in original.iter().zip(extracted.iter()) {
+                assert_eq!(vertex_scalar[j], i as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_vertex_scalar_length_mismatch() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+
+        let result = mesh.set_vertex_scalar("index", vec![0.; 3]);
+
+        assert!(result.is_err());
+        assert!(mesh.vertex_scalar("index").is_none());
+    }
+
+    #[test]
+    fn test_vertex_scalar_merge() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let other = HeMesh::import_obj(path).unwrap();
+
+        mesh.set_vertex_scalar("value", vec![1.; 8]).unwrap();
+        mesh.merge(&other);
+
+        let values = mesh.vertex_scalar("value").unwrap();
+
+        assert_eq!(values.len(), 16);
+        assert!(values[..8].iter().all(|&v| v == 1.));
+        assert!(values[8..].iter().all(|v| v.is_nan()));
+    }
+
+    #[test]
+    fn test_merge_welded() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 1., 0.));
+        soup.insert_vertex(Vector3::new(0., 1., 0.));
+        soup.insert_face(&[0, 1, 2], None);
+        soup.insert_face(&[0, 2, 3], None);
+
+        let mut mesh = HeMesh::new(&soup).unwrap();
+
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(1., 0., 0.));
+        soup.insert_vertex(Vector3::new(2., 0., 0.));
+        soup.insert_vertex(Vector3::new(2., 1., 0.));
+        soup.insert_vertex(Vector3::new(1., 1., 0.));
+        soup.insert_face(&[0, 1, 2], None);
+        soup.insert_face(&[0, 2, 3], None);
+
+        let other = HeMesh::new(&soup).unwrap();
+        mesh.merge_welded(&other, 1e-6).unwrap();
+
+        assert_eq!(mesh.n_vertices(), 6);
+        assert_eq!(mesh.n_faces(), 4);
+        assert_eq!(mesh.components().len(), 1);
+        assert!(mesh.is_consistent());
+    }
+
+    #[test]
+    fn test_triangles() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert_eq!(mesh.triangles().count(), 12);
+
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert_eq!(mesh.triangles().count(), 12);
+    }
+
+    fn import_box_offset(offset: Vector3) -> HeMesh {
+        let path = "tests/fixtures/box.obj";
+        let soup = ObjReader::new(path).read().unwrap();
+        let mut other = PolygonSoupMesh::new();
+
+        for i in 0..soup.n_vertices() {
+            other.insert_vertex(soup.vertex(i) + offset);
+        }
+
+        for i in 0..soup.n_faces() {
+            let (vertices, patch) = soup.face(i);
+            other.insert_face(vertices, patch);
+        }
+
+        HeMesh::new(&other).unwrap()
+    }
+
+    #[test]
+    fn test_intersecting_faces() {
+        let mesh = import_box_offset(Vector3::zeros());
+        let other = import_box_offset(Vector3::new(0.5, 0.25, 0.25));
+        let pairs = mesh.intersecting_faces(&other);
+
+        assert!(mesh.intersects(&other));
+        assert_eq!(pairs.len(), 24);
+        assert!(pairs.contains(&(2, 4)));
+        assert!(pairs.contains(&(6, 0)));
+        assert!(!pairs.contains(&(10, 8)));
+    }
+
+    #[test]
+    fn test_intersecting_faces_none() {
+        let mesh = import_box_offset(Vector3::zeros());
+        let other = import_box_offset(Vector3::new(2., 0., 0.));
+
+        assert!(!mesh.intersects(&other));
+        assert!(mesh.intersecting_faces(&other).is_empty());
+    }
+
+    #[test]
+    fn self_intersections() {
+        let mut mesh = import_box_offset(Vector3::zeros());
+        let other = import_box_offset(Vector3::new(0.5, 0.25, 0.25));
+        let expected: Vec<(usize, usize)> = mesh
+            .intersecting_faces(&other)
+            .into_iter()
+            .map(|(i, j)| (i, j + mesh.n_faces()))
+            .collect();
+
+        assert!(mesh.self_intersections().is_empty());
+        assert!(mesh.self_intersection_iter().next().is_none());
+
+        mesh.merge(&other);
+
+        assert_eq!(mesh.self_intersections(), expected);
+        assert_eq!(mesh.self_intersection_iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_volume() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!((mesh.volume() - 1.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        assert!(mesh.contains_point(&Vector3::new(0.1, 0.2, 0.3)));
+        assert!(mesh.contains_point(&Vector3::new(0.49, -0.49, 0.49)));
+        assert!(!mesh.contains_point(&Vector3::new(0.51, 0., 0.)));
+        assert!(!mesh.contains_point(&Vector3::new(2., 3., 4.)));
+    }
 }
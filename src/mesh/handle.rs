@@ -0,0 +1,133 @@
+//! Lightweight handles for traversing a half edge mesh.
+//!
+//! ```
+//! use meshr::mesh::{FaceHandle, HeMesh};
+//!
+//! let mesh = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+//! let face = FaceHandle::new(0);
+//! let start = face.half_edge(&mesh);
+//!
+//! let mut vertices = vec![];
+//! let mut he = start;
+//!
+//! loop {
+//!     vertices.push(he.origin(&mesh).index());
+//!     he = he.next(&mesh);
+//!
+//!     if he == start {
+//!         break;
+//!     }
+//! }
+//!
+//! assert_eq!(vertices, mesh.face_vertices(0));
+//! ```
+
+use crate::geometry::Vector3;
+use crate::mesh::HeMesh;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VertexHandle(usize);
+
+impl VertexHandle {
+    /// Construct a VertexHandle from its index
+    pub fn new(index: usize) -> VertexHandle {
+        VertexHandle(index)
+    }
+
+    /// Get the index
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    /// Get the position
+    pub fn origin(&self, mesh: &HeMesh) -> Vector3 {
+        mesh.vertex(self.0).origin()
+    }
+
+    /// Get an outgoing half edge
+    pub fn half_edge(&self, mesh: &HeMesh) -> HalfEdgeHandle {
+        HalfEdgeHandle(mesh.vertex(self.0).half_edge())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FaceHandle(usize);
+
+impl FaceHandle {
+    /// Construct a FaceHandle from its index
+    pub fn new(index: usize) -> FaceHandle {
+        FaceHandle(index)
+    }
+
+    /// Get the index
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    /// Get a half edge on the boundary of the face
+    pub fn half_edge(&self, mesh: &HeMesh) -> HalfEdgeHandle {
+        HalfEdgeHandle(mesh.face(self.0).half_edge())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HalfEdgeHandle(usize);
+
+impl HalfEdgeHandle {
+    /// Construct a HalfEdgeHandle from its index
+    pub fn new(index: usize) -> HalfEdgeHandle {
+        HalfEdgeHandle(index)
+    }
+
+    /// Get the index
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    /// Get the origin vertex
+    pub fn origin(&self, mesh: &HeMesh) -> VertexHandle {
+        VertexHandle(mesh.half_edge(self.0).origin())
+    }
+
+    /// Get the face
+    pub fn face(&self, mesh: &HeMesh) -> FaceHandle {
+        FaceHandle(mesh.half_edge(self.0).face())
+    }
+
+    /// Get the previous half edge
+    pub fn prev(&self, mesh: &HeMesh) -> HalfEdgeHandle {
+        HalfEdgeHandle(mesh.half_edge(self.0).prev())
+    }
+
+    /// Get the next half edge
+    pub fn next(&self, mesh: &HeMesh) -> HalfEdgeHandle {
+        HalfEdgeHandle(mesh.half_edge(self.0).next())
+    }
+
+    /// Get the twin half edge (if not a boundary)
+    pub fn twin(&self, mesh: &HeMesh) -> Option<HalfEdgeHandle> {
+        mesh.half_edge(self.0).twin().map(HalfEdgeHandle)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn traverse() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let he = HalfEdgeHandle::new(0);
+
+        assert_eq!(he.next(&mesh).prev(&mesh), he);
+        assert_eq!(he.twin(&mesh).unwrap().twin(&mesh), Some(he));
+        assert_eq!(he.face(&mesh), FaceHandle::new(0));
+
+        let twin = he.twin(&mesh).unwrap();
+        assert_eq!(twin.next(&mesh).origin(&mesh), he.origin(&mesh));
+
+        let vertex = he.origin(&mesh);
+        assert_eq!(vertex.half_edge(&mesh).origin(&mesh), vertex);
+    }
+}
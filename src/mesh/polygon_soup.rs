@@ -3,6 +3,7 @@ use crate::geometry::Vector3;
 #[derive(Debug, Clone, Default)]
 pub struct PolygonSoupMesh {
     vertices: Vec<Vector3>,
+    colors: Vec<Vector3>,
     face_offsets: Vec<usize>,
     face_vertices: Vec<usize>,
     face_patches: Vec<Option<usize>>,
@@ -30,6 +31,26 @@ impl PolygonSoupMesh {
         self.vertices.push(position);
     }
 
+    /// Insert the RGB color of the next vertex without a color. Colors are
+    /// only used when every vertex has one.
+    pub fn insert_color(&mut self, color: Vector3) {
+        self.colors.push(color);
+    }
+
+    /// Check if every vertex has an RGB color
+    pub fn has_colors(&self) -> bool {
+        !self.vertices.is_empty() && self.colors.len() == self.vertices.len()
+    }
+
+    /// Get the RGB color of a vertex (if every vertex has a color)
+    pub fn color(&self, index: usize) -> Option<Vector3> {
+        if self.has_colors() {
+            Some(self.colors[index])
+        } else {
+            None
+        }
+    }
+
     /// Get the number of faces
     pub fn n_faces(&self) -> usize {
         self.face_offsets.len()
@@ -70,4 +91,98 @@ impl PolygonSoupMesh {
     pub fn insert_patch(&mut self, name: &str) {
         self.patches.push(name.to_string());
     }
+
+    /// Validate the faces reference existing vertices and patches and have
+    /// at least three vertices each
+    pub fn validate(&self) -> Result<(), SoupError> {
+        for i in 0..self.n_faces() {
+            let (vertices, patch) = self.face(i);
+
+            if vertices.len() < 3 {
+                return Err(SoupError::DegenerateFace(i));
+            }
+
+            if vertices.iter().any(|&v| v >= self.n_vertices()) {
+                return Err(SoupError::VertexOutOfRange(i));
+            }
+
+            if patch.is_some_and(|p| p >= self.n_patches()) {
+                return Err(SoupError::PatchOutOfRange(i));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SoupError {
+    DegenerateFace(usize),
+    VertexOutOfRange(usize),
+    PatchOutOfRange(usize),
+}
+
+impl std::fmt::Display for SoupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SoupError::DegenerateFace(i) => write!(f, "face {} has fewer than 3 vertices", i),
+            SoupError::VertexOutOfRange(i) => write!(f, "face {} vertex index out of range", i),
+            SoupError::PatchOutOfRange(i) => write!(f, "face {} patch index out of range", i),
+        }
+    }
+}
+
+impl std::error::Error for SoupError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn triangle() -> PolygonSoupMesh {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 0., 0.));
+        soup.insert_vertex(Vector3::new(0., 1., 0.));
+        soup.insert_patch("patch");
+        soup
+    }
+
+    #[test]
+    fn validate() {
+        let mut soup = triangle();
+        soup.insert_face(&[0, 1, 2], Some(0));
+
+        assert!(soup.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_vertex_out_of_range() {
+        let mut soup = triangle();
+        soup.insert_face(&[0, 1, 3], None);
+
+        assert!(matches!(
+            soup.validate(),
+            Err(SoupError::VertexOutOfRange(0))
+        ));
+    }
+
+    #[test]
+    fn validate_degenerate_face() {
+        let mut soup = triangle();
+        soup.insert_face(&[0, 1, 2], None);
+        soup.insert_face(&[0, 1], None);
+
+        assert!(matches!(soup.validate(), Err(SoupError::DegenerateFace(1))));
+    }
+
+    #[test]
+    fn validate_patch_out_of_range() {
+        let mut soup = triangle();
+        soup.insert_face(&[0, 1, 2], Some(1));
+
+        assert!(matches!(
+            soup.validate(),
+            Err(SoupError::PatchOutOfRange(0))
+        ));
+    }
 }
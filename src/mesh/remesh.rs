@@ -0,0 +1,1521 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use nalgebra::{Matrix4, Vector4};
+
+use crate::geometry::{Vector3, EPSILON};
+use crate::mesh::{HeMesh, HeMeshError, PolygonSoupMesh};
+
+// Angle (in radians) between faces above which an edge is preserved as a
+// feature while remeshing
+const FEATURE_ANGLE: f64 = std::f64::consts::FRAC_PI_4;
+
+// Weight of the planes constraining boundary and patch boundary edges while
+// decimating
+const BOUNDARY_WEIGHT: f64 = 1e3;
+
+impl HeMesh {
+    /// Remesh toward near-uniform triangles with the target edge length. Each
+    /// iteration splits long edges, collapses short edges, flips edges to
+    /// equalize vertex valence, and tangentially relaxes the vertices.
+    /// Boundary, patch boundary, and feature edges are preserved. The mesh
+    /// must be composed of triangles and the target edge length must be
+    /// positive. Vertex scalars are interpolated along
+    /// the split and collapsed edges and face scalars follow the face each
+    /// new face was split from.
+    pub fn remesh(
+        &mut self,
+        target_edge_length: f64,
+        iterations: usize,
+    ) -> Result<(), HeMeshError> {
+        self.remesh_with_cancel(target_edge_length, iterations, || true)
+    }
+
+    /// Remesh as in `remesh`, checking the callback between each pass and
+    /// stopping when it returns false. The partially remeshed mesh is kept
+    /// and remains valid.
+    pub fn remesh_with_cancel<C>(
+        &mut self,
+        target_edge_length: f64,
+        iterations: usize,
+        should_continue: C,
+    ) -> Result<(), HeMeshError>
+    where
+        C: Fn() -> bool,
+    {
+        if target_edge_length <= EPSILON {
+            return Err(HeMeshError::InvalidParameter);
+        }
+
+        let max_length = target_edge_length * 4. / 3.;
+        let min_length = target_edge_length * 4. / 5.;
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+
+        mesh.insert_features(self, FEATURE_ANGLE);
+
+        for _ in 0..iterations {
+            if !should_continue() {
+                break;
+            }
+
+            while mesh.split_long_edges(max_length) > 0 && should_continue() {}
+            while mesh.collapse_short_edges(min_length, max_length) > 0 && should_continue() {}
+            while mesh.flip_edges() > 0 && should_continue() {}
+            mesh.relax();
+        }
+
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+
+    /// Split every edge longer than the max length at its midpoint until no
+    /// such edges remain. The mesh must be composed of triangles and the max
+    /// length must be positive.
+    pub fn split_long_edges(&mut self, max_length: f64) -> Result<(), HeMeshError> {
+        if max_length <= EPSILON {
+            return Err(HeMeshError::InvalidParameter);
+        }
+
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+        while mesh.split_long_edges(max_length) > 0 {}
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+
+    /// Split an edge by half edge index at its midpoint, dividing each of
+    /// its incident faces in two. The mesh must be composed of triangles.
+    /// The midpoint takes the mean vertex scalars of the edge and both
+    /// halves of a face keep its face scalars.
+    pub fn split_edge(&mut self, half_edge: usize) -> Result<(), HeMeshError> {
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+        let a = self.half_edge(half_edge).origin();
+        let b = self.half_edge(self.half_edge(half_edge).next()).origin();
+        let edges = mesh.edge_faces();
+
+        mesh.split_edge(&edges[&edge_key(a, b)], a, b);
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+
+    /// Insert vertices between the feature edges ahead of smoothing or
+    /// remeshing and return the number of edges split. An edge joining two
+    /// vertices on feature edges (face angle above the threshold in radians)
+    /// without being a feature edge itself spans the region between creases
+    /// with no free vertex, so it is split at its midpoint. Later smoothing
+    /// then moves the inserted vertices rather than pulling faces across the
+    /// creases. The mesh must be composed of triangles.
+    pub fn insert_feature_vertices(&mut self, threshold: f64) -> Result<usize, HeMeshError> {
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+        mesh.insert_features(self, threshold);
+
+        let mut count = 0;
+
+        loop {
+            match mesh.split_feature_chords() {
+                0 => break,
+                n => count += n,
+            }
+        }
+
+        *self = mesh.into_mesh(self)?;
+
+        Ok(count)
+    }
+
+    /// Tangentially relax the vertices toward the centroid of their neighbors
+    /// for a number of iterations. Vertices on boundary, patch boundary, and
+    /// feature edges (face angle above the threshold in radians) are kept in
+    /// place. The mesh must be composed of triangles.
+    pub fn relax(&mut self, iterations: usize, threshold: f64) -> Result<(), HeMeshError> {
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+        mesh.insert_features(self, threshold);
+
+        for _ in 0..iterations {
+            mesh.relax();
+        }
+
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+
+    /// Tangentially relax the vertices as in `relax` without locking any
+    /// feature edges, so each vertex moves toward the centroid of its one-ring
+    /// projected onto its tangent plane. Boundary and patch boundary vertices
+    /// are kept in place. The mesh must be composed of triangles.
+    pub fn relax_tangential(&mut self, iterations: usize) -> Result<(), HeMeshError> {
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+
+        for _ in 0..iterations {
+            mesh.relax();
+        }
+
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+
+    /// Collapse every edge shorter than the min length where doing so keeps
+    /// the mesh manifold and does not invert any faces. Boundary and patch
+    /// boundary vertices remain in place unless on a straight section. The
+    /// mesh must be composed of triangles.
+    pub fn collapse_short_edges(&mut self, min_length: f64) -> Result<(), HeMeshError> {
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+        while mesh.collapse_short_edges(min_length, f64::INFINITY) > 0 {}
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+}
+
+impl HeMesh {
+    /// Decimate toward the target number of faces by repeatedly collapsing
+    /// the edge of least quadric error. Collapses that would make the mesh
+    /// non-manifold or invert a face are skipped, so the target may not be
+    /// reached. Boundary and patch boundary edges are weighted to preserve
+    /// their shape. The mesh must be composed of triangles. The vertex
+    /// scalars of a kept vertex are interpolated toward the removed vertex
+    /// by how far the collapse moves it along the edge.
+    pub fn decimate(&mut self, target_faces: usize) -> Result<(), HeMeshError> {
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+        let locked = vec![false; mesh.vertices.len()];
+        mesh.decimate(target_faces, &locked, &mut |_, _| true);
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+
+    /// Decimate as in `decimate`, checking the callback after each collapse
+    /// and stopping when it returns false. The partially decimated mesh is
+    /// kept and remains valid.
+    pub fn decimate_with_cancel<C>(
+        &mut self,
+        target_faces: usize,
+        should_continue: C,
+    ) -> Result<(), HeMeshError>
+    where
+        C: Fn() -> bool,
+    {
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+        let locked = vec![false; mesh.vertices.len()];
+        mesh.decimate(target_faces, &locked, &mut |_, _| should_continue());
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+
+    /// Decimate toward the target number of faces while locking vertices in
+    /// place. With lock_boundary set the boundary vertices are locked so
+    /// holes do not grow. With a feature angle (in radians) the vertices on
+    /// feature edges are locked so creases stay sharp. A locked vertex is
+    /// never moved or removed, which is equivalent to an infinite quadric.
+    pub fn decimate_locked(
+        &mut self,
+        target_faces: usize,
+        lock_boundary: bool,
+        feature_angle: Option<f64>,
+    ) -> Result<(), HeMeshError> {
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+        let mut locked = vec![false; mesh.vertices.len()];
+
+        if lock_boundary {
+            for (&(a, b), faces) in mesh.edge_faces().iter() {
+                if faces.len() == 1 {
+                    locked[a] = true;
+                    locked[b] = true;
+                }
+            }
+        }
+
+        if let Some(threshold) = feature_angle {
+            for (i, j) in self.feature_edges(threshold) {
+                locked[self.half_edge(i).origin()] = true;
+                locked[self.half_edge(j).origin()] = true;
+            }
+        }
+
+        mesh.decimate(target_faces, &locked, &mut |_, _| true);
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+
+    /// Decimate toward a ratio of the current number of faces (e.g. 0.5
+    /// keeps half the faces)
+    pub fn decimate_ratio(&mut self, ratio: f64) -> Result<(), HeMeshError> {
+        self.decimate_ratio_with_progress(ratio, |_, _| {})
+    }
+
+    /// Decimate toward a ratio of the current number of faces, which must be
+    /// between zero and one. The progress callback is invoked after each
+    /// collapse with the number of faces removed so far and the number of
+    /// faces to remove.
+    pub fn decimate_ratio_with_progress<F>(
+        &mut self,
+        ratio: f64,
+        mut progress: F,
+    ) -> Result<(), HeMeshError>
+    where
+        F: FnMut(usize, usize),
+    {
+        if !(0. ..=1.).contains(&ratio) {
+            return Err(HeMeshError::InvalidParameter);
+        }
+
+        let target_faces = (self.n_faces() as f64 * ratio).round() as usize;
+        let mut mesh = TriangleMesh::from_mesh(self)?;
+        let locked = vec![false; mesh.vertices.len()];
+        mesh.decimate(target_faces, &locked, &mut |done, total| {
+            progress(done, total);
+            true
+        });
+        *self = mesh.into_mesh(self)?;
+
+        Ok(())
+    }
+}
+
+/// Candidate edge collapse ordered by increasing cost for a max-heap
+#[derive(Debug, Clone, Copy)]
+struct Collapse {
+    cost: f64,
+    keep: usize,
+    remove: usize,
+    position: Vector3,
+    stamps: (usize, usize),
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then((other.keep, other.remove).cmp(&(self.keep, self.remove)))
+    }
+}
+
+/// Edges keyed by their sorted vertex indices with the incident faces
+type EdgeFaces = HashMap<(usize, usize), Vec<usize>>;
+
+/// Triangle mesh stored as indexed faces for topological edits. Vertices are
+/// dropped when unreferenced on conversion back into a half edge mesh. The
+/// feature edges are constrained like boundary edges. Vertex scalars are
+/// interpolated along split and collapsed edges and face scalars follow the
+/// face they were split from.
+#[derive(Debug, Clone)]
+struct TriangleMesh {
+    vertices: Vec<Vector3>,
+    faces: Vec<[usize; 3]>,
+    patches: Vec<Option<usize>>,
+    features: HashSet<(usize, usize)>,
+    vertex_scalars: Vec<(String, Vec<f64>)>,
+    face_scalars: Vec<(String, Vec<f64>)>,
+}
+
+impl TriangleMesh {
+    /// Construct from a half edge mesh of triangles
+    fn from_mesh(mesh: &HeMesh) -> Result<TriangleMesh, HeMeshError> {
+        if !mesh.is_triangles() {
+            return Err(HeMeshError::NonTriangular);
+        }
+
+        let vertices = mesh.vertices().iter().map(|v| v.origin()).collect();
+        let faces = (0..mesh.n_faces())
+            .map(|i| {
+                let vertices = mesh.face_vertices(i);
+                [vertices[0], vertices[1], vertices[2]]
+            })
+            .collect();
+        let patches = mesh.faces().iter().map(|f| f.patch()).collect();
+
+        let vertex_scalars = mesh
+            .vertex_scalar_names()
+            .into_iter()
+            .map(|name| (name.to_string(), mesh.vertex_scalar(name).unwrap().to_vec()))
+            .collect();
+
+        let face_scalars = mesh
+            .face_scalar_names()
+            .into_iter()
+            .map(|name| (name.to_string(), mesh.face_scalar(name).unwrap().to_vec()))
+            .collect();
+
+        let mut mesh = TriangleMesh {
+            vertices,
+            faces,
+            patches,
+            features: HashSet::new(),
+            vertex_scalars,
+            face_scalars,
+        };
+
+        // Patch boundaries are preserved as features
+        for (&(a, b), faces) in mesh.edge_faces().iter() {
+            if faces
+                .iter()
+                .any(|&f| mesh.patches[f] != mesh.patches[faces[0]])
+            {
+                mesh.features.insert((a, b));
+            }
+        }
+
+        Ok(mesh)
+    }
+
+    /// Constrain the feature edges of the source mesh with a face angle above
+    /// the threshold (in radians)
+    fn insert_features(&mut self, source: &HeMesh, threshold: f64) {
+        for (i, j) in source.feature_edges(threshold) {
+            let a = source.half_edge(i).origin();
+            let b = source.half_edge(j).origin();
+            self.features.insert(edge_key(a, b));
+        }
+    }
+
+    /// Convert into a half edge mesh using the patches of the source mesh
+    fn into_mesh(self, source: &HeMesh) -> Result<HeMesh, HeMeshError> {
+        let mut soup = PolygonSoupMesh::new();
+        let mut index = vec![usize::MAX; self.vertices.len()];
+
+        for patch in source.patches().iter() {
+            soup.insert_patch(patch.name());
+        }
+
+        for face in self.faces.iter() {
+            for &v in face.iter() {
+                if index[v] == usize::MAX {
+                    index[v] = soup.n_vertices();
+                    soup.insert_vertex(self.vertices[v]);
+                }
+            }
+        }
+
+        for (face, patch) in self.faces.iter().zip(self.patches.iter()) {
+            soup.insert_face(&face.map(|v| index[v]), *patch);
+        }
+
+        let mut mesh = HeMesh::new(&soup)?;
+
+        for (name, values) in self.vertex_scalars {
+            let mut carried = vec![f64::NAN; mesh.n_vertices()];
+
+            for (v, &i) in index.iter().enumerate() {
+                if i != usize::MAX {
+                    carried[i] = values[v];
+                }
+            }
+
+            mesh.set_vertex_scalar(&name, carried)?;
+        }
+
+        for (name, values) in self.face_scalars {
+            mesh.set_face_scalar(&name, values)?;
+        }
+
+        Ok(mesh)
+    }
+
+    /// Get the length of an edge
+    fn length(&self, a: usize, b: usize) -> f64 {
+        (self.vertices[b] - self.vertices[a]).mag()
+    }
+
+    /// Get the normal of a face with one vertex optionally moved
+    fn face_normal(&self, face: &[usize; 3], moved: Option<(usize, Vector3)>) -> Vector3 {
+        let [p, q, r] = face.map(|v| match moved {
+            Some((u, position)) if u == v => position,
+            _ => self.vertices[v],
+        });
+
+        Vector3::cross(&(q - p), &(r - p))
+    }
+
+    /// Get the faces incident to each edge
+    fn edge_faces(&self) -> EdgeFaces {
+        let mut edges = EdgeFaces::new();
+
+        for (i, face) in self.faces.iter().enumerate() {
+            for k in 0..3 {
+                edges
+                    .entry(edge_key(face[k], face[(k + 1) % 3]))
+                    .or_default()
+                    .push(i);
+            }
+        }
+
+        edges
+    }
+
+    /// Get the faces incident to each vertex
+    fn vertex_faces(&self) -> Vec<Vec<usize>> {
+        let mut vertex_faces = vec![vec![]; self.vertices.len()];
+
+        for (i, face) in self.faces.iter().enumerate() {
+            for &v in face.iter() {
+                vertex_faces[v].push(i);
+            }
+        }
+
+        vertex_faces
+    }
+
+    /// Check if an edge is constrained as a boundary or feature edge
+    fn is_constrained(&self, edges: &EdgeFaces, key: (usize, usize)) -> bool {
+        edges[&key].len() == 1 || self.features.contains(&key)
+    }
+
+    /// Get the neighbors of each vertex across a constrained edge. This is
+    /// empty for unconstrained vertices.
+    fn constrained_neighbors(&self, edges: &EdgeFaces) -> Vec<Vec<usize>> {
+        let mut constrained = vec![vec![]; self.vertices.len()];
+
+        for &(a, b) in edges.keys() {
+            if self.is_constrained(edges, (a, b)) {
+                constrained[a].push(b);
+                constrained[b].push(a);
+            }
+        }
+
+        constrained
+    }
+
+    /// Check if a constrained vertex lies on a straight section of its
+    /// constrained edges such that removing it does not change their shape
+    fn is_straight(&self, constrained: &[Vec<usize>], v: usize) -> bool {
+        if let [u, w] = constrained[v][..] {
+            let e0 = (self.vertices[v] - self.vertices[u]).unit();
+            let e1 = (self.vertices[w] - self.vertices[v]).unit();
+            return Vector3::dot(&e0, &e1) >= 1. - EPSILON;
+        }
+
+        false
+    }
+
+    /// Get the vertices adjacent to a vertex
+    fn neighbors(&self, vertex_faces: &[Vec<usize>], v: usize) -> HashSet<usize> {
+        vertex_faces[v]
+            .iter()
+            .flat_map(|&f| self.faces[f])
+            .filter(|&u| u != v)
+            .collect()
+    }
+
+    /// Split the edges longer than the max length in a single pass and return
+    /// the number of edges split. Edges sharing a face with an edge already
+    /// split in this pass are deferred to the next pass.
+    fn split_long_edges(&mut self, max_length: f64) -> usize {
+        let edges = self.edge_faces();
+        let mut long: Vec<(f64, (usize, usize))> = edges
+            .keys()
+            .map(|&(a, b)| (self.length(a, b), (a, b)))
+            .filter(|&(length, _)| length > max_length)
+            .collect();
+
+        long.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let mut modified = vec![false; self.faces.len()];
+        let mut count = 0;
+
+        for (_, (a, b)) in long {
+            let faces = &edges[&(a, b)];
+
+            if faces.iter().any(|&f| modified[f]) {
+                continue;
+            }
+
+            for &f in faces.iter() {
+                modified[f] = true;
+            }
+
+            self.split_edge(faces, a, b);
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Split the edges joining two feature vertices that are not themselves
+    /// feature edges in a single pass and return the number of edges split.
+    /// Edges sharing a face with an edge already split in this pass are
+    /// deferred to the next pass.
+    fn split_feature_chords(&mut self) -> usize {
+        let edges = self.edge_faces();
+        let on_feature: HashSet<usize> = self.features.iter().flat_map(|&(a, b)| [a, b]).collect();
+
+        let mut chords: Vec<(usize, usize)> = edges
+            .keys()
+            .copied()
+            .filter(|key| !self.features.contains(key))
+            .filter(|(a, b)| on_feature.contains(a) && on_feature.contains(b))
+            .collect();
+
+        chords.sort();
+
+        let mut modified = vec![false; self.faces.len()];
+        let mut count = 0;
+
+        for (a, b) in chords {
+            let faces = &edges[&(a, b)];
+
+            if faces.iter().any(|&f| modified[f]) {
+                continue;
+            }
+
+            for &f in faces.iter() {
+                modified[f] = true;
+            }
+
+            self.split_edge(faces, a, b);
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Split an edge at its midpoint, dividing each of its incident faces in
+    /// two. A feature edge remains a feature along both halves.
+    fn split_edge(&mut self, faces: &[usize], a: usize, b: usize) {
+        let m = self.vertices.len();
+        self.vertices
+            .push((self.vertices[a] + self.vertices[b]) * 0.5);
+
+        for (_, values) in self.vertex_scalars.iter_mut() {
+            values.push((values[a] + values[b]) * 0.5);
+        }
+
+        for &f in faces.iter() {
+            let face = self.faces[f];
+            self.faces[f] = face.map(|v| if v == b { m } else { v });
+            self.faces.push(face.map(|v| if v == a { m } else { v }));
+            self.patches.push(self.patches[f]);
+
+            for (_, values) in self.face_scalars.iter_mut() {
+                values.push(values[f]);
+            }
+        }
+
+        if self.features.remove(&edge_key(a, b)) {
+            self.features.insert(edge_key(a, m));
+            self.features.insert(edge_key(m, b));
+        }
+    }
+
+    /// Interpolate the vertex scalars of the kept vertex of a collapse at
+    /// its new position projected onto the collapsed edge. This must be
+    /// called before the kept vertex is moved.
+    fn collapse_scalars(&mut self, keep: usize, remove: usize, position: Vector3) {
+        let edge = self.vertices[remove] - self.vertices[keep];
+        let length = Vector3::dot(&edge, &edge);
+
+        if length == 0. {
+            return;
+        }
+
+        let t = (Vector3::dot(&(position - self.vertices[keep]), &edge) / length).clamp(0., 1.);
+
+        if t > 0. {
+            for (_, values) in self.vertex_scalars.iter_mut() {
+                values[keep] += (values[remove] - values[keep]) * t;
+            }
+        }
+    }
+
+    /// Remove the flagged faces along with their patches and face scalars
+    fn remove_faces(&mut self, removed: &[bool]) {
+        let mut i = 0;
+        self.faces.retain(|_| {
+            i += 1;
+            !removed[i - 1]
+        });
+
+        let mut i = 0;
+        self.patches.retain(|_| {
+            i += 1;
+            !removed[i - 1]
+        });
+
+        for (_, values) in self.face_scalars.iter_mut() {
+            let mut i = 0;
+            values.retain(|_| {
+                i += 1;
+                !removed[i - 1]
+            });
+        }
+    }
+
+    /// Collapse the edges shorter than the min length in a single pass and
+    /// return the number of edges collapsed. A collapse is rejected if it
+    /// would create an edge longer than the max length. Vertices adjacent to
+    /// a collapse are locked for the remainder of the pass.
+    fn collapse_short_edges(&mut self, min_length: f64, max_length: f64) -> usize {
+        let edges = self.edge_faces();
+        let vertex_faces = self.vertex_faces();
+        let constrained = self.constrained_neighbors(&edges);
+
+        let mut short: Vec<(f64, (usize, usize))> = edges
+            .keys()
+            .map(|&(a, b)| (self.length(a, b), (a, b)))
+            .filter(|&(length, _)| length < min_length)
+            .collect();
+
+        short.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+        let mut locked = vec![false; self.vertices.len()];
+        let mut removed = vec![false; self.faces.len()];
+        let mut count = 0;
+
+        for (_, (a, b)) in short {
+            if locked[a] || locked[b] {
+                continue;
+            }
+
+            let faces = &edges[&(a, b)];
+            let is_boundary_edge = faces.len() == 1;
+            let is_constrained_edge = self.is_constrained(&edges, (a, b));
+
+            // Constrained vertices are kept in place unless they lie on a
+            // straight section of the constrained edge being collapsed.
+            let (keep, remove, position) =
+                match (!constrained[a].is_empty(), !constrained[b].is_empty()) {
+                    (true, true) if !is_constrained_edge => continue,
+                    (true, true) if self.is_straight(&constrained, b) => (a, b, self.vertices[a]),
+                    (true, true) if self.is_straight(&constrained, a) => (b, a, self.vertices[b]),
+                    (true, true) => continue,
+                    (true, false) => (a, b, self.vertices[a]),
+                    (false, true) => (b, a, self.vertices[b]),
+                    _ => (a, b, (self.vertices[a] + self.vertices[b]) * 0.5),
+                };
+
+            // Link condition: the shared neighbors must be exactly the
+            // vertices opposite the collapsed edge.
+            let neighbors_keep = self.neighbors(&vertex_faces, keep);
+            let neighbors_remove = self.neighbors(&vertex_faces, remove);
+            let shared: HashSet<usize> = neighbors_keep
+                .intersection(&neighbors_remove)
+                .copied()
+                .collect();
+
+            let opposite: HashSet<usize> = faces
+                .iter()
+                .flat_map(|&f| self.faces[f])
+                .filter(|&v| v != a && v != b)
+                .collect();
+
+            if shared != opposite {
+                continue;
+            }
+
+            let ring: HashSet<usize> = neighbors_keep
+                .union(&neighbors_remove)
+                .copied()
+                .filter(|&v| v != a && v != b)
+                .collect();
+
+            if !is_boundary_edge && faces.len() == 2 && ring.len() <= 3 {
+                continue;
+            }
+
+            if ring
+                .iter()
+                .any(|&v| (self.vertices[v] - position).mag() > max_length)
+            {
+                continue;
+            }
+
+            // Reject collapses that would invert or degenerate a face
+            let inverted = vertex_faces[keep]
+                .iter()
+                .chain(vertex_faces[remove].iter())
+                .filter(|f| !faces.contains(f))
+                .any(|&f| {
+                    let face = self.faces[f];
+                    let before = self.face_normal(&face, None);
+                    let moved = face.map(|v| if v == remove { keep } else { v });
+                    let after = self.face_normal(&moved, Some((keep, position)));
+
+                    Vector3::dot(&before, &after) <= EPSILON
+                });
+
+            if inverted {
+                continue;
+            }
+
+            for &f in faces.iter() {
+                removed[f] = true;
+            }
+
+            for &f in vertex_faces[remove].iter() {
+                self.faces[f] = self.faces[f].map(|v| if v == remove { keep } else { v });
+            }
+
+            self.collapse_scalars(keep, remove, position);
+            self.vertices[keep] = position;
+
+            for &v in constrained[remove].iter() {
+                if self.features.remove(&edge_key(remove, v)) && v != keep {
+                    self.features.insert(edge_key(keep, v));
+                }
+            }
+
+            for &v in ring.iter().chain([keep, remove].iter()) {
+                locked[v] = true;
+            }
+
+            count += 1;
+        }
+
+        self.remove_faces(&removed);
+
+        count
+    }
+
+    /// Flip the edges whose flip reduces the deviation of the adjacent vertex
+    /// valences from their ideal (six for interior and four for boundary
+    /// vertices) in a single pass and return the number of edges flipped.
+    fn flip_edges(&mut self) -> usize {
+        let edges = self.edge_faces();
+        let constrained = self.constrained_neighbors(&edges);
+        let mut valence = vec![0isize; self.vertices.len()];
+
+        for &(a, b) in edges.keys() {
+            valence[a] += 1;
+            valence[b] += 1;
+        }
+
+        let ideal = |v: usize| if constrained[v].is_empty() { 6 } else { 4 };
+        let mut keys: Vec<(usize, usize)> = edges.keys().copied().collect();
+        keys.sort();
+
+        let mut locked = vec![false; self.vertices.len()];
+        let mut flipped = HashSet::<(usize, usize)>::new();
+        let mut count = 0;
+
+        for (a, b) in keys {
+            if locked[a] || locked[b] || self.is_constrained(&edges, (a, b)) {
+                continue;
+            }
+
+            let faces = &edges[&(a, b)];
+
+            if faces.len() != 2 || self.patches[faces[0]] != self.patches[faces[1]] {
+                continue;
+            }
+
+            // Orient the faces as (a, b, c) and (b, a, d)
+            let (f0, f1) = if rotate_to(&self.faces[faces[0]], a)[1] == b {
+                (faces[0], faces[1])
+            } else {
+                (faces[1], faces[0])
+            };
+
+            let c = rotate_to(&self.faces[f0], a)[2];
+            let d = rotate_to(&self.faces[f1], b)[2];
+
+            if c == d || edges.contains_key(&edge_key(c, d)) || flipped.contains(&edge_key(c, d)) {
+                continue;
+            }
+
+            let deviation = |va: isize, vb: isize, vc: isize, vd: isize| {
+                (va - ideal(a)).abs()
+                    + (vb - ideal(b)).abs()
+                    + (vc - ideal(c)).abs()
+                    + (vd - ideal(d)).abs()
+            };
+
+            let before = deviation(valence[a], valence[b], valence[c], valence[d]);
+            let after = deviation(
+                valence[a] - 1,
+                valence[b] - 1,
+                valence[c] + 1,
+                valence[d] + 1,
+            );
+
+            if after >= before {
+                continue;
+            }
+
+            // Reject flips that fold the faces over
+            let normal =
+                self.face_normal(&self.faces[f0], None) + self.face_normal(&self.faces[f1], None);
+            let g0 = [a, d, c];
+            let g1 = [d, b, c];
+
+            if Vector3::dot(&self.face_normal(&g0, None), &normal) <= EPSILON
+                || Vector3::dot(&self.face_normal(&g1, None), &normal) <= EPSILON
+            {
+                continue;
+            }
+
+            self.faces[f0] = g0;
+            self.faces[f1] = g1;
+            valence[a] -= 1;
+            valence[b] -= 1;
+            valence[c] += 1;
+            valence[d] += 1;
+            flipped.insert(edge_key(c, d));
+
+            for v in [a, b, c, d] {
+                locked[v] = true;
+            }
+
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Collapse the edges of least quadric error until no more than the
+    /// target number of faces remain, no valid collapse is left, or the
+    /// progress callback returns false. Locked vertices are never moved or
+    /// removed.
+    fn decimate<F>(&mut self, target_faces: usize, locked: &[bool], progress: &mut F)
+    where
+        F: FnMut(usize, usize) -> bool,
+    {
+        let n_faces = self.faces.len();
+
+        if target_faces >= n_faces {
+            return;
+        }
+
+        let edges = self.edge_faces();
+        let mut constrained = self.constrained_neighbors(&edges);
+        let mut quadrics = vec![Matrix4::<f64>::zeros(); self.vertices.len()];
+
+        for face in self.faces.iter() {
+            let normal = self.face_normal(face, None);
+
+            if normal.mag() > EPSILON {
+                let quadric = plane_quadric(&normal.unit(), &self.vertices[face[0]]);
+
+                for &v in face.iter() {
+                    quadrics[v] += quadric;
+                }
+            }
+        }
+
+        // Constrained edges are held in place by planes perpendicular to
+        // their incident faces
+        for (&(a, b), faces) in edges.iter() {
+            if self.is_constrained(&edges, (a, b)) {
+                let normal = self.face_normal(&self.faces[faces[0]], None);
+                let edge = self.vertices[b] - self.vertices[a];
+                let perpendicular = Vector3::cross(&edge, &normal);
+
+                if perpendicular.mag() > EPSILON {
+                    let quadric = plane_quadric(&perpendicular.unit(), &self.vertices[a])
+                        * (BOUNDARY_WEIGHT * edge.mag() * edge.mag());
+                    quadrics[a] += quadric;
+                    quadrics[b] += quadric;
+                }
+            }
+        }
+
+        let mut vertex_faces: Vec<HashSet<usize>> = self
+            .vertex_faces()
+            .into_iter()
+            .map(|faces| faces.into_iter().collect())
+            .collect();
+
+        let mut stamps = vec![0; self.vertices.len()];
+        let mut removed = vec![false; n_faces];
+        let mut n_removed = 0;
+        let mut heap = BinaryHeap::new();
+
+        for &(a, b) in edges.keys() {
+            heap.extend(self.collapse_candidate(&quadrics, &constrained, locked, &stamps, a, b));
+        }
+
+        while n_faces - n_removed > target_faces {
+            let Some(collapse) = heap.pop() else {
+                break;
+            };
+
+            let Collapse {
+                keep,
+                remove,
+                position,
+                ..
+            } = collapse;
+
+            if collapse.stamps != (stamps[keep], stamps[remove]) {
+                continue;
+            }
+
+            let shared: Vec<usize> = vertex_faces[keep]
+                .intersection(&vertex_faces[remove])
+                .copied()
+                .collect();
+
+            if shared.is_empty() {
+                continue;
+            }
+
+            // Link condition: the shared neighbors must be exactly the
+            // vertices opposite the collapsed edge.
+            let neighbors_keep = ring_vertices(&self.faces, &vertex_faces, keep);
+            let neighbors_remove = ring_vertices(&self.faces, &vertex_faces, remove);
+
+            let opposite: HashSet<usize> = shared
+                .iter()
+                .flat_map(|&f| self.faces[f])
+                .filter(|&v| v != keep && v != remove)
+                .collect();
+
+            let linked: HashSet<usize> = neighbors_keep
+                .intersection(&neighbors_remove)
+                .copied()
+                .collect();
+
+            if linked != opposite {
+                continue;
+            }
+
+            let ring = neighbors_keep.union(&neighbors_remove).count() - 2;
+
+            if shared.len() == 2 && ring <= 3 {
+                continue;
+            }
+
+            // Reject collapses that would invert or degenerate a face
+            let inverted = vertex_faces[keep]
+                .iter()
+                .chain(vertex_faces[remove].iter())
+                .filter(|f| !shared.contains(f))
+                .any(|&f| {
+                    let face = self.faces[f];
+                    let before = self.face_normal(&face, None);
+                    let moved = face.map(|v| if v == remove { keep } else { v });
+                    let after = self.face_normal(&moved, Some((keep, position)));
+
+                    Vector3::dot(&before, &after) <= EPSILON
+                });
+
+            if inverted {
+                continue;
+            }
+
+            for &f in shared.iter() {
+                removed[f] = true;
+
+                for v in self.faces[f] {
+                    vertex_faces[v].remove(&f);
+                }
+            }
+
+            for f in std::mem::take(&mut vertex_faces[remove]) {
+                self.faces[f] = self.faces[f].map(|v| if v == remove { keep } else { v });
+                vertex_faces[keep].insert(f);
+            }
+
+            for u in std::mem::take(&mut constrained[remove]) {
+                constrained[u].retain(|&w| w != remove);
+
+                if u != keep && !constrained[keep].contains(&u) {
+                    constrained[keep].push(u);
+                    constrained[u].push(keep);
+                }
+            }
+
+            self.collapse_scalars(keep, remove, position);
+            self.vertices[keep] = position;
+            quadrics[keep] = quadrics[keep] + quadrics[remove];
+            stamps[keep] += 1;
+            stamps[remove] += 1;
+
+            for u in ring_vertices(&self.faces, &vertex_faces, keep) {
+                heap.extend(self.collapse_candidate(
+                    &quadrics,
+                    &constrained,
+                    locked,
+                    &stamps,
+                    keep,
+                    u,
+                ));
+            }
+
+            n_removed += shared.len();
+
+            if !progress(n_removed, n_faces - target_faces) {
+                break;
+            }
+        }
+
+        self.remove_faces(&removed);
+    }
+
+    /// Get the collapse of an edge with the least quadric error. Constrained
+    /// vertices are kept in place and edges between two constrained vertices
+    /// are only collapsed along constrained edges. Locked vertices are never
+    /// removed or moved.
+    fn collapse_candidate(
+        &self,
+        quadrics: &[Matrix4<f64>],
+        constrained: &[Vec<usize>],
+        locked: &[bool],
+        stamps: &[usize],
+        a: usize,
+        b: usize,
+    ) -> Option<Collapse> {
+        let quadric = quadrics[a] + quadrics[b];
+        let (pa, pb) = (self.vertices[a], self.vertices[b]);
+
+        let mut candidates = match (!constrained[a].is_empty(), !constrained[b].is_empty()) {
+            (true, true) if !constrained[a].contains(&b) => return None,
+            (true, true) => vec![(a, b, pa), (b, a, pb)],
+            (true, false) => vec![(a, b, pa)],
+            (false, true) => vec![(b, a, pb)],
+            _ => vec![(a, b, pa), (b, a, pb), (a, b, (pa + pb) * 0.5)],
+        };
+
+        if constrained[a].is_empty() && constrained[b].is_empty() {
+            let mut system = quadric;
+            system.set_row(3, &Vector4::new(0., 0., 0., 1.).transpose());
+
+            if let Some(inverse) = system.try_inverse() {
+                let v = inverse * Vector4::new(0., 0., 0., 1.);
+                candidates.push((a, b, Vector3::new(v[0], v[1], v[2])));
+            }
+        }
+
+        candidates.retain(|&(keep, remove, position)| {
+            !locked[remove] && (!locked[keep] || position == self.vertices[keep])
+        });
+
+        candidates
+            .into_iter()
+            .map(|(keep, remove, position)| {
+                let v = Vector4::new(position[0], position[1], position[2], 1.);
+                let cost = (v.transpose() * quadric * v)[0].max(0.);
+
+                Collapse {
+                    cost,
+                    keep,
+                    remove,
+                    position,
+                    stamps: (stamps[keep], stamps[remove]),
+                }
+            })
+            .min_by(|x, y| x.cost.total_cmp(&y.cost))
+    }
+
+    /// Move each unconstrained vertex toward the centroid of its neighbors
+    /// within its tangent plane
+    fn relax(&mut self) {
+        let edges = self.edge_faces();
+        let constrained = self.constrained_neighbors(&edges);
+        let vertex_faces = self.vertex_faces();
+        let mut positions = self.vertices.clone();
+
+        for (v, position) in positions.iter_mut().enumerate() {
+            if !constrained[v].is_empty() || vertex_faces[v].is_empty() {
+                continue;
+            }
+
+            let neighbors = self.neighbors(&vertex_faces, v);
+            let centroid = neighbors
+                .iter()
+                .fold(Vector3::zeros(), |c, &u| c + self.vertices[u])
+                / neighbors.len() as f64;
+
+            let normal = vertex_faces[v].iter().fold(Vector3::zeros(), |n, &f| {
+                n + self.face_normal(&self.faces[f], None)
+            });
+
+            if normal.mag() <= EPSILON {
+                continue;
+            }
+
+            let normal = normal.unit();
+            let p = self.vertices[v];
+            *position = centroid + normal * Vector3::dot(&normal, &(p - centroid));
+        }
+
+        self.vertices = positions;
+    }
+}
+
+/// Get the vertices sharing a face with a vertex
+fn ring_vertices(
+    faces: &[[usize; 3]],
+    vertex_faces: &[HashSet<usize>],
+    v: usize,
+) -> HashSet<usize> {
+    vertex_faces[v]
+        .iter()
+        .flat_map(|&f| faces[f])
+        .filter(|&u| u != v)
+        .collect()
+}
+
+/// Get the quadric of the squared distance to a plane
+fn plane_quadric(normal: &Vector3, origin: &Vector3) -> Matrix4<f64> {
+    let d = -Vector3::dot(normal, origin);
+    let plane = Vector4::new(normal[0], normal[1], normal[2], d);
+    plane * plane.transpose()
+}
+
+/// Rotate the vertices of a face so the given vertex is first
+fn rotate_to(face: &[usize; 3], v: usize) -> [usize; 3] {
+    if face[0] == v {
+        *face
+    } else if face[1] == v {
+        [face[1], face[2], face[0]]
+    } else {
+        [face[2], face[0], face[1]]
+    }
+}
+
+/// Get the key of an edge from its vertex indices
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::mesh::fixtures::plane_grid;
+
+    fn edge_lengths(mesh: &HeMesh) -> Vec<f64> {
+        mesh.half_edges()
+            .iter()
+            .map(|h| {
+                let p = mesh.vertex(h.origin()).origin();
+                let q = mesh.vertex(mesh.half_edge(h.next()).origin()).origin();
+                (q - p).mag()
+            })
+            .collect()
+    }
+
+    fn area(mesh: &HeMesh) -> f64 {
+        mesh.triangles().map(|t| t.area()).sum()
+    }
+
+    #[test]
+    fn split_long_edges() {
+        let mut mesh = plane_grid(4, 1., false);
+        mesh.split_long_edges(0.3).unwrap();
+
+        assert!(edge_lengths(&mesh).iter().all(|&l| l <= 0.3));
+        assert!((area(&mesh) - 9.).abs() < 1e-9);
+        assert!(mesh.is_consistent());
+    }
+
+    #[test]
+    fn split_long_edges_invalid() {
+        let mut mesh = plane_grid(4, 1., false);
+        let result = mesh.split_long_edges(0.);
+
+        assert!(matches!(result, Err(HeMeshError::InvalidParameter)));
+        assert_eq!(mesh.n_faces(), 18);
+    }
+
+    #[test]
+    fn split_long_edges_closed() {
+        let path = "tests/fixtures/box.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.split_long_edges(0.4).unwrap();
+
+        assert!(edge_lengths(&mesh).iter().all(|&l| l <= 0.4));
+        assert!(mesh.is_closed());
+        assert!((mesh.volume() - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn split_collapse_scalars() {
+        let mut mesh = plane_grid(5, 0.25, false);
+        let x = mesh.vertices().iter().map(|v| v.origin().x()).collect();
+        let ids = (0..mesh.n_faces()).map(|f| f as f64).collect();
+        mesh.set_vertex_scalar("x", x).unwrap();
+        mesh.set_face_scalar("id", ids).unwrap();
+
+        let original = mesh.clone();
+        let linear = |mesh: &HeMesh| {
+            let x = mesh.vertex_scalar("x").unwrap();
+
+            mesh.vertices()
+                .iter()
+                .enumerate()
+                .all(|(v, vertex)| (x[v] - vertex.origin().x()).abs() < 1e-12)
+        };
+
+        // Each split face lies within the face it was split from
+        mesh.split_long_edges(0.1).unwrap();
+        let ids = mesh.face_scalar("id").unwrap();
+
+        assert!(linear(&mesh));
+        assert!(mesh.n_faces() > original.n_faces());
+
+        for (f, triangle) in mesh.triangles().enumerate() {
+            let source = original.face_triangles(ids[f] as usize)[0];
+            assert!(source.contains(&triangle.center(), 1e-12));
+        }
+
+        // A linear field stays exact along collapsed edges
+        let n_faces = mesh.n_faces();
+        mesh.collapse_short_edges(0.2).unwrap();
+
+        assert!(linear(&mesh));
+        assert!(mesh.n_faces() < n_faces);
+        assert_eq!(mesh.face_scalar("id").unwrap().len(), mesh.n_faces());
+    }
+
+    #[test]
+    fn collapse_short_edges() {
+        let mut mesh = plane_grid(11, 0.1, false);
+        let n_faces = mesh.n_faces();
+        mesh.collapse_short_edges(0.25).unwrap();
+
+        let lengths = edge_lengths(&mesh);
+        let min_length = lengths.iter().copied().fold(f64::INFINITY, f64::min);
+
+        assert!(mesh.n_faces() < n_faces);
+        assert!(min_length > 0.1);
+        assert!((area(&mesh) - 1.).abs() < 1e-9);
+        assert!(mesh.is_consistent());
+    }
+
+    // Get the mean and standard deviation of the edge lengths
+    fn edge_statistics(mesh: &HeMesh) -> (f64, f64) {
+        let lengths = edge_lengths(mesh);
+        let n = lengths.len() as f64;
+        let mean = lengths.iter().sum::<f64>() / n;
+        let variance = lengths.iter().map(|l| (l - mean).powi(2)).sum::<f64>() / n;
+        (mean, variance.sqrt())
+    }
+
+    #[test]
+    fn remesh() {
+        let path = "tests/fixtures/sphere.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.split_long_edges(0.05).unwrap();
+
+        let target = 0.1;
+        let (mean_before, std_before) = edge_statistics(&mesh);
+        mesh.remesh(target, 5).unwrap();
+        let (mean, std_after) = edge_statistics(&mesh);
+
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!(std_after / mean < 0.5 * std_before / mean_before);
+        assert!((mean - target).abs() < 0.2 * target);
+    }
+
+    #[test]
+    fn remesh_invalid() {
+        let mut mesh = plane_grid(4, 1., false);
+        let result = mesh.remesh(-1., 3);
+
+        assert!(matches!(result, Err(HeMeshError::InvalidParameter)));
+        assert_eq!(mesh.n_faces(), 18);
+    }
+
+    #[test]
+    fn remesh_preserves_boundary() {
+        let mut mesh = plane_grid(5, 0.25, false);
+        mesh.remesh(0.1, 3).unwrap();
+
+        assert!((area(&mesh) - 1.).abs() < 1e-9);
+        assert!(mesh.is_consistent());
+    }
+
+    #[test]
+    fn split_edge() {
+        let mut mesh = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+        mesh.split_edge(0).unwrap();
+
+        assert_eq!(mesh.n_vertices(), 9);
+        assert_eq!(mesh.n_faces(), 14);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn insert_feature_vertices() {
+        let threshold = std::f64::consts::FRAC_PI_4;
+        let mut mesh = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+        let count = mesh.insert_feature_vertices(threshold).unwrap();
+
+        assert_eq!(count, 6);
+        assert_eq!(mesh.n_vertices(), 14);
+        assert_eq!(mesh.feature_edges(threshold).len(), 12);
+
+        mesh.relax(5, threshold).unwrap();
+
+        assert_eq!(mesh.feature_edges(threshold).len(), 12);
+        assert!((mesh.volume() - 1.).abs() < 1e-12);
+        assert_eq!(mesh.bounds().min(), Vector3::new(-0.5, -0.5, -0.5));
+        assert_eq!(mesh.bounds().max(), Vector3::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn collapse_short_edges_nontriangular() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 1., 0.));
+        soup.insert_vertex(Vector3::new(0., 1., 0.));
+        soup.insert_face(&[0, 1, 2, 3], None);
+
+        let mut mesh = HeMesh::new(&soup).unwrap();
+        let result = mesh.collapse_short_edges(0.5);
+
+        assert!(result.is_err_and(|e| e.to_string() == "non-triangular mesh"));
+    }
+
+    #[test]
+    fn decimate_ratio() {
+        let path = "tests/fixtures/sphere.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.split_long_edges(0.1).unwrap();
+
+        let n_faces = mesh.n_faces();
+        let volume = mesh.volume();
+        let target = (n_faces as f64 * 0.25).round() as usize;
+        let mut calls = vec![];
+        mesh.decimate_ratio_with_progress(0.25, |done, total| calls.push((done, total)))
+            .unwrap();
+
+        assert!(mesh.n_faces().abs_diff(target) <= 1);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - volume).abs() < 0.1 * volume);
+
+        assert!(!calls.is_empty());
+        assert!(calls.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(calls.iter().all(|&(_, total)| total == n_faces - target));
+    }
+
+    #[test]
+    fn decimate_ratio_invalid() {
+        let path = "tests/fixtures/sphere.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let n_faces = mesh.n_faces();
+
+        for ratio in [-0.5, 1.5, f64::NAN] {
+            let result = mesh.decimate_ratio(ratio);
+            assert!(matches!(result, Err(HeMeshError::InvalidParameter)));
+        }
+
+        assert_eq!(mesh.n_faces(), n_faces);
+    }
+
+    #[test]
+    fn decimate_with_cancel() {
+        let path = "tests/fixtures/sphere.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let n_faces = mesh.n_faces();
+        let collapses = std::cell::Cell::new(0);
+
+        mesh.decimate_with_cancel(0, || {
+            collapses.set(collapses.get() + 1);
+            collapses.get() < 10
+        })
+        .unwrap();
+
+        assert_eq!(collapses.get(), 10);
+        assert_eq!(mesh.n_faces(), n_faces - 20);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+    }
+
+    #[test]
+    fn remesh_with_cancel() {
+        let path = "tests/fixtures/sphere.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let n_faces = mesh.n_faces();
+
+        mesh.remesh_with_cancel(0.05, 3, || false).unwrap();
+
+        assert_eq!(mesh.n_faces(), n_faces);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+    }
+
+    #[test]
+    fn decimate_progress_unchanged() {
+        let path = "tests/fixtures/sphere.obj";
+        let mut a = HeMesh::import_obj(path).unwrap();
+        let mut b = a.clone();
+
+        a.decimate_ratio(0.5).unwrap();
+        b.decimate_ratio_with_progress(0.5, |_, _| {}).unwrap();
+
+        assert_eq!(a.n_faces(), b.n_faces());
+
+        for (u, v) in a.vertices().iter().zip(b.vertices().iter()) {
+            assert_eq!(u.origin(), v.origin());
+        }
+    }
+
+    #[test]
+    fn decimate_preserves_boundary() {
+        let mut mesh = plane_grid(11, 0.1, false);
+        mesh.decimate(20).unwrap();
+
+        assert!(mesh.n_faces() <= 21);
+        assert!((area(&mesh) - 1.).abs() < 1e-9);
+        assert!(mesh.is_consistent());
+    }
+
+    #[test]
+    fn decimate_locked_boundary() {
+        let boundary = |mesh: &HeMesh| {
+            let mut points: Vec<[f64; 3]> = mesh
+                .half_edges()
+                .iter()
+                .filter(|h| h.is_boundary())
+                .map(|h| mesh.vertex(h.origin()).origin().into())
+                .collect();
+
+            points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            points
+        };
+
+        let mut mesh = plane_grid(11, 0.1, false);
+        let before = boundary(&mesh);
+        mesh.decimate_locked(20, true, None).unwrap();
+
+        assert_eq!(before.len(), 40);
+        assert_eq!(boundary(&mesh), before);
+        assert!(mesh.n_faces() < 200);
+        assert!((area(&mesh) - 1.).abs() < 1e-9);
+        assert!(mesh.is_consistent());
+    }
+
+    #[test]
+    fn decimate_locked_features() {
+        let threshold = std::f64::consts::FRAC_PI_4;
+        let mut mesh = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+        mesh.split_long_edges(0.2).unwrap();
+        mesh.decimate_locked(0, false, Some(threshold)).unwrap();
+
+        assert!(mesh.is_closed());
+        assert!((mesh.volume() - 1.).abs() < 1e-9);
+        assert_eq!(mesh.bounds().min(), Vector3::new(-0.5, -0.5, -0.5));
+        assert_eq!(mesh.bounds().max(), Vector3::new(0.5, 0.5, 0.5));
+    }
+}
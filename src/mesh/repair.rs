@@ -0,0 +1,627 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::geometry::{Triangle, Vector3, EPSILON};
+use crate::mesh::half_edge::{triangulate_polygon, weld_points};
+use crate::mesh::{HeMesh, HeMeshError, PolygonSoupMesh};
+
+/// Faces as their vertex indices, optional patch, and the source face they
+/// were taken from (None for faces filling a hole)
+type Faces = Vec<(Vec<usize>, Option<usize>, Option<usize>)>;
+
+#[derive(Debug, Clone)]
+pub struct RepairOptions {
+    /// Weld vertices within the tolerance (disabled if None)
+    pub weld_tolerance: Option<f64>,
+    /// Remove faces with repeated vertices or zero area
+    pub remove_degenerate_faces: bool,
+    /// Remove faces using the same vertices as an earlier face
+    pub remove_duplicate_faces: bool,
+    /// Fill holes bounded by at most this many edges (disabled if None)
+    pub max_hole_edges: Option<usize>,
+    /// Orient each closed component outward
+    pub orient_outward: bool,
+}
+
+impl Default for RepairOptions {
+    fn default() -> RepairOptions {
+        RepairOptions {
+            weld_tolerance: Some(EPSILON),
+            remove_degenerate_faces: true,
+            remove_duplicate_faces: true,
+            max_hole_edges: Some(8),
+            orient_outward: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RepairReport {
+    n_welded_vertices: usize,
+    n_degenerate_faces: usize,
+    n_duplicate_faces: usize,
+    n_filled_holes: usize,
+    n_flipped_faces: usize,
+}
+
+impl RepairReport {
+    /// Get the number of vertices merged into another vertex
+    pub fn n_welded_vertices(&self) -> usize {
+        self.n_welded_vertices
+    }
+
+    /// Get the number of degenerate faces removed
+    pub fn n_degenerate_faces(&self) -> usize {
+        self.n_degenerate_faces
+    }
+
+    /// Get the number of duplicate faces removed
+    pub fn n_duplicate_faces(&self) -> usize {
+        self.n_duplicate_faces
+    }
+
+    /// Get the number of holes filled
+    pub fn n_filled_holes(&self) -> usize {
+        self.n_filled_holes
+    }
+
+    /// Get the number of remaining original faces whose winding was flipped
+    pub fn n_flipped_faces(&self) -> usize {
+        self.n_flipped_faces
+    }
+}
+
+impl HeMesh {
+    /// Repair the mesh by welding vertices, removing degenerate and duplicate
+    /// faces, filling small holes, and orienting outward as enabled by the
+    /// options. Filling holes or orienting first orients the faces
+    /// consistently. Unreferenced vertices are removed. Welded vertices take
+    /// the scalars of the vertex they are welded to and faces filling a hole
+    /// have NaN face scalars.
+    pub fn repair(&mut self, options: RepairOptions) -> Result<RepairReport, HeMeshError> {
+        let mut report = RepairReport::default();
+        let points: Vec<Vector3> = self.vertices().iter().map(|v| v.origin()).collect();
+
+        let mut faces: Faces = (0..self.n_faces())
+            .map(|f| (self.face_vertices(f), self.face(f).patch(), Some(f)))
+            .collect();
+
+        if let Some(tolerance) = options.weld_tolerance {
+            let welded = weld_points(&points, tolerance);
+            report.n_welded_vertices = welded.iter().enumerate().filter(|(i, &j)| *i != j).count();
+
+            for (vertices, _, _) in faces.iter_mut() {
+                for v in vertices.iter_mut() {
+                    *v = welded[*v];
+                }
+            }
+        }
+
+        if options.remove_degenerate_faces {
+            let n = faces.len();
+            faces.retain(|(vertices, _, _)| !is_degenerate(&points, vertices));
+            report.n_degenerate_faces = n - faces.len();
+        }
+
+        if options.remove_duplicate_faces {
+            let n = faces.len();
+            let mut index = HashSet::<Vec<usize>>::new();
+
+            faces.retain(|(vertices, _, _)| index.insert(sorted(vertices)));
+
+            report.n_duplicate_faces = n - faces.len();
+        }
+
+        let original = faces.clone();
+        let mut mesh = build_mesh(self, &points, &faces)?;
+
+        if options.max_hole_edges.is_some() || options.orient_outward {
+            mesh.orient();
+        }
+
+        if options.max_hole_edges.is_some() {
+            report.n_filled_holes = mesh.fill_holes(options.max_hole_edges)?;
+        }
+
+        if options.orient_outward {
+            mesh.orient_outward();
+        }
+
+        report.n_flipped_faces = original
+            .iter()
+            .enumerate()
+            .filter(|(f, (vertices, _, _))| {
+                // Vertices are renumbered when the mesh is rebuilt so the
+                // winding is compared using the positions.
+                let current: Vec<Vector3> = mesh
+                    .face_vertices(*f)
+                    .iter()
+                    .map(|&v| mesh.vertex(v).origin())
+                    .collect();
+
+                let i = current
+                    .iter()
+                    .position(|&p| p == points[vertices[0]])
+                    .unwrap();
+                current[(i + 1) % current.len()] != points[vertices[1]]
+            })
+            .count();
+
+        *self = mesh;
+
+        Ok(report)
+    }
+
+    /// Fill the holes bounded by at most the maximum number of edges (or all
+    /// holes if None) and return the number of holes filled. Each hole is
+    /// triangulated by ear clipping into faces not assigned to a patch. The
+    /// mesh should be consistently oriented. The mesh is rebuilt when a hole
+    /// is filled, so vertices may be renumbered and the filling faces have
+    /// NaN face scalars.
+    pub fn fill_holes(&mut self, max_edges: Option<usize>) -> Result<usize, HeMeshError> {
+        self.fill_loops(|hole| max_edges.is_none_or(|m| hole.len() <= m))
+    }
+
+    // Fill the holes whose boundary loop is selected by a predicate on its
+    // points and return the number of holes filled
+    fn fill_loops<P>(&mut self, predicate: P) -> Result<usize, HeMeshError>
+    where
+        P: Fn(&[Vector3]) -> bool,
+    {
+        let points: Vec<Vector3> = self.vertices().iter().map(|v| v.origin()).collect();
+        let mut faces: Faces = (0..self.n_faces())
+            .map(|f| (self.face_vertices(f), self.face(f).patch(), Some(f)))
+            .collect();
+
+        let existing: HashSet<Vec<usize>> = faces.iter().map(|(v, _, _)| sorted(v)).collect();
+        let mut n_filled = 0;
+
+        // A hole bounded by a single face (e.g. an isolated face) is not
+        // filled since it would duplicate the face.
+        for hole in self.boundary_loops() {
+            // The filling faces traverse the hole opposite to the boundary
+            // half edges.
+            let hole: Vec<usize> = hole.into_iter().rev().collect();
+            let polygon: Vec<Vector3> = hole.iter().map(|&v| points[v]).collect();
+
+            if predicate(&polygon) && !existing.contains(&sorted(&hole)) {
+                for [a, b, c] in triangulate_polygon(&polygon) {
+                    faces.push((vec![hole[a], hole[b], hole[c]], None, None));
+                }
+
+                n_filled += 1;
+            }
+        }
+
+        if n_filled > 0 {
+            *self = build_mesh(self, &points, &faces)?;
+        }
+
+        Ok(n_filled)
+    }
+
+    /// Fill only the holes bounded by at most the maximum number of edges
+    /// and return the number of holes filled. This caps small gaps left by
+    /// an import while leaving intentional openings alone.
+    pub fn fill_small_holes(&mut self, max_edges: usize) -> Result<usize, HeMeshError> {
+        self.fill_holes(Some(max_edges))
+    }
+
+    /// Remove the connected components with fewer than the minimum number of
+    /// faces (e.g. floating noise from a scan) and return the number of
+    /// components removed. Patches and scalar attributes are preserved for
+    /// the remaining faces and vertices.
+    pub fn remove_small_components(&mut self, min_faces: usize) -> Result<usize, HeMeshError> {
+        let small: Vec<Vec<usize>> = self
+            .components()
+            .into_iter()
+            .filter(|faces| faces.len() < min_faces)
+            .collect();
+
+        if !small.is_empty() {
+            self.remove_faces(&small.concat())?;
+        }
+
+        Ok(small.len())
+    }
+
+    /// Delete the faces of a patch by name and fill the exposed holes. This
+    /// removes a feature (e.g. an embossed logo) in one step. Holes that do
+    /// not touch the boundary of the patch are left open.
+    pub fn delete_and_fill(&mut self, name: &str) -> Result<(), HeMeshError> {
+        let patch = self.patches().iter().position(|p| p.name() == name);
+        let mut removed = HashSet::new();
+        let mut kept = HashSet::new();
+
+        for f in 0..self.n_faces() {
+            let vertices = self.face_vertices(f);
+
+            if patch.is_some() && self.face(f).patch() == patch {
+                removed.extend(vertices);
+            } else {
+                kept.extend(vertices);
+            }
+        }
+
+        // Vertices are renumbered when the faces are removed so the patch
+        // boundary is recorded by position
+        let boundary: Vec<Vector3> = removed
+            .intersection(&kept)
+            .map(|&v| self.vertex(v).origin())
+            .collect();
+
+        self.remove_patch_names(&[name])?;
+
+        self.fill_loops(|hole| hole.iter().any(|p| boundary.contains(p)))?;
+
+        Ok(())
+    }
+
+    /// Get the boundary loops as closed polylines of vertex indices ordered
+    /// along the boundary half edges. Each polyline repeats its first vertex
+    /// at the end, matching `feature_lines`. Loops through a vertex with
+    /// several boundary half edges are skipped.
+    pub fn boundary_lines(&self) -> Vec<Vec<usize>> {
+        self.boundary_loops()
+            .into_iter()
+            .map(|mut vertices| {
+                vertices.push(vertices[0]);
+                vertices
+            })
+            .collect()
+    }
+
+    // Get the boundary loops as vertex indices ordered along the boundary
+    // half edges. Loops through a vertex with several boundary half edges
+    // are skipped.
+    fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let mut outgoing = HashMap::<usize, Vec<usize>>::new();
+
+        for (i, half_edge) in self.half_edges().iter().enumerate() {
+            if half_edge.is_boundary() {
+                outgoing.entry(half_edge.origin()).or_default().push(i);
+            }
+        }
+
+        let mut visited = HashSet::<usize>::new();
+        let mut loops = vec![];
+
+        for start in 0..self.n_half_edges() {
+            if !self.half_edge(start).is_boundary() || visited.contains(&start) {
+                continue;
+            }
+
+            let mut vertices = vec![];
+            let mut curr = start;
+            let mut simple = true;
+
+            loop {
+                visited.insert(curr);
+                let half_edge = self.half_edge(curr);
+                vertices.push(half_edge.origin());
+
+                let next = self.half_edge(half_edge.next()).origin();
+
+                match outgoing.get(&next).map(Vec::as_slice) {
+                    Some([single]) => curr = *single,
+                    _ => {
+                        simple = false;
+                        break;
+                    }
+                }
+
+                if curr == start || visited.contains(&curr) {
+                    simple &= curr == start;
+                    break;
+                }
+            }
+
+            if simple {
+                loops.push(vertices);
+            }
+        }
+
+        loops
+    }
+}
+
+/// Get the sorted vertex indices identifying a face regardless of winding
+fn sorted(vertices: &[usize]) -> Vec<usize> {
+    let mut key = vertices.to_vec();
+    key.sort();
+    key
+}
+
+/// Check if a face has repeated vertices or zero area. A polygonal face is
+/// degenerate when each triangle of its fan is.
+fn is_degenerate(points: &[Vector3], vertices: &[usize]) -> bool {
+    let unique: HashSet<usize> = vertices.iter().copied().collect();
+
+    if unique.len() != vertices.len() || vertices.len() < 3 {
+        return true;
+    }
+
+    let p = points[vertices[0]];
+
+    vertices[1..].windows(2).all(|w| {
+        let triangle = Triangle::new(p, points[w[0]], points[w[1]]);
+        triangle.is_degenerate()
+    })
+}
+
+/// Build a half edge mesh from the faces indexing the source mesh vertices,
+/// dropping unreferenced vertices and carrying over the patches and scalar
+/// attributes
+fn build_mesh(source: &HeMesh, points: &[Vector3], faces: &Faces) -> Result<HeMesh, HeMeshError> {
+    let mut soup = PolygonSoupMesh::new();
+    let mut index = HashMap::<usize, usize>::new();
+    let mut vertex_sources = vec![];
+
+    for patch in source.patches().iter() {
+        soup.insert_patch(patch.name());
+    }
+
+    for (vertices, patch, _) in faces.iter() {
+        let vertices: Vec<usize> = vertices
+            .iter()
+            .map(|&v| {
+                *index.entry(v).or_insert_with(|| {
+                    soup.insert_vertex(points[v]);
+                    vertex_sources.push((v, v, 0.));
+                    soup.n_vertices() - 1
+                })
+            })
+            .collect();
+
+        soup.insert_face(&vertices, *patch);
+    }
+
+    let face_sources: Vec<Option<usize>> = faces.iter().map(|(_, _, f)| *f).collect();
+    let mut mesh = HeMesh::new(&soup)?;
+    mesh.carry_scalars(source, &vertex_sources, &face_sources);
+
+    Ok(mesh)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mesh::ObjReader;
+
+    #[test]
+    fn repair() {
+        let path = "tests/fixtures/box.inverted.obj";
+        let box_soup = ObjReader::new(path).read().unwrap();
+        let mut soup = PolygonSoupMesh::new();
+
+        for i in 0..box_soup.n_vertices() {
+            soup.insert_vertex(box_soup.vertex(i));
+        }
+
+        // Duplicate the first face on its own copies of the vertices
+        let (first, _) = box_soup.face(0);
+
+        for &v in first.iter() {
+            soup.insert_vertex(box_soup.vertex(v));
+        }
+
+        for i in 0..box_soup.n_faces() {
+            let (vertices, _) = box_soup.face(i);
+
+            match i {
+                // Leave a hole
+                3 => continue,
+                // Invert the winding relative to the other faces
+                5 => soup.insert_face(&[vertices[0], vertices[2], vertices[1]], None),
+                _ => soup.insert_face(vertices, None),
+            }
+        }
+
+        soup.insert_face(&[8, 9, 10], None);
+
+        let mut mesh = HeMesh::new(&soup).unwrap();
+        let report = mesh.repair(RepairOptions::default()).unwrap();
+
+        assert_eq!(report.n_welded_vertices(), 3);
+        assert_eq!(report.n_degenerate_faces(), 0);
+        assert_eq!(report.n_duplicate_faces(), 1);
+        assert_eq!(report.n_filled_holes(), 1);
+        assert_eq!(report.n_flipped_faces(), 10);
+
+        assert_eq!(mesh.n_vertices(), 8);
+        assert_eq!(mesh.n_faces(), 12);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - 1.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn fill_holes() {
+        let path = "tests/fixtures/box.open.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+
+        let n_faces = mesh.n_faces();
+        mesh.set_face_scalar("value", vec![1.; n_faces]).unwrap();
+
+        assert_eq!(mesh.fill_holes(Some(2)).unwrap(), 0);
+        assert_eq!(mesh.fill_holes(None).unwrap(), 1);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+
+        // The filling faces are appended without face scalars
+        let values = mesh.face_scalar("value").unwrap();
+
+        assert!(values[..n_faces].iter().all(|&v| v == 1.));
+        assert!(values[n_faces..].iter().all(|v| v.is_nan()));
+    }
+
+    #[test]
+    fn fill_small_holes() {
+        let path = "tests/fixtures/box.open.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+
+        assert_eq!(mesh.fill_small_holes(2).unwrap(), 0);
+        assert!(!mesh.is_closed());
+        assert_eq!(mesh.fill_small_holes(3).unwrap(), 1);
+        assert!(mesh.is_closed());
+    }
+
+    #[test]
+    fn remove_small_components() {
+        let path = "tests/fixtures/box.obj";
+        let box_soup = ObjReader::new(path).read().unwrap();
+        let mut soup = PolygonSoupMesh::new();
+
+        for i in 0..box_soup.n_vertices() {
+            soup.insert_vertex(box_soup.vertex(i));
+        }
+
+        for i in 0..box_soup.n_faces() {
+            soup.insert_face(box_soup.face(i).0, None);
+        }
+
+        // A floating two face shard away from the box
+        let n = soup.n_vertices();
+        soup.insert_vertex(Vector3::new(2., 0., 0.));
+        soup.insert_vertex(Vector3::new(3., 0., 0.));
+        soup.insert_vertex(Vector3::new(3., 1., 0.));
+        soup.insert_vertex(Vector3::new(2., 1., 0.));
+        soup.insert_face(&[n, n + 1, n + 2], None);
+        soup.insert_face(&[n, n + 2, n + 3], None);
+
+        let mut mesh = HeMesh::new(&soup).unwrap();
+
+        assert_eq!(mesh.remove_small_components(2).unwrap(), 0);
+        assert_eq!(mesh.remove_small_components(3).unwrap(), 1);
+        assert_eq!(mesh.n_faces(), 12);
+        assert_eq!(mesh.n_vertices(), 8);
+        assert!(mesh.is_closed());
+        assert!((mesh.volume() - 1.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn boundary_lines() {
+        let mesh = HeMesh::import_obj("tests/fixtures/box.open.obj").unwrap();
+        let lines = mesh.boundary_lines();
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].len(), 4);
+        assert_eq!(lines[0].first(), lines[0].last());
+
+        let closed = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+        assert!(closed.boundary_lines().is_empty());
+    }
+
+    #[test]
+    fn delete_and_fill() {
+        let mesh = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+        let points: Vec<Vector3> = mesh.vertices().iter().map(|v| v.origin()).collect();
+        let faces: Vec<Vec<usize>> = (0..mesh.n_faces()).map(|f| mesh.face_vertices(f)).collect();
+        let patches: Vec<usize> = (0..mesh.n_faces()).map(|f| f / 2).collect();
+
+        let mut mesh = HeMesh::from_arrays(&points, &faces, Some(&patches)).unwrap();
+        mesh.subdivide_midpoint(1).unwrap();
+        let volume = mesh.volume();
+
+        mesh.delete_and_fill("patch0").unwrap();
+
+        assert_eq!(mesh.n_patches(), 5);
+        assert!(mesh.is_closed());
+        assert!(mesh.is_consistent());
+        assert!((mesh.volume() - volume).abs() < 1e-12);
+    }
+
+    #[test]
+    fn delete_and_fill_existing_hole() {
+        let mesh = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+        let points: Vec<Vector3> = mesh.vertices().iter().map(|v| v.origin()).collect();
+        let faces: Vec<Vec<usize>> = (0..mesh.n_faces()).map(|f| mesh.face_vertices(f)).collect();
+        let patches: Vec<usize> = (0..mesh.n_faces()).map(|f| f / 2).collect();
+
+        let mut mesh = HeMesh::from_arrays(&points, &faces, Some(&patches)).unwrap();
+        mesh.subdivide_midpoint(1).unwrap();
+
+        // Open the side opposite to the deleted patch beforehand
+        let normal = |mesh: &HeMesh, patch: usize| {
+            let f = (0..mesh.n_faces())
+                .find(|&f| mesh.face(f).patch() == Some(patch))
+                .unwrap();
+            mesh.face_normal(f)
+        };
+
+        let opposite = (1..6)
+            .find(|&p| Vector3::dot(&normal(&mesh, 0), &normal(&mesh, p)) < -0.5)
+            .unwrap();
+        let name = format!("patch{}", opposite);
+        mesh.remove_patch_names(&[&name]).unwrap();
+        let n_faces = mesh.n_faces();
+
+        mesh.delete_and_fill("patch0").unwrap();
+
+        assert_eq!(mesh.n_patches(), 4);
+        assert_eq!(mesh.boundary_lines().len(), 1);
+        assert_eq!(mesh.boundary_lines()[0].len(), 9);
+        assert_eq!(mesh.n_faces(), n_faces - 2);
+        assert!(mesh.is_consistent());
+    }
+
+    #[test]
+    fn repair_disabled() {
+        let path = "tests/fixtures/box.inverted.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        let options = RepairOptions {
+            weld_tolerance: None,
+            remove_degenerate_faces: false,
+            remove_duplicate_faces: false,
+            max_hole_edges: None,
+            orient_outward: false,
+        };
+
+        let report = mesh.repair(options).unwrap();
+
+        assert_eq!(report.n_flipped_faces(), 0);
+        assert!((mesh.volume() + 1.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn degenerate() {
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1., 0., 0.));
+        soup.insert_vertex(Vector3::new(0., 1., 0.));
+        soup.insert_vertex(Vector3::new(2., 0., 0.));
+        soup.insert_face(&[0, 1, 2], None);
+        soup.insert_face(&[0, 3, 1], None);
+
+        let mut mesh = HeMesh::new(&soup).unwrap();
+        let report = mesh.repair(RepairOptions::default()).unwrap();
+
+        assert_eq!(report.n_degenerate_faces(), 1);
+        assert_eq!(mesh.n_faces(), 1);
+        assert_eq!(mesh.n_vertices(), 3);
+    }
+
+    #[test]
+    fn degenerate_scale() {
+        // A small well-shaped face is kept and a large sliver is removed
+        let mut soup = PolygonSoupMesh::new();
+        soup.insert_vertex(Vector3::new(0., 0., 0.));
+        soup.insert_vertex(Vector3::new(1e-9, 0., 0.));
+        soup.insert_vertex(Vector3::new(0., 1e-9, 0.));
+        soup.insert_vertex(Vector3::new(10., 0., 0.));
+        soup.insert_vertex(Vector3::new(20., 1e-8, 0.));
+        soup.insert_vertex(Vector3::new(30., 0., 0.));
+        soup.insert_face(&[0, 1, 2], None);
+        soup.insert_face(&[3, 5, 4], None);
+
+        let mut mesh = HeMesh::new(&soup).unwrap();
+        let options = RepairOptions {
+            weld_tolerance: None,
+            ..RepairOptions::default()
+        };
+        let report = mesh.repair(options).unwrap();
+
+        assert_eq!(report.n_degenerate_faces(), 1);
+        assert_eq!(mesh.n_faces(), 1);
+        assert!((mesh.face_areas()[0] - 5e-19).abs() < 1e-30);
+    }
+}
@@ -2,18 +2,47 @@ use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs::File;
 use std::io::prelude::*;
+use std::io::{BufReader, BufWriter};
 use std::path::Path;
 
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
+use rayon::prelude::*;
 
 use crate::geometry::Vector3;
-use crate::mesh::PolygonSoupMesh;
+use crate::mesh::half_edge::triangulate_face;
+use crate::mesh::{HeMesh, PolygonSoupMesh};
+
+// Leading bytes of a GZIP stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+// Minimum number of lines parsed per chunk when reading in parallel
+const MIN_CHUNK_LINES: usize = 4096;
+
+/// Axis pointing up in the coordinate frame of a file
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum UpAxis {
+    #[default]
+    Y,
+    Z,
+}
 
 #[derive(Debug, Clone)]
 pub struct ObjReader {
     path: String,
+    up_axis: UpAxis,
+    scale: f64,
+}
+
+impl Default for ObjReader {
+    fn default() -> ObjReader {
+        ObjReader {
+            path: String::new(),
+            up_axis: UpAxis::Y,
+            scale: 1.,
+        }
+    }
 }
 
 impl ObjReader {
@@ -21,54 +50,222 @@ impl ObjReader {
     pub fn new(path: &str) -> ObjReader {
         ObjReader {
             path: path.to_string(),
+            ..ObjReader::default()
         }
     }
 
+    /// Set the up axis of the file. The vertices of a Z-up file are rotated
+    /// into the Y-up frame as they are read.
+    pub fn with_up_axis(mut self, up_axis: UpAxis) -> ObjReader {
+        self.up_axis = up_axis;
+        self
+    }
+
+    /// Set the uniform scale applied to the vertices as they are read (e.g.
+    /// 0.001 to convert millimeters to meters)
+    pub fn with_scale(mut self, scale: f64) -> ObjReader {
+        self.scale = scale;
+        self
+    }
+
     /// Read the file into a PolygonSoup mesh
     pub fn read(&self) -> std::io::Result<PolygonSoupMesh> {
-        let mut file = File::open(&self.path)?;
+        let data = self.read_data()?;
+        self.parse(&data)
+    }
+
+    /// Read an (optionally compressed) stream into a PolygonSoup mesh
+    pub fn from_reader<R: Read>(reader: R) -> std::io::Result<PolygonSoupMesh> {
+        let data = read_to_string(reader)?;
+        ObjReader::default().parse(&data)
+    }
+
+    /// Parse the contents into a PolygonSoup mesh
+    fn parse(&self, data: &str) -> std::io::Result<PolygonSoupMesh> {
         let mut mesh = PolygonSoupMesh::new();
-        let mut data = String::new();
 
-        if is_gzip(&self.path) {
-            let mut file = GzDecoder::new(file);
-            file.read_to_string(&mut data)?;
-        } else {
-            file.read_to_string(&mut data)?;
+        for line in data.lines() {
+            self.parse_line(&mut mesh, line)?;
         }
 
+        Ok(mesh)
+    }
+
+    /// Read the file into a PolygonSoup mesh by parsing chunks of lines in
+    /// parallel. The result is identical to `read`.
+    pub fn read_parallel(&self) -> std::io::Result<PolygonSoupMesh> {
+        let data = self.read_data()?;
+        let lines = data.lines().collect::<Vec<&str>>();
+        let chunk_size = lines.len().div_ceil(rayon::current_num_threads());
+        self.parse_chunks(&lines, chunk_size.max(MIN_CHUNK_LINES))
+    }
+
+    /// Read the file into a PolygonSoup mesh, skipping any invalid vertices
+    /// or faces. Faces referencing a skipped vertex are skipped as well, so
+    /// the remaining faces keep their vertices. The messages for the skipped
+    /// lines are returned alongside the mesh.
+    pub fn read_lenient(&self) -> std::io::Result<(PolygonSoupMesh, Vec<String>)> {
+        let data = self.read_data()?;
+        let mut mesh = PolygonSoupMesh::new();
+        let mut warnings = vec![];
+
+        // Map each vertex record in the file to its index in the mesh, or
+        // None if it was skipped
+        let mut index = vec![];
+
         for line in data.lines() {
-            let line = line.trim();
-            let args = line.splitn(2, char::is_whitespace).collect::<Vec<&str>>();
+            let n_vertices = mesh.n_vertices();
+            let result = self.parse_line(&mut mesh, line);
+
+            if line.split_whitespace().next() == Some("v") {
+                index.push(result.is_ok().then_some(n_vertices));
+            }
+
+            if let Err(err) = result {
+                warnings.push(err.to_string());
+            }
+        }
+
+        // Faces may only be validated against the vertex records once the
+        // whole file has been read.
+        let mut valid = PolygonSoupMesh::new();
 
-            match args.first() {
-                Some(&"v") => self.parse_vertex(&mut mesh, &args[1]),
-                Some(&"f") => self.parse_face(&mut mesh, &args[1]),
-                Some(&"g") => self.parse_group(&mut mesh, &args[1]),
-                _ => Ok(()),
-            }?;
+        for i in 0..mesh.n_vertices() {
+            valid.insert_vertex(mesh.vertex(i));
+
+            if let Some(color) = mesh.color(i) {
+                valid.insert_color(color);
+            }
+        }
+
+        for i in 0..mesh.n_patches() {
+            valid.insert_patch(mesh.patch(i));
+        }
+
+        for i in 0..mesh.n_faces() {
+            let (vertices, patch) = mesh.face(i);
+            let mapped: Option<Vec<usize>> = vertices
+                .iter()
+                .map(|&v| index.get(v).copied().flatten())
+                .collect();
+
+            if let Some(mapped) = mapped {
+                valid.insert_face(&mapped, patch);
+            } else {
+                let data = vertices
+                    .iter()
+                    .map(|v| (v + 1).to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+
+                warnings.push(ParseObjError::InvalidFace(data).to_string());
+            }
+        }
+
+        Ok((valid, warnings))
+    }
+
+    /// Read the (optionally compressed) file contents
+    fn read_data(&self) -> std::io::Result<String> {
+        let file = File::open(&self.path)?;
+        read_to_string(file)
+    }
+
+    /// Parse chunks of lines in parallel and merge them in order. Faces in a
+    /// chunk that precede its first group belong to the last group of the
+    /// preceding chunks.
+    fn parse_chunks(&self, lines: &[&str], chunk_size: usize) -> std::io::Result<PolygonSoupMesh> {
+        let chunks = lines
+            .par_chunks(chunk_size.max(1))
+            .map(|chunk| {
+                let mut mesh = PolygonSoupMesh::new();
+
+                for line in chunk {
+                    self.parse_line(&mut mesh, line)?;
+                }
+
+                Ok(mesh)
+            })
+            .collect::<std::io::Result<Vec<PolygonSoupMesh>>>()?;
+
+        let mut mesh = PolygonSoupMesh::new();
+
+        for chunk in chunks.iter() {
+            let n_patches = mesh.n_patches();
+
+            for i in 0..chunk.n_vertices() {
+                mesh.insert_vertex(chunk.vertex(i));
+
+                if let Some(color) = chunk.color(i) {
+                    mesh.insert_color(color);
+                }
+            }
+
+            for i in 0..chunk.n_faces() {
+                let (vertices, patch) = chunk.face(i);
+
+                let patch = match patch {
+                    Some(patch) => Some(patch + n_patches),
+                    None => n_patches.checked_sub(1),
+                };
+
+                mesh.insert_face(vertices, patch);
+            }
+
+            for i in 0..chunk.n_patches() {
+                mesh.insert_patch(chunk.patch(i));
+            }
         }
 
         Ok(mesh)
     }
 
-    /// Parse a vertex
+    /// Parse a line
+    fn parse_line(&self, mesh: &mut PolygonSoupMesh, line: &str) -> std::io::Result<()> {
+        let line = line.trim();
+        let (keyword, data) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+        match keyword {
+            "v" => self.parse_vertex(mesh, data),
+            "f" => self.parse_face(mesh, data),
+            "g" => self.parse_group(mesh, data),
+            _ => Ok(()),
+        }
+    }
+
+    /// Parse a vertex with an optional trailing RGB color
     fn parse_vertex(&self, mesh: &mut PolygonSoupMesh, data: &str) -> std::io::Result<()> {
-        let mut vertex = Vector3::zeros();
+        let mut values = [0.; 6];
+        let mut count = 0;
 
-        for (i, text) in data.split_whitespace().enumerate() {
-            if i >= 3 {
+        for text in data.split_whitespace() {
+            if count >= 6 {
                 return Err(ParseObjError::InvalidVertex(data.to_string()).into());
             }
 
             if let Ok(value) = text.parse::<f64>() {
-                vertex[i] = value;
+                values[count] = value;
+                count += 1;
             } else {
                 return Err(ParseObjError::InvalidVertex(data.to_string()).into());
             }
         }
 
-        mesh.insert_vertex(vertex);
+        if count != 3 && count != 6 {
+            return Err(ParseObjError::InvalidVertex(data.to_string()).into());
+        }
+
+        let mut vertex = Vector3::new(values[0], values[1], values[2]);
+
+        if self.up_axis == UpAxis::Z {
+            vertex = Vector3::new(vertex[0], vertex[2], -vertex[1]);
+        }
+
+        mesh.insert_vertex(vertex * self.scale);
+
+        if count == 6 {
+            mesh.insert_color(Vector3::new(values[3], values[4], values[5]));
+        }
 
         Ok(())
     }
@@ -79,9 +276,9 @@ impl ObjReader {
         let patch = mesh.n_patches();
 
         for text in data.split_whitespace() {
-            if let Some(text) = text.splitn(2, "/").next() {
+            if let Some(text) = text.split("/").next() {
                 if let Ok(value) = text.parse::<usize>() {
-                    if value <= 0 {
+                    if value == 0 {
                         return Err(ParseObjError::InvalidFace(data.to_string()).into());
                     }
 
@@ -111,25 +308,55 @@ impl ObjReader {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ObjWriter {
     vertices: Vec<Vector3>,
+    colors: Vec<Vector3>,
     faces: Vec<Vec<usize>>,
     face_groups: Vec<Option<usize>>,
     lines: Vec<Vec<usize>>,
     groups: Vec<String>,
+    texcoords: Vec<(f64, f64)>,
+    triangulate: bool,
 }
 
 impl ObjWriter {
     /// Construct a default ObjWriter
     pub fn new() -> ObjWriter {
-        ObjWriter {
-            vertices: vec![],
-            faces: vec![],
-            face_groups: vec![],
-            lines: vec![],
-            groups: vec![],
-        }
+        ObjWriter::default()
+    }
+
+    /// Construct an ObjWriter populated from a half edge mesh. Each patch is
+    /// written as a group.
+    pub fn from_mesh(mesh: &HeMesh) -> ObjWriter {
+        let vertices: Vec<Vector3> = mesh.vertices().iter().map(|v| v.origin()).collect();
+
+        let faces: Vec<Vec<usize>> = (0..mesh.n_faces()).map(|f| mesh.face_vertices(f)).collect();
+
+        let face_groups: Vec<Option<usize>> = mesh.faces().iter().map(|f| f.patch()).collect();
+
+        let groups: Vec<String> = mesh
+            .patches()
+            .iter()
+            .map(|p| p.name().to_string())
+            .collect();
+
+        let mut writer = ObjWriter::new();
+        writer.set_colors(mesh.vertex_colors().unwrap_or_default());
+        writer.set_vertices(vertices);
+        writer.set_faces(faces);
+        writer.set_face_groups(face_groups);
+        writer.set_groups(groups);
+        writer
+    }
+
+    /// Construct an ObjWriter with the vertices of a half edge mesh and
+    /// polylines indexing them, written as line records without the faces
+    pub fn from_mesh_lines(mesh: &HeMesh, lines: Vec<Vec<usize>>) -> ObjWriter {
+        let mut writer = ObjWriter::new();
+        writer.set_vertices(mesh.vertices().iter().map(|v| v.origin()).collect());
+        writer.set_lines(lines);
+        writer
     }
 
     /// Set the vertices
@@ -137,6 +364,12 @@ impl ObjWriter {
         self.vertices = vertices;
     }
 
+    /// Set the RGB color of each vertex. The colors are written on the
+    /// vertex records when there is one per vertex.
+    pub fn set_colors(&mut self, colors: Vec<Vector3>) {
+        self.colors = colors;
+    }
+
     /// Set the faces
     pub fn set_faces(&mut self, faces: Vec<Vec<usize>>) {
         self.faces = faces;
@@ -157,56 +390,75 @@ impl ObjWriter {
         self.groups = groups;
     }
 
-    /// Write the data to file
-    pub fn write(&self, path: &str) -> std::io::Result<()> {
-        let mut content = String::new();
-        content.push_str(&self.format_vertices());
-        content.push_str(&self.format_lines());
-        content.push_str(&self.format_faces());
+    /// Set the texture coordinates, one for each vertex. Faces reference the
+    /// texture coordinate of each vertex when set.
+    pub fn set_texcoords(&mut self, texcoords: Vec<(f64, f64)>) {
+        self.texcoords = texcoords;
+    }
+
+    /// Enable or disable triangulating polygonal faces on write. Each face
+    /// with more than three vertices is written as triangles in the same
+    /// group: quads as a fan about their first vertex and larger polygons by
+    /// ear clipping.
+    pub fn triangulate(&mut self, enabled: bool) {
+        self.triangulate = enabled;
+    }
 
-        let mut file = File::create(path)?;
-        let data = content.as_bytes();
+    /// Write the data to file. The output is compressed if the path has a
+    /// GZIP extension.
+    pub fn write(&self, path: &str) -> std::io::Result<()> {
+        let file = File::create(path)?;
 
         if is_gzip(path) {
-            let mut encoder = GzEncoder::new(&mut file, Compression::default());
-            encoder.write_all(&data)?;
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            self.write_to(&mut encoder)?;
+            encoder.finish()?;
+            Ok(())
         } else {
-            file.write_all(&data)?;
+            self.write_to(file)
         }
-
-        Ok(())
     }
 
-    /// Format the vertices into a string
-    fn format_vertices(&self) -> String {
-        self.vertices
-            .iter()
-            .map(|v| format!("v {} {} {}\n", v[0], v[1], v[2]))
-            .collect::<Vec<String>>()
-            .join("")
+    /// Write the (uncompressed) data to a stream. The data is written
+    /// incrementally through a buffer rather than formatted up front.
+    pub fn write_to<W: Write>(&self, w: W) -> std::io::Result<()> {
+        let mut w = BufWriter::new(w);
+        self.write_vertices(&mut w)?;
+        self.write_lines(&mut w)?;
+        self.write_faces(&mut w)?;
+        w.flush()
     }
 
-    /// Format the lines into a string
-    fn format_lines(&self) -> String {
-        let mut content = String::new();
+    /// Write the vertices (and texture coordinates) to a stream
+    fn write_vertices<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        if self.colors.len() == self.vertices.len() {
+            for (v, c) in self.vertices.iter().zip(self.colors.iter()) {
+                writeln!(w, "v {} {} {} {} {} {}", v[0], v[1], v[2], c[0], c[1], c[2])?;
+            }
+        } else {
+            for v in self.vertices.iter() {
+                writeln!(w, "v {} {} {}", v[0], v[1], v[2])?;
+            }
+        }
 
-        for l in self.lines.iter() {
-            let vertices = l
-                .iter()
-                .map(|v| (v + 1).to_string())
-                .collect::<Vec<String>>()
-                .join(" ");
+        for (u, v) in self.texcoords.iter() {
+            writeln!(w, "vt {} {}", u, v)?;
+        }
 
-            let entry = format!("l {}\n", vertices);
-            content.push_str(&entry);
+        Ok(())
+    }
+
+    /// Write the lines to a stream
+    fn write_lines<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        for l in self.lines.iter() {
+            write_element(w, "l", l)?;
         }
 
-        content
+        Ok(())
     }
 
-    /// Format the faces into a string
-    fn format_faces(&self) -> String {
-        let mut content = String::new();
+    /// Write the faces to a stream
+    fn write_faces<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         let mut index = HashMap::<Option<usize>, Vec<usize>>::new();
         let n = self.faces.len();
 
@@ -233,27 +485,55 @@ impl ObjWriter {
             let mut group_id = None;
 
             if let Some(name) = group {
-                let entry = format!("g {}\n", name);
-                content.push_str(&entry);
+                writeln!(w, "g {}", name)?;
                 group_id = Some(i - 1);
             }
 
             if let Some(face_ids) = index.remove(&group_id) {
                 for face_id in face_ids.iter() {
-                    let vertices = self.faces[*face_id]
-                        .iter()
-                        .map(|v| (v + 1).to_string())
-                        .collect::<Vec<String>>()
-                        .join(" ");
-
-                    let entry = format!("f {}\n", vertices);
-                    content.push_str(&entry);
+                    let face = &self.faces[*face_id];
+
+                    if self.triangulate && face.len() > 3 {
+                        let points: Vec<Vector3> = face.iter().map(|&v| self.vertices[v]).collect();
+
+                        for [a, b, c] in triangulate_face(&points) {
+                            self.write_face(w, &[face[a], face[b], face[c]])?;
+                        }
+                    } else {
+                        self.write_face(w, face)?;
+                    }
                 }
             }
         }
 
-        content
+        Ok(())
     }
+
+    /// Write a face, referencing the texture coordinates if set
+    fn write_face<W: Write>(&self, w: &mut W, vertices: &[usize]) -> std::io::Result<()> {
+        if self.texcoords.is_empty() {
+            return write_element(w, "f", vertices);
+        }
+
+        w.write_all(b"f")?;
+
+        for v in vertices.iter() {
+            write!(w, " {}/{}", v + 1, v + 1)?;
+        }
+
+        writeln!(w)
+    }
+}
+
+/// Write an element as its keyword followed by its one-based vertex indices
+fn write_element<W: Write>(w: &mut W, keyword: &str, vertices: &[usize]) -> std::io::Result<()> {
+    w.write_all(keyword.as_bytes())?;
+
+    for v in vertices.iter() {
+        write!(w, " {}", v + 1)?;
+    }
+
+    writeln!(w)
 }
 
 #[derive(Debug, Clone)]
@@ -275,7 +555,7 @@ impl std::error::Error for ParseObjError {}
 
 impl From<ParseObjError> for std::io::Error {
     fn from(err: ParseObjError) -> Self {
-        std::io::Error::new(std::io::ErrorKind::Other, err.to_string())
+        std::io::Error::other(err.to_string())
     }
 }
 
@@ -291,6 +571,21 @@ fn is_gzip(path: &str) -> bool {
     false
 }
 
+/// Read the contents of a stream to a string, decompressing it if it starts
+/// with the GZIP magic bytes
+fn read_to_string<R: Read>(reader: R) -> std::io::Result<String> {
+    let mut reader = BufReader::new(reader);
+    let mut data = String::new();
+
+    if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        GzDecoder::new(reader).read_to_string(&mut data)?;
+    } else {
+        reader.read_to_string(&mut data)?;
+    }
+
+    Ok(data)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -298,7 +593,7 @@ mod test {
     #[test]
     fn read() {
         let path = "tests/fixtures/box.obj";
-        let mesh = ObjReader::new(&path).read().unwrap();
+        let mesh = ObjReader::new(path).read().unwrap();
 
         assert_eq!(8, mesh.n_vertices());
         assert_eq!(12, mesh.n_faces());
@@ -308,20 +603,384 @@ mod test {
     #[test]
     fn read_gzip() {
         let path = "tests/fixtures/box.obj.gz";
-        let mesh = ObjReader::new(&path).read().unwrap();
+        let mesh = ObjReader::new(path).read().unwrap();
+
+        assert_eq!(8, mesh.n_vertices());
+        assert_eq!(12, mesh.n_faces());
+        assert_eq!(0, mesh.n_patches());
+    }
+
+    #[test]
+    fn read_gzip_mislabeled() {
+        let path = std::env::temp_dir().join("meshr.read_gzip_mislabeled.obj");
+        let path = path.to_str().unwrap();
+        std::fs::copy("tests/fixtures/box.obj.gz", path).unwrap();
+
+        let mesh = ObjReader::new(path).read();
+        std::fs::remove_file(path).unwrap();
+        let mesh = mesh.unwrap();
 
         assert_eq!(8, mesh.n_vertices());
         assert_eq!(12, mesh.n_faces());
         assert_eq!(0, mesh.n_patches());
     }
 
+    #[test]
+    fn from_reader() {
+        let data = std::fs::read("tests/fixtures/box.obj").unwrap();
+        let mesh = ObjReader::from_reader(std::io::Cursor::new(&data[..])).unwrap();
+
+        assert_eq!(8, mesh.n_vertices());
+        assert_eq!(12, mesh.n_faces());
+        assert_eq!(0, mesh.n_patches());
+    }
+
+    #[test]
+    fn from_reader_gzip() {
+        let data = std::fs::read("tests/fixtures/box.obj.gz").unwrap();
+        let mesh = ObjReader::from_reader(&data[..]).unwrap();
+
+        assert_eq!(8, mesh.n_vertices());
+        assert_eq!(12, mesh.n_faces());
+    }
+
+    #[test]
+    fn read_up_axis_scale() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = ObjReader::new(path)
+            .with_up_axis(UpAxis::Z)
+            .with_scale(0.001)
+            .read()
+            .unwrap();
+
+        // The vertex (0, 1, 1) in the Z-up frame is (0, 1, -1) in Y-up
+        assert_eq!(mesh.vertex(3), Vector3::new(0., 0.001, -0.001));
+        assert_eq!(mesh.n_vertices(), 8);
+
+        let mesh = ObjReader::new(path).read_parallel().unwrap();
+        assert_eq!(mesh.vertex(3), Vector3::new(0., 1., 1.));
+    }
+
     #[test]
     fn read_groups() {
         let path = "tests/fixtures/box.groups.obj";
-        let mesh = ObjReader::new(&path).read().unwrap();
+        let mesh = ObjReader::new(path).read().unwrap();
 
         assert_eq!(8, mesh.n_vertices());
         assert_eq!(7, mesh.n_faces());
         assert_eq!(6, mesh.n_patches());
     }
+
+    #[test]
+    fn read_invalid() {
+        let path = "tests/fixtures/box.invalid.obj";
+        let result = ObjReader::new(path).read();
+
+        assert!(result.is_err_and(|e| e.to_string() == "invalid vertex: 1.0 nan? 2.0"));
+    }
+
+    #[test]
+    fn read_lenient() {
+        let path = "tests/fixtures/box.invalid.obj";
+        let (mesh, warnings) = ObjReader::new(path).read_lenient().unwrap();
+
+        assert_eq!(8, mesh.n_vertices());
+        assert_eq!(12, mesh.n_faces());
+        assert_eq!(
+            warnings,
+            vec![
+                "invalid vertex: 1.0 nan? 2.0",
+                "invalid face: 1 2",
+                "invalid face: 1 2 42",
+            ]
+        );
+    }
+
+    #[test]
+    fn read_lenient_skipped_vertex() {
+        let path = "tests/fixtures/box.invalid-middle.obj";
+        let (mesh, warnings) = ObjReader::new(path).read_lenient().unwrap();
+
+        assert_eq!(8, mesh.n_vertices());
+        assert_eq!(12, mesh.n_faces());
+        assert_eq!(
+            warnings,
+            vec![
+                "invalid vertex: 1.0 nan? 2.0",
+                "invalid vertex: ",
+                "invalid face: ",
+                "invalid face: 1 5 2",
+            ]
+        );
+
+        let mesh = HeMesh::new(&mesh).unwrap();
+
+        assert!(mesh.is_closed());
+        assert!((mesh.volume() - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn read_bare_records() {
+        let mut mesh = PolygonSoupMesh::new();
+        let reader = ObjReader::default();
+
+        assert!(reader.parse_line(&mut mesh, "v").is_err());
+        assert!(reader.parse_line(&mut mesh, "f").is_err());
+        assert!(reader.parse_line(&mut mesh, "").is_ok());
+    }
+
+    #[test]
+    fn read_parallel() {
+        let n = 100;
+        let mut content = String::new();
+
+        for j in 0..n {
+            for i in 0..n {
+                content.push_str(&format!("v {} {} 0\n", i, j));
+            }
+        }
+
+        for j in 0..n - 1 {
+            if j % 7 == 3 {
+                content.push_str(&format!("g row{}\n", j));
+            }
+
+            for i in 0..n - 1 {
+                let a = j * n + i + 1;
+                content.push_str(&format!("f {} {} {}\n", a, a + 1, a + n + 1));
+                content.push_str(&format!("f {} {} {}\n", a, a + n + 1, a + n));
+            }
+        }
+
+        let path = std::env::temp_dir().join("meshr.read_parallel.obj");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, content).unwrap();
+
+        let reader = ObjReader::new(path);
+        let expected = reader.read().unwrap();
+        let parallel = reader.read_parallel().unwrap();
+        let data = reader.read_data().unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(parallel.n_faces(), expected.n_faces());
+        assert_eq!(parallel.n_patches(), expected.n_patches());
+
+        let lines = data.lines().collect::<Vec<&str>>();
+
+        for chunk_size in [1, 97, 1000, lines.len()] {
+            let mesh = reader.parse_chunks(&lines, chunk_size).unwrap();
+
+            assert_eq!(mesh.n_vertices(), expected.n_vertices());
+            assert_eq!(mesh.n_faces(), expected.n_faces());
+            assert_eq!(mesh.n_patches(), expected.n_patches());
+
+            for i in 0..mesh.n_vertices() {
+                assert_eq!(mesh.vertex(i), expected.vertex(i));
+            }
+
+            for i in 0..mesh.n_faces() {
+                assert_eq!(mesh.face(i), expected.face(i));
+            }
+
+            for i in 0..mesh.n_patches() {
+                assert_eq!(mesh.patch(i), expected.patch(i));
+            }
+        }
+    }
+
+    #[test]
+    fn write_from_mesh() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let writer = ObjWriter::from_mesh(&mesh);
+
+        let path = std::env::temp_dir().join("meshr.write_from_mesh.obj");
+        let path = path.to_str().unwrap();
+        writer.write(path).unwrap();
+
+        let soup = ObjReader::new(path).read().unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(mesh.n_vertices(), soup.n_vertices());
+        assert_eq!(mesh.n_faces(), soup.n_faces());
+        assert_eq!(mesh.n_patches(), soup.n_patches());
+    }
+
+    #[test]
+    fn write_to() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let writer = ObjWriter::from_mesh(&mesh);
+
+        let mut buffer = Vec::<u8>::new();
+        writer.write_to(&mut buffer).unwrap();
+        let soup = ObjReader::from_reader(&buffer[..]).unwrap();
+
+        assert_eq!(mesh.n_vertices(), soup.n_vertices());
+        assert_eq!(mesh.n_faces(), soup.n_faces());
+
+        for i in 0..mesh.n_faces() {
+            assert_eq!(mesh.face_vertices(i), soup.face(i).0.to_vec());
+        }
+    }
+
+    #[test]
+    fn read_colors() {
+        let path = "tests/fixtures/box.colors.obj";
+        let soup = ObjReader::new(path).read().unwrap();
+
+        assert!(soup.has_colors());
+        assert_eq!(soup.n_vertices(), 8);
+        assert_eq!(soup.vertex(1), Vector3::new(-0.5, -0.5, 0.5));
+        assert_eq!(soup.color(1), Some(Vector3::new(0., 0., 1.)));
+
+        let soup = ObjReader::new("tests/fixtures/box.obj").read().unwrap();
+
+        assert!(!soup.has_colors());
+        assert_eq!(soup.color(0), None);
+    }
+
+    #[test]
+    fn write_colors() {
+        let path = "tests/fixtures/box.colors.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let colors = mesh.vertex_colors().unwrap();
+
+        let mut buffer = Vec::<u8>::new();
+        ObjWriter::from_mesh(&mesh).write_to(&mut buffer).unwrap();
+        let soup = ObjReader::from_reader(&buffer[..]).unwrap();
+        let other = HeMesh::new(&soup).unwrap();
+
+        assert_eq!(colors.len(), 8);
+        assert_eq!(other.vertex_colors(), Some(colors));
+
+        let mesh = HeMesh::import_obj("tests/fixtures/box.obj").unwrap();
+        let mut buffer = Vec::<u8>::new();
+        ObjWriter::from_mesh(&mesh).write_to(&mut buffer).unwrap();
+
+        assert!(String::from_utf8(buffer)
+            .unwrap()
+            .starts_with("v -0.5 -0.5 -0.5\n"));
+    }
+
+    #[test]
+    fn write_triangulate() {
+        let path = "tests/fixtures/box.groups.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut writer = ObjWriter::from_mesh(&mesh);
+        writer.triangulate(true);
+
+        let mut buffer = Vec::<u8>::new();
+        writer.write_to(&mut buffer).unwrap();
+        let soup = ObjReader::from_reader(&buffer[..]).unwrap();
+        let mesh = HeMesh::new(&soup).unwrap();
+
+        assert_eq!(mesh.n_faces(), 12);
+        assert_eq!(mesh.n_patches(), 6);
+        assert!(mesh.is_triangles());
+        assert!(mesh.is_closed());
+
+        for (name, n_faces, _) in mesh.patch_summary() {
+            assert_eq!(n_faces, 2, "{}", name);
+        }
+    }
+
+    #[test]
+    fn write_texcoords() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut writer = ObjWriter::from_mesh(&mesh);
+        writer.set_texcoords(mesh.planar_uv());
+
+        let mut buffer = Vec::<u8>::new();
+        writer.write_to(&mut buffer).unwrap();
+        let data = String::from_utf8(buffer.clone()).unwrap();
+        let soup = ObjReader::from_reader(&buffer[..]).unwrap();
+
+        assert_eq!(data.lines().filter(|l| l.starts_with("vt ")).count(), 8);
+        assert!(data.contains("f 1/1 2/2 3/3"));
+        assert_eq!(mesh.n_faces(), soup.n_faces());
+
+        for i in 0..mesh.n_faces() {
+            assert_eq!(mesh.face_vertices(i), soup.face(i).0.to_vec());
+        }
+    }
+
+    #[test]
+    fn write_to_large() {
+        let n = 200;
+        let mut writer = ObjWriter::new();
+        let mut expected = String::new();
+
+        let vertices: Vec<Vector3> = (0..n * n)
+            .map(|i| Vector3::new((i % n) as f64 * 0.1, (i / n) as f64 / 3., 0.))
+            .collect();
+
+        let faces: Vec<Vec<usize>> = (0..(n - 1) * (n - 1))
+            .map(|i| {
+                let a = (i / (n - 1)) * n + i % (n - 1);
+                vec![a, a + 1, a + n + 1, a + n]
+            })
+            .collect();
+
+        let face_groups: Vec<Option<usize>> = (0..faces.len())
+            .map(|i| [None, Some(0), Some(1)][i % 3])
+            .collect();
+
+        let lines = vec![vec![0, 1, 2], vec![n, n + 1]];
+
+        // The output of the former string-based implementation
+        for v in vertices.iter() {
+            expected.push_str(&format!("v {} {} {}\n", v[0], v[1], v[2]));
+        }
+
+        for l in lines.iter() {
+            let indices: Vec<String> = l.iter().map(|v| (v + 1).to_string()).collect();
+            expected.push_str(&format!("l {}\n", indices.join(" ")));
+        }
+
+        for (group, name) in [(None, None), (Some(0), Some("a")), (Some(1), Some("b"))] {
+            if let Some(name) = name {
+                expected.push_str(&format!("g {}\n", name));
+            }
+
+            for (face, _) in faces
+                .iter()
+                .zip(face_groups.iter())
+                .filter(|f| *f.1 == group)
+            {
+                let indices: Vec<String> = face.iter().map(|v| (v + 1).to_string()).collect();
+                expected.push_str(&format!("f {}\n", indices.join(" ")));
+            }
+        }
+
+        writer.set_vertices(vertices);
+        writer.set_faces(faces);
+        writer.set_face_groups(face_groups);
+        writer.set_lines(lines);
+        writer.set_groups(vec!["a".to_string(), "b".to_string()]);
+
+        let mut buffer = Vec::<u8>::new();
+        writer.write_to(&mut buffer).unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+    }
+
+    #[test]
+    fn write_gzip() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let writer = ObjWriter::from_mesh(&mesh);
+
+        let path = std::env::temp_dir().join("meshr.write_gzip.obj.gz");
+        let path = path.to_str().unwrap();
+        writer.write(path).unwrap();
+
+        let soup = ObjReader::new(path).read();
+        std::fs::remove_file(path).unwrap();
+        let soup = soup.unwrap();
+
+        assert_eq!(mesh.n_vertices(), soup.n_vertices());
+        assert_eq!(mesh.n_faces(), soup.n_faces());
+    }
 }
@@ -1,7 +1,9 @@
+pub mod grid;
 pub mod octree;
 
 // Re-exports
-pub use octree::Octree;
+pub use grid::UniformGrid;
+pub use octree::{Octree, OctreeConfig, OctreeStats};
 
 /// Find items spatial intersecting the query
 pub trait Query<Q> {
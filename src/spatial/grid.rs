@@ -0,0 +1,268 @@
+use rayon::prelude::*;
+use std::collections::HashSet;
+
+use crate::geometry::{Aabb, Intersects, Vector3};
+use crate::spatial::{Query, QueryMany};
+
+#[derive(Debug, Clone)]
+pub struct UniformGrid<T>
+where
+    T: Intersects<Aabb>,
+{
+    bounds: Aabb,
+    cell_size: f64,
+    shape: [usize; 3],
+    cells: Vec<Vec<usize>>,
+    items: Vec<T>,
+}
+
+impl<T> UniformGrid<T>
+where
+    T: Intersects<Aabb>,
+{
+    /// Construct a UniformGrid from its bounds and the edge length of its
+    /// cubic cells. The cells may extend beyond the max bound.
+    pub fn new(bounds: Aabb, cell_size: f64) -> UniformGrid<T> {
+        if cell_size <= 0. {
+            panic!("cell size must be positive");
+        }
+
+        let size = bounds.max() - bounds.min();
+        let shape = [0, 1, 2].map(|i| ((size[i] / cell_size).ceil() as usize).max(1));
+
+        UniformGrid {
+            bounds,
+            cell_size,
+            shape,
+            cells: vec![vec![]; shape[0] * shape[1] * shape[2]],
+            items: vec![],
+        }
+    }
+
+    /// Get the number of cells along each axis
+    pub fn shape(&self) -> [usize; 3] {
+        self.shape
+    }
+
+    /// Get the indexed items of a cell by its (i, j, k) position
+    pub fn cell(&self, i: usize, j: usize, k: usize) -> &[usize] {
+        &self.cells[self.cell_index([i, j, k])]
+    }
+
+    /// Get a slice of the items
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Insert an item which may be indexed on one or more cells
+    /// but must overlap with the UniformGrid bounds.
+    pub fn insert(&mut self, item: T) -> usize {
+        let index = self.items.len();
+        let cells = self.find_cells(|aabb| item.intersects(aabb));
+
+        if cells.is_empty() {
+            panic!("item not inserted");
+        }
+
+        for cell in cells {
+            self.cells[cell].push(index);
+        }
+
+        self.items.push(item);
+        index
+    }
+
+    /// Find the items spatially intersecting an AABB
+    pub fn query_aabb(&self, query: &Aabb) -> Vec<usize> {
+        let mut results = HashSet::new();
+
+        for cell in self.find_cells(|aabb| query.intersects(aabb)) {
+            for &i in self.cells[cell].iter() {
+                if !results.contains(&i) && self.items[i].intersects(query) {
+                    results.insert(i);
+                }
+            }
+        }
+
+        results.into_iter().collect()
+    }
+
+    // Get the linear index of a cell from its (i, j, k) position
+    fn cell_index(&self, ijk: [usize; 3]) -> usize {
+        ijk[0] + self.shape[0] * (ijk[1] + self.shape[1] * ijk[2])
+    }
+
+    // Get the AABB spanning a half-open range of cells
+    fn range_bounds(&self, lo: [usize; 3], hi: [usize; 3]) -> Aabb {
+        let min = self.bounds.min();
+        let offset = |ijk: [usize; 3]| Vector3::new(ijk[0] as f64, ijk[1] as f64, ijk[2] as f64);
+        Aabb::from_bounds(
+            min + offset(lo) * self.cell_size,
+            min + offset(hi) * self.cell_size,
+        )
+    }
+
+    // Find the cells passing the intersection test by recursively bisecting
+    // ranges of cells along their longest axis, pruning ranges that fail.
+    fn find_cells<F>(&self, intersects: F) -> Vec<usize>
+    where
+        F: Fn(&Aabb) -> bool,
+    {
+        let mut cells = vec![];
+        let mut queue = vec![([0; 3], self.shape)];
+
+        while let Some((lo, hi)) = queue.pop() {
+            if !intersects(&self.range_bounds(lo, hi)) {
+                continue;
+            }
+
+            let axis = (0..3).max_by_key(|&i| hi[i] - lo[i]).unwrap();
+
+            if hi[axis] - lo[axis] == 1 {
+                cells.push(self.cell_index(lo));
+            } else {
+                let mid = (lo[axis] + hi[axis]) / 2;
+                let mut split_hi = hi;
+                let mut split_lo = lo;
+                split_hi[axis] = mid;
+                split_lo[axis] = mid;
+                queue.push((lo, split_hi));
+                queue.push((split_lo, hi));
+            }
+        }
+
+        cells
+    }
+}
+
+impl<T, Q> Query<Q> for UniformGrid<T>
+where
+    T: Intersects<Aabb> + Intersects<Q>,
+    Q: Intersects<Aabb>,
+{
+    fn query(&self, query: &Q) -> Vec<usize> {
+        let mut results = HashSet::new();
+
+        for cell in self.find_cells(|aabb| query.intersects(aabb)) {
+            for &i in self.cells[cell].iter() {
+                if !results.contains(&i) && Intersects::<Q>::intersects(&self.items[i], query) {
+                    results.insert(i);
+                }
+            }
+        }
+
+        results.into_iter().collect()
+    }
+}
+
+impl<T, Q> QueryMany<Q> for UniformGrid<T>
+where
+    T: Intersects<Aabb> + Intersects<Q> + Sync,
+    Q: Intersects<Aabb> + Sync,
+{
+    fn query_many(&self, queries: &[Q]) -> Vec<Vec<usize>> {
+        queries.par_iter().map(|q| self.query(q)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::{Sphere, Triangle};
+    use crate::mesh::HeMesh;
+    use crate::spatial::Octree;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn shape() {
+        let bounds = Aabb::from_bounds(Vector3::zeros(), Vector3::new(1., 2., 0.25));
+        let grid = UniformGrid::<Vector3>::new(bounds, 0.3);
+
+        assert_eq!(grid.shape(), [4, 7, 1]);
+    }
+
+    #[test]
+    fn insert() {
+        let bounds = Aabb::unit();
+        let mut grid = UniformGrid::<Vector3>::new(bounds, 0.25);
+        let index = grid.insert(Vector3::new(-0.4, -0.4, 0.3));
+
+        assert_eq!(index, 0);
+        assert_eq!(grid.cell(0, 0, 3), &[0]);
+        assert_eq!(grid.items().len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "item not inserted")]
+    fn insert_outside() {
+        let bounds = Aabb::unit();
+        let mut grid = UniformGrid::<Vector3>::new(bounds, 0.25);
+        grid.insert(Vector3::new(2., 0., 0.));
+    }
+
+    #[test]
+    fn query_points() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let bounds = Aabb::unit();
+        let mut grid = UniformGrid::<Vector3>::new(bounds, 0.1);
+        let mut octree = Octree::<Vector3>::new(bounds);
+
+        for _ in 0..1000 {
+            let p = Vector3::new(
+                rng.gen_range(-0.5..0.5),
+                rng.gen_range(-0.5..0.5),
+                rng.gen_range(-0.5..0.5),
+            );
+
+            grid.insert(p);
+            octree.insert(p);
+        }
+
+        for _ in 0..20 {
+            let center = Vector3::new(
+                rng.gen_range(-0.5..0.5),
+                rng.gen_range(-0.5..0.5),
+                rng.gen_range(-0.5..0.5),
+            );
+
+            let query = Sphere::new(center, rng.gen_range(0.05..0.3));
+            let mut expected = octree.query(&query);
+            let mut results = grid.query(&query);
+            expected.sort();
+            results.sort();
+
+            assert_eq!(expected, results);
+        }
+    }
+
+    #[test]
+    fn query_triangles() {
+        let path = "tests/fixtures/sphere.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let bounds = mesh.bounds();
+        let mut grid = UniformGrid::<Triangle>::new(bounds, 0.1);
+        let mut octree = Octree::<Triangle>::new(bounds);
+
+        for triangle in mesh.triangles() {
+            grid.insert(triangle);
+            octree.insert(triangle);
+        }
+
+        let min = bounds.min();
+        let max = bounds.max();
+
+        for i in 0..5 {
+            let t = i as f64 / 4.;
+            let center = min + (max - min) * t;
+            let query = Aabb::new(center, Vector3::new(0.2, 0.2, 0.2));
+
+            let mut expected = octree.query_aabb(&query);
+            let mut results = grid.query_aabb(&query);
+            expected.sort();
+            results.sort();
+
+            assert_eq!(expected, results);
+        }
+    }
+}
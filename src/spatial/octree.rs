@@ -1,7 +1,11 @@
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
+#[cfg(feature = "serde")]
+use std::fs::File;
+#[cfg(feature = "serde")]
+use std::io::{prelude::*, BufReader, BufWriter, Error, ErrorKind};
 
-use crate::geometry::{Aabb, Intersects};
+use crate::geometry::{Aabb, Bounded, Intersects, Ray, Vector3};
 use crate::spatial::{Query, QueryMany};
 
 /// Maximum depth of the Octree
@@ -10,13 +14,52 @@ const MAX_DEPTH: usize = (std::mem::size_of::<usize>() * 8 - 1) / 3;
 /// Maximum number of items indexed on a leaf node
 const MAX_ITEMS_PER_NODE: usize = 100;
 
+/// Leading bytes of a saved Octree
+#[cfg(feature = "serde")]
+const MAGIC: &[u8; 8] = b"MESHROCT";
+
+/// Version of the saved Octree format
+#[cfg(feature = "serde")]
+const FORMAT_VERSION: u32 = 2;
+
+/// Check for a spatial intersection between an item and an AABB
+pub type ItemIntersects<T> = fn(&T, &Aabb) -> bool;
+
+/// Get the largest side of an item's AABB
+pub type ItemExtent<T> = fn(&T) -> f64;
+
+/// Policy deciding when a leaf node is split and which items are pushed down
+/// to the children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OctreeConfig {
+    /// Maximum number of items indexed on a leaf node before it is split
+    pub max_items_per_node: usize,
+    /// Keep items larger than the children of a node on the node itself
+    /// rather than replicating them across the children. Only the items
+    /// that would be pushed down count toward splitting a leaf.
+    pub split_by_size: bool,
+}
+
+impl Default for OctreeConfig {
+    fn default() -> OctreeConfig {
+        OctreeConfig {
+            max_items_per_node: MAX_ITEMS_PER_NODE,
+            split_by_size: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct Octree<T>
+pub struct Octree<T, F = ItemIntersects<T>>
 where
-    T: Intersects<Aabb>,
+    F: Fn(&T, &Aabb) -> bool,
 {
     nodes: HashMap<usize, OctreeNode>,
     items: Vec<T>,
+    intersects: F,
+    config: OctreeConfig,
+    extent: Option<ItemExtent<T>>,
 }
 
 impl<T> Octree<T>
@@ -28,9 +71,146 @@ where
         Octree {
             nodes: HashMap::from([(1, OctreeNode::new(1, bounds))]),
             items: vec![],
+            intersects: |item, aabb| item.intersects(aabb),
+            config: OctreeConfig::default(),
+            extent: None,
+        }
+    }
+}
+
+impl<T> Octree<T>
+where
+    T: Intersects<Aabb> + Bounded,
+{
+    /// Construct an Octree from its bounds and the policy used to split its
+    /// nodes as items are inserted
+    pub fn with_config(bounds: Aabb, config: OctreeConfig) -> Octree<T> {
+        Octree {
+            config,
+            extent: Some(|item: &T| largest_side(&item.bounds())),
+            ..Octree::new(bounds)
+        }
+    }
+}
+
+impl<T> Octree<T>
+where
+    T: Intersects<Aabb> + Sync,
+{
+    /// Construct an Octree from its bounds and items by partitioning the
+    /// items among the octants top-down in parallel. Every item must overlap
+    /// with the Octree bounds. The nodes may differ from those built by
+    /// inserting the items one at a time, but queries give the same results.
+    pub fn build_parallel(bounds: Aabb, items: Vec<T>) -> Octree<T> {
+        Octree::new(bounds).build_items(items)
+    }
+
+    // Index the items of an empty Octree by building its nodes top-down
+    // under its split policy
+    fn build_items(mut self, items: Vec<T>) -> Octree<T> {
+        let bounds = self.nodes[&1].bounds;
+
+        if !items.par_iter().all(|item| item.intersects(&bounds)) {
+            panic!("item not inserted");
+        }
+
+        let indices = (0..items.len()).collect();
+        self.items = items;
+        self.nodes = self
+            .build_nodes(1, bounds, indices)
+            .into_iter()
+            .map(|node| (node.code, node))
+            .collect();
+
+        self
+    }
+}
+
+impl<T> Octree<T>
+where
+    T: Intersects<Aabb> + Bounded + Sync,
+{
+    /// Construct an Octree from its bounds and items in parallel as in
+    /// `build_parallel`, splitting the nodes under a given policy
+    pub fn build_parallel_with_config(
+        bounds: Aabb,
+        items: Vec<T>,
+        config: OctreeConfig,
+    ) -> Octree<T> {
+        Octree::with_config(bounds, config).build_items(items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> Octree<T>
+where
+    T: Intersects<Aabb> + Bounded + serde::de::DeserializeOwned,
+{
+    /// Load an Octree saved with `save` along with its split policy
+    pub fn load(path: &str) -> std::io::Result<Octree<T>> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut header = [0; 12];
+        reader.read_exact(&mut header)?;
+
+        if &header[..8] != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "invalid octree file"));
+        }
+
+        let version = u32::from_le_bytes(header[8..].try_into().unwrap());
+
+        if version != FORMAT_VERSION {
+            let message = format!("unsupported octree format version: {}", version);
+            return Err(Error::new(ErrorKind::InvalidData, message));
         }
+
+        let (nodes, items, config, sized): (_, _, _, bool) = bincode::deserialize_from(reader)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+        Ok(Octree {
+            nodes,
+            items,
+            intersects: |item, aabb| item.intersects(aabb),
+            config,
+            extent: sized.then_some(|item: &T| largest_side(&item.bounds())),
+        })
     }
+}
 
+impl<T> Octree<T> {
+    /// Construct an Octree from its bounds and a function computing the
+    /// AABB of an item. Items are indexed on the nodes overlapping their
+    /// AABB, which allows indexing items that do not implement
+    /// Intersects<Aabb> (e.g. face indices).
+    pub fn with_bounds_fn<G>(bounds: Aabb, f: G) -> Octree<T, impl Fn(&T, &Aabb) -> bool>
+    where
+        G: Fn(&T) -> Aabb,
+    {
+        Octree {
+            nodes: HashMap::from([(1, OctreeNode::new(1, bounds))]),
+            items: vec![],
+            intersects: move |item: &T, aabb: &Aabb| f(item).intersects(aabb),
+            config: OctreeConfig::default(),
+            extent: None,
+        }
+    }
+}
+
+impl<T> Octree<T>
+where
+    T: Bounded,
+{
+    /// Construct an Octree from its bounds indexing items on the nodes
+    /// overlapping their bounding box. This is coarser than indexing with
+    /// Intersects<Aabb>, but only requires that items are Bounded.
+    pub fn from_bounded(bounds: Aabb) -> Octree<T, impl Fn(&T, &Aabb) -> bool> {
+        Octree::with_bounds_fn(bounds, |item: &T| item.bounds())
+    }
+}
+
+impl<T, F> Octree<T, F>
+where
+    F: Fn(&T, &Aabb) -> bool,
+{
     /// Get a borrowed reference to a node
     pub fn node(&self, code: usize) -> &OctreeNode {
         &self.nodes[&code]
@@ -41,63 +221,116 @@ where
         &self.items
     }
 
+    /// Save the nodes, items and split policy to file. The intersection
+    /// function is not saved, so the Octree must be loaded with items
+    /// implementing Intersects<Aabb>.
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: &str) -> std::io::Result<()>
+    where
+        T: serde::Serialize,
+    {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+
+        let sized = self.extent.is_some();
+
+        bincode::serialize_into(
+            &mut writer,
+            &(&self.nodes, &self.items, &self.config, sized),
+        )
+        .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+        writer.flush()
+    }
+
+    /// Get the statistics describing the balance of the tree
+    pub fn stats(&self) -> OctreeStats {
+        let mut stats = OctreeStats {
+            n_nodes: self.nodes.len(),
+            ..OctreeStats::default()
+        };
+
+        let mut n_leaf_items = 0;
+
+        for node in self.nodes.values() {
+            stats.max_depth = stats.max_depth.max(node.depth());
+
+            if node.is_leaf() {
+                stats.n_leaves += 1;
+                stats.max_items_per_leaf = stats.max_items_per_leaf.max(node.items.len());
+                n_leaf_items += node.items.len();
+            }
+        }
+
+        stats.mean_items_per_leaf = n_leaf_items as f64 / stats.n_leaves as f64;
+        stats
+    }
+
     /// Insert an item which may be indexed on one or more nodes
     /// but must overlap with the Octree bounds.
     pub fn insert(&mut self, item: T) -> usize {
         let index = self.items.len();
-        let mut queue = vec![1];
-        let mut codes = vec![];
-
-        while let Some(code) = queue.pop() {
-            if let Some(node) = self.nodes.get_mut(&code) {
-                if item.intersects(&node.bounds) {
-                    if node.is_leaf() {
-                        node.items.push(index);
-                        codes.push(code);
-                    } else {
-                        let mut children = node.children();
-                        queue.append(&mut children);
-                    }
-                }
-            }
-        }
+        let codes = self.find_leaves(&item);
 
         if codes.is_empty() {
             panic!("item not inserted");
         }
 
         self.items.push(item);
+        self.index_leaves(index, codes);
 
-        for code in codes {
-            if self.nodes[&code].should_split() {
-                self.split(code);
+        index
+    }
+
+    /// Replace an item and re-index it on the nodes overlapping its new
+    /// location. The new item must overlap with the Octree bounds. Leaves
+    /// emptied by the move are not merged, so the tree may remain
+    /// unbalanced until it is rebuilt.
+    pub fn update(&mut self, index: usize, new_item: T) {
+        let codes = self.find_leaves(&new_item);
+
+        if codes.is_empty() {
+            panic!("item not inserted");
+        }
+
+        for code in self.find_leaves(&self.items[index]) {
+            if let Some(node) = self.nodes.get_mut(&code) {
+                node.items.retain(|&i| i != index);
             }
         }
 
-        index
+        self.items[index] = new_item;
+        self.index_leaves(index, codes);
     }
 
     /// Split an internal (non-leaf) node and redistribute any indexed
-    /// items amongst the children leaf nodes.
+    /// items amongst the children leaf nodes. Items larger than the children
+    /// stay on the node when splitting by size.
     pub fn split(&mut self, code: usize) {
-        if let Some(node) = self.nodes.get_mut(&code) {
+        if let Some(node) = self.nodes.get(&code) {
             if !node.can_split() {
                 panic!("octree node cannot be split");
             }
 
             let children = node.children();
             let bounds = node.bounds;
-            let items = node.items.clone();
 
+            let (kept, items): (Vec<usize>, Vec<usize>) = node
+                .items
+                .iter()
+                .partition(|&&item| self.is_large(&self.items[item], &bounds));
+
+            let node = self.nodes.get_mut(&code).unwrap();
             node.is_leaf = false;
-            node.items.clear();
+            node.items = kept;
 
             for (octant, &child_code) in children.iter().enumerate() {
                 let child_bounds = bounds.octant(octant);
                 let mut child_node = OctreeNode::new(child_code, child_bounds);
 
                 for &item in items.iter() {
-                    if self.items[item].intersects(&child_bounds) {
+                    if (self.intersects)(&self.items[item], &child_bounds) {
                         child_node.items.push(item);
                     }
                 }
@@ -106,11 +339,369 @@ where
             }
         }
     }
+
+    // Check if an item is larger than the children of a node, in which case
+    // the split policy may keep it on the node
+    fn is_large(&self, item: &T, bounds: &Aabb) -> bool {
+        self.config.split_by_size
+            && self
+                .extent
+                .is_some_and(|extent| extent(item) > 0.5 * largest_side(bounds))
+    }
+
+    // Check if a leaf node should be split under the split policy
+    fn should_split(&self, code: usize) -> bool {
+        let node = &self.nodes[&code];
+        let n_items = node
+            .items
+            .iter()
+            .filter(|&&i| !self.is_large(&self.items[i], &node.bounds))
+            .count();
+
+        n_items > self.config.max_items_per_node && node.can_split()
+    }
+
+    // Find the nodes intersecting an item that index it: the leaf nodes and
+    // any internal nodes keeping it under the split policy
+    fn find_leaves(&self, item: &T) -> Vec<usize> {
+        let mut queue = vec![1];
+        let mut codes = vec![];
+
+        while let Some(code) = queue.pop() {
+            if let Some(node) = self.nodes.get(&code) {
+                if (self.intersects)(item, &node.bounds) {
+                    if node.is_leaf() || self.is_large(item, &node.bounds) {
+                        codes.push(code);
+                    } else {
+                        let mut children = node.children();
+                        queue.append(&mut children);
+                    }
+                }
+            }
+        }
+
+        codes
+    }
+
+    // Index an item on leaf nodes, splitting any that become too full
+    fn index_leaves(&mut self, index: usize, codes: Vec<usize>) {
+        for &code in codes.iter() {
+            if let Some(node) = self.nodes.get_mut(&code) {
+                node.items.push(index);
+            }
+        }
+
+        for code in codes {
+            if self.should_split(code) {
+                self.split(code);
+            }
+        }
+    }
+
+    /// Find the items spatially intersecting an AABB. Items are tested using
+    /// the same intersection test used to index them.
+    pub fn query_aabb(&self, query: &Aabb) -> Vec<usize> {
+        let mut results = HashSet::new();
+        let mut queue = vec![1];
+
+        while let Some(code) = queue.pop() {
+            if let Some(node) = self.nodes.get(&code) {
+                if query.intersects(&node.bounds) {
+                    for i in node.items.iter() {
+                        if !results.contains(i) && (self.intersects)(&self.items[*i], query) {
+                            results.insert(*i);
+                        }
+                    }
+
+                    if !node.is_leaf() {
+                        let mut children = node.children();
+                        queue.append(&mut children);
+                    }
+                }
+            }
+        }
+
+        results.into_iter().collect()
+    }
+
+    /// Find the items intersecting a ray grouped by the node indexing them
+    /// and ordered front to back by the (entry, exit) interval of the ray
+    /// through the node. Items on several nodes are placed with the nearest
+    /// and items on the same node are in ascending order. The groups order
+    /// the nodes rather than the hits: an item may be hit beyond the exit of
+    /// its group, so a hit is only known to be the closest once it is no
+    /// farther than the entry of the next group (see `first_hit`).
+    pub fn query_nearest_first(&self, ray: &Ray) -> Vec<(f64, f64, Vec<usize>)>
+    where
+        T: Intersects<Ray>,
+    {
+        let mut nodes = vec![];
+        let mut queue = vec![1];
+
+        while let Some(code) = queue.pop() {
+            if let Some(node) = self.nodes.get(&code) {
+                if let Some((entry, exit)) = node.bounds.ray_interval(ray) {
+                    nodes.push((entry, exit, code));
+
+                    if !node.is_leaf() {
+                        queue.append(&mut node.children());
+                    }
+                }
+            }
+        }
+
+        nodes.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.2.cmp(&b.2)));
+
+        let mut visited = HashSet::new();
+        let mut groups = vec![];
+
+        for (entry, exit, code) in nodes {
+            let mut items: Vec<usize> = self.nodes[&code]
+                .items
+                .iter()
+                .copied()
+                .filter(|&i| visited.insert(i) && self.items[i].intersects(ray))
+                .collect();
+
+            if !items.is_empty() {
+                items.sort();
+                groups.push((entry, exit, items));
+            }
+        }
+
+        groups
+    }
+
+    /// Find the closest item hit by a ray along with the hit parameter. The
+    /// hit function returns the parameter along the ray at which an item is
+    /// hit, if any. The groups of `query_nearest_first` are tested in order
+    /// and the search stops once the closest hit so far lies before the
+    /// entry of the next group.
+    pub fn first_hit<H>(&self, ray: &Ray, hit: H) -> Option<(usize, f64)>
+    where
+        T: Intersects<Ray>,
+        H: Fn(&T) -> Option<f64>,
+    {
+        let mut closest: Option<(usize, f64)> = None;
+
+        for (entry, _, items) in self.query_nearest_first(ray) {
+            if closest.is_some_and(|(_, t)| t <= entry) {
+                break;
+            }
+
+            for i in items {
+                if let Some(t) = hit(&self.items[i]) {
+                    if closest.is_none_or(|(_, best)| t < best) {
+                        closest = Some((i, t));
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Get the location codes of the leaf nodes indexing at least one item
+    /// in ascending order
+    pub fn occupied_leaves(&self) -> Vec<usize> {
+        let mut codes: Vec<usize> = self
+            .nodes
+            .values()
+            .filter(|node| node.is_leaf() && !node.items.is_empty())
+            .map(|node| node.code)
+            .collect();
+
+        codes.sort();
+        codes
+    }
+
+    /// Get the location codes of the nodes an item is indexed on in ascending
+    /// order. These are leaf nodes unless the item is kept on an internal
+    /// node when splitting by size.
+    pub fn item_leaves(&self, index: usize) -> Vec<usize> {
+        let mut codes = self.find_leaves(&self.items[index]);
+        codes.sort();
+        codes
+    }
+
+    /// Get the location code of the leaf node containing a point by
+    /// descending through the octant containing it at each level. Returns
+    /// None if the point is outside the Octree bounds.
+    pub fn locate(&self, point: Vector3) -> Option<usize> {
+        let mut node = self.nodes.get(&1)?;
+
+        if !node.bounds.intersects(&point) {
+            return None;
+        }
+
+        while !node.is_leaf() {
+            let center = node.bounds.center();
+            let octant = (usize::from(point[0] >= center[0]) << 2)
+                | (usize::from(point[1] >= center[1]) << 1)
+                | usize::from(point[2] >= center[2]);
+
+            node = self.nodes.get(&((node.code << 3) | octant))?;
+        }
+
+        Some(node.code)
+    }
+
+    /// Get the location codes of the leaf nodes sharing a face with a leaf
+    /// node. Neighbors are found through location code arithmetic: a coarser
+    /// neighbor is found by walking up from the equal-depth code and a finer
+    /// neighbor by collecting the leaves of the equal-depth node on the
+    /// shared face.
+    pub fn face_neighbors(&self, code: usize) -> Vec<usize> {
+        let mut neighbors = vec![];
+
+        let Some(node) = self.nodes.get(&code) else {
+            return neighbors;
+        };
+
+        let depth = node.depth();
+        let coords = decode_code(code, depth);
+        let size = 1 << depth;
+
+        for axis in 0..3 {
+            for positive in [false, true] {
+                let mut coords = coords;
+
+                match positive {
+                    true if coords[axis] + 1 < size => coords[axis] += 1,
+                    false if coords[axis] > 0 => coords[axis] -= 1,
+                    _ => continue,
+                }
+
+                let mut neighbor = encode_code(coords, depth);
+
+                while !self.nodes.contains_key(&neighbor) {
+                    neighbor >>= 3;
+                }
+
+                let mut queue = vec![neighbor];
+
+                while let Some(next) = queue.pop() {
+                    let node = &self.nodes[&next];
+
+                    if node.is_leaf() {
+                        if !neighbors.contains(&next) {
+                            neighbors.push(next);
+                        }
+                    } else {
+                        // Only the children on the face shared with the
+                        // original leaf can be adjacent to it
+                        let bit = 4 >> axis;
+                        let side = if positive { 0 } else { bit };
+
+                        for child in node.children() {
+                            if child & bit == side {
+                                queue.push(child);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        neighbors
+    }
+}
+
+// Get the largest side of an AABB
+fn largest_side(aabb: &Aabb) -> f64 {
+    let size = aabb.max() - aabb.min();
+    size[size.max_index()]
+}
+
+// Decode a location code at a depth into its integer cell coordinates
+fn decode_code(code: usize, depth: usize) -> [usize; 3] {
+    let mut coords = [0; 3];
+
+    for level in (0..depth).rev() {
+        let octant = (code >> (3 * level)) & 7;
+
+        for (axis, coord) in coords.iter_mut().enumerate() {
+            *coord = (*coord << 1) | ((octant >> (2 - axis)) & 1);
+        }
+    }
+
+    coords
+}
+
+// Encode integer cell coordinates at a depth into a location code
+fn encode_code(coords: [usize; 3], depth: usize) -> usize {
+    let mut code = 1;
+
+    for level in (0..depth).rev() {
+        let octant = (0..3).fold(0, |o, axis| (o << 1) | ((coords[axis] >> level) & 1));
+        code = (code << 3) | octant;
+    }
+
+    code
+}
+
+impl<T, F> Octree<T, F>
+where
+    T: Sync,
+    F: Fn(&T, &Aabb) -> bool + Sync,
+{
+    // Build a node and its descendants from the items it indexes. A node
+    // with too many items is split unless every child would index all of
+    // them (e.g. items spanning the node center), which would not reduce the
+    // load. Items kept by the split policy stay on the node.
+    fn build_nodes(&self, code: usize, bounds: Aabb, indices: Vec<usize>) -> Vec<OctreeNode> {
+        let mut node = OctreeNode::new(code, bounds);
+
+        let (kept, indices): (Vec<usize>, Vec<usize>) = indices
+            .into_iter()
+            .partition(|&i| self.is_large(&self.items[i], &bounds));
+
+        if indices.len() <= self.config.max_items_per_node || !node.can_split() {
+            node.items = kept.into_iter().chain(indices).collect();
+            return vec![node];
+        }
+
+        let children: Vec<(usize, Aabb, Vec<usize>)> = node
+            .children()
+            .into_par_iter()
+            .enumerate()
+            .map(|(octant, child)| {
+                let child_bounds = bounds.octant(octant);
+                let child_items = indices
+                    .iter()
+                    .copied()
+                    .filter(|&i| (self.intersects)(&self.items[i], &child_bounds))
+                    .collect();
+
+                (child, child_bounds, child_items)
+            })
+            .collect();
+
+        if children.iter().all(|(_, _, c)| c.len() == indices.len()) {
+            node.items = kept.into_iter().chain(indices).collect();
+            return vec![node];
+        }
+
+        node.is_leaf = false;
+        node.items = kept;
+
+        let descendants: Vec<Vec<OctreeNode>> = children
+            .into_par_iter()
+            .map(|(child, child_bounds, child_items)| {
+                self.build_nodes(child, child_bounds, child_items)
+            })
+            .collect();
+
+        let mut nodes = vec![node];
+        nodes.extend(descendants.into_iter().flatten());
+        nodes
+    }
 }
 
-impl<T, Q> Query<Q> for Octree<T>
+impl<T, F, Q> Query<Q> for Octree<T, F>
 where
-    T: Intersects<Aabb> + Intersects<Q>,
+    T: Intersects<Q>,
+    F: Fn(&T, &Aabb) -> bool,
     Q: Intersects<Aabb>,
 {
     fn query(&self, query: &Q) -> Vec<usize> {
@@ -120,13 +711,13 @@ where
         while let Some(code) = queue.pop() {
             if let Some(node) = self.nodes.get(&code) {
                 if query.intersects(&node.bounds) {
-                    if node.is_leaf() {
-                        for i in node.items.iter() {
-                            if !results.contains(i) && self.items[*i].intersects(query) {
-                                results.insert(*i);
-                            }
+                    for i in node.items.iter() {
+                        if !results.contains(i) && self.items[*i].intersects(query) {
+                            results.insert(*i);
                         }
-                    } else {
+                    }
+
+                    if !node.is_leaf() {
                         let mut children = node.children();
                         queue.append(&mut children);
                     }
@@ -138,18 +729,58 @@ where
     }
 }
 
-impl<T, Q> QueryMany<Q> for Octree<T>
+impl<T, F, Q> QueryMany<Q> for Octree<T, F>
 where
-    T: Intersects<Aabb> + Intersects<Q> + Sync,
+    T: Intersects<Q> + Sync,
+    F: Fn(&T, &Aabb) -> bool + Sync,
     Q: Intersects<Aabb> + Sync,
-    Octree<T>: Query<Q>,
+    Octree<T, F>: Query<Q>,
 {
     fn query_many(&self, queries: &[Q]) -> Vec<Vec<usize>> {
         queries.par_iter().map(|q| self.query(q)).collect()
     }
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct OctreeStats {
+    n_nodes: usize,
+    n_leaves: usize,
+    max_depth: usize,
+    mean_items_per_leaf: f64,
+    max_items_per_leaf: usize,
+}
+
+impl OctreeStats {
+    /// Get the number of nodes
+    pub fn n_nodes(&self) -> usize {
+        self.n_nodes
+    }
+
+    /// Get the number of leaf nodes
+    pub fn n_leaves(&self) -> usize {
+        self.n_leaves
+    }
+
+    /// Get the maximum depth of any node
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Get the mean number of items indexed on a leaf node. Items indexed on
+    /// multiple leaf nodes are counted once per node.
+    pub fn mean_items_per_leaf(&self) -> f64 {
+        self.mean_items_per_leaf
+    }
+
+    /// Get the maximum number of items indexed on any leaf node. This may
+    /// exceed the split threshold for leaf nodes at the maximum depth.
+    pub fn max_items_per_leaf(&self) -> usize {
+        self.max_items_per_leaf
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OctreeNode {
     code: usize,
     bounds: Aabb,
@@ -191,7 +822,7 @@ impl OctreeNode {
     /// Get the depth of the node
     pub fn depth(&self) -> usize {
         (0..MAX_DEPTH + 1)
-            .find(|d| self.code >> d * 3 == 1)
+            .find(|d| self.code >> (d * 3) == 1)
             .expect("invalid octree code")
     }
 
@@ -214,7 +845,22 @@ impl OctreeNode {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::geometry::Vector3;
+    use crate::geometry::{Geometry, Sphere, Triangle, EPSILON};
+    use crate::mesh::HeMesh;
+
+    // Build an Octree split once by inserting one more point than a node
+    // holds along the diagonal through the center
+    fn split_octree() -> Octree<Vector3> {
+        let mut octree = Octree::<Vector3>::new(Aabb::unit());
+        let count = MAX_ITEMS_PER_NODE + 1;
+
+        for i in 0..count {
+            let v = 0.5 * (i as f64) / (count as f64 - 1.) - 0.25;
+            octree.insert(Vector3::new(v, v, v));
+        }
+
+        octree
+    }
 
     #[test]
     fn insert_single() {
@@ -258,6 +904,346 @@ mod test {
         assert_eq!(count / 2 + 1, octree.node(15).items.len());
     }
 
+    #[test]
+    fn query_nearest_first() {
+        let bounds = Aabb::unit();
+        let mut octree = Octree::<Sphere>::new(bounds);
+        let count = MAX_ITEMS_PER_NODE + 1;
+
+        for i in 0..count {
+            let x = 0.9 * (i as f64) / (count as f64 - 1.) - 0.45;
+            octree.insert(Sphere::new(Vector3::new(x, 0.1, 0.1), 0.001));
+        }
+
+        assert!(octree.nodes.len() > 1);
+
+        let items = |groups: Vec<(f64, f64, Vec<usize>)>| -> Vec<usize> {
+            groups.into_iter().flat_map(|(_, _, items)| items).collect()
+        };
+
+        let ray = Ray::new(Vector3::new(-1., 0.1, 0.1), Vector3::new(1., 0., 0.));
+        let groups = octree.query_nearest_first(&ray);
+
+        assert!(groups.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(groups.iter().all(|(entry, exit, _)| entry <= exit));
+        assert_eq!(items(groups), (0..count).collect::<Vec<usize>>());
+
+        // Reversing the ray visits the far half first
+        let ray = Ray::new(Vector3::new(1., 0.1, 0.1), Vector3::new(-1., 0., 0.));
+        let expected: Vec<usize> = (count / 2..count).chain(0..count / 2).collect();
+        assert_eq!(items(octree.query_nearest_first(&ray)), expected);
+
+        let ray = Ray::new(Vector3::new(-1., -0.1, 0.1), Vector3::new(1., 0., 0.));
+        assert!(octree.query_nearest_first(&ray).is_empty());
+    }
+
+    #[test]
+    fn first_hit_spanning_item() {
+        let config = OctreeConfig {
+            max_items_per_node: 2,
+            ..OctreeConfig::default()
+        };
+
+        let mut octree = Octree::<Triangle>::with_config(Aabb::unit(), config);
+
+        // Spans octants 0 and 4 but is hit beyond the near octant
+        let spanning = octree.insert(Triangle::new(
+            Vector3::new(-0.1, -0.45, -0.45),
+            Vector3::new(0.5, -0.25, -0.15),
+            Vector3::new(-0.1, -0.05, -0.45),
+        ));
+
+        let near = octree.insert(Triangle::new(
+            Vector3::new(0.1, -0.45, -0.45),
+            Vector3::new(0.1, -0.25, -0.05),
+            Vector3::new(0.1, -0.05, -0.45),
+        ));
+
+        octree.insert(Triangle::new(
+            Vector3::new(0.3, 0.3, 0.3),
+            Vector3::new(0.4, 0.3, 0.3),
+            Vector3::new(0.3, 0.4, 0.3),
+        ));
+
+        let ray = Ray::new(Vector3::new(-1., -0.25, -0.25), Vector3::new(1., 0., 0.));
+        let groups = octree.query_nearest_first(&ray);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].2, vec![spanning]);
+        assert_eq!(groups[1].2, vec![near]);
+
+        let (item, t) = octree
+            .first_hit(&ray, |t| ray.hit_triangle(t, true))
+            .unwrap();
+
+        assert_eq!(item, near);
+        assert!((t - 1.1).abs() < EPSILON);
+    }
+
+    #[test]
+    fn occupied_leaves() {
+        let octree = split_octree();
+        let count = octree.items().len();
+
+        let mut expected: Vec<usize> = octree
+            .nodes
+            .values()
+            .filter(|node| !node.items.is_empty())
+            .map(|node| node.code)
+            .collect();
+
+        expected.sort();
+
+        assert_eq!(octree.occupied_leaves(), expected);
+        assert_eq!(octree.occupied_leaves(), vec![8, 9, 10, 11, 12, 13, 14, 15]);
+
+        for index in 0..count {
+            let leaves = octree.item_leaves(index);
+
+            assert!(!leaves.is_empty());
+            assert!(leaves
+                .iter()
+                .all(|&code| octree.node(code).items().contains(&index)));
+        }
+
+        assert_eq!(octree.item_leaves(0), vec![8]);
+    }
+
+    #[test]
+    fn from_bounded() {
+        let path = "tests/fixtures/sphere.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+        let mut octree = Octree::<Triangle>::from_bounded(mesh.bounds());
+
+        for triangle in mesh.triangles() {
+            octree.insert(triangle);
+        }
+
+        let query = Aabb::new(mesh.vertex(0).origin(), Vector3::ones() * 0.1);
+        let results = octree.query_aabb(&query);
+
+        assert!(!results.is_empty());
+
+        for (i, triangle) in octree.items().iter().enumerate() {
+            assert_eq!(results.contains(&i), triangle.bounds().intersects(&query),);
+        }
+    }
+
+    #[test]
+    fn split_by_size() {
+        let bounds = Aabb::unit();
+        let config = OctreeConfig {
+            split_by_size: true,
+            ..OctreeConfig::default()
+        };
+
+        let mut octree = Octree::<Triangle>::new(bounds);
+        let mut sized = Octree::<Triangle>::with_config(bounds, config);
+
+        let big = Triangle::new(
+            Vector3::new(-0.5, -0.5, -0.1),
+            Vector3::new(0.5, -0.5, 0.1),
+            Vector3::new(0., 0.5, 0.),
+        );
+
+        octree.insert(big);
+        sized.insert(big);
+
+        let n = 20;
+        let h = 0.5 / n as f64;
+
+        for j in 0..n {
+            for i in 0..n {
+                let p = Vector3::new(
+                    -0.45 + 0.9 * i as f64 / n as f64,
+                    -0.45 + 0.9 * j as f64 / n as f64,
+                    0.3,
+                );
+                let small =
+                    Triangle::new(p, p + Vector3::new(h, 0., 0.), p + Vector3::new(0., h, 0.));
+                octree.insert(small);
+                sized.insert(small);
+            }
+        }
+
+        assert!(octree.item_leaves(0).len() > 10);
+        assert_eq!(sized.item_leaves(0), vec![1]);
+        assert!(!sized.node(1).is_leaf());
+
+        for center in [
+            Vector3::zeros(),
+            Vector3::new(0.2, -0.3, 0.3),
+            Vector3::new(0.4, 0.4, 0.),
+        ] {
+            let query = Aabb::new(center, Vector3::ones() * 0.05);
+            let mut expected = octree.query_aabb(&query);
+            let mut results = sized.query_aabb(&query);
+            expected.sort();
+            results.sort();
+
+            assert_eq!(results, expected);
+            assert_eq!(sized.query(&query).len(), expected.len());
+        }
+    }
+
+    #[test]
+    fn locate() {
+        let octree = split_octree();
+
+        for &p in octree.items() {
+            let code = octree.locate(p).unwrap();
+            let node = octree.node(code);
+
+            assert!(node.is_leaf());
+            assert!(node.bounds().intersects(&p));
+        }
+
+        assert_eq!(octree.locate(Vector3::new(-0.2, 0.2, -0.2)), Some(10));
+        assert_eq!(octree.locate(Vector3::new(1., 0., 0.)), None);
+    }
+
+    #[test]
+    fn face_neighbors() {
+        let mut octree = Octree::<Vector3>::new(Aabb::unit());
+        octree.split(1);
+        octree.split(8);
+        octree.split(15);
+        octree.split(8 * 8 + 7);
+
+        // Leaves sharing a face have touching bounds along one axis and
+        // overlapping bounds along the other two
+        let adjacent = |a: &Aabb, b: &Aabb| {
+            let mut touching = 0;
+
+            for axis in 0..3 {
+                let gap = (a.min()[axis] - b.max()[axis]).max(b.min()[axis] - a.max()[axis]);
+
+                if gap > EPSILON {
+                    return false;
+                } else if gap > -EPSILON {
+                    touching += 1;
+                }
+            }
+
+            touching == 1
+        };
+
+        let leaves: Vec<&OctreeNode> = octree.nodes.values().filter(|n| n.is_leaf()).collect();
+
+        for leaf in leaves.iter() {
+            let mut neighbors = octree.face_neighbors(leaf.code());
+            let mut expected: Vec<usize> = leaves
+                .iter()
+                .filter(|n| adjacent(&leaf.bounds(), &n.bounds()))
+                .map(|n| n.code())
+                .collect();
+
+            neighbors.sort();
+            expected.sort();
+
+            assert_eq!(neighbors, expected);
+        }
+
+        let mut neighbors = octree.face_neighbors(14);
+        neighbors.sort();
+
+        assert_eq!(neighbors, vec![10, 12, 120, 122, 124, 126]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn save_load() {
+        let octree = split_octree();
+
+        let path = std::env::temp_dir().join("meshr.save_load.octree");
+        let path = path.to_str().unwrap();
+        octree.save(path).unwrap();
+
+        let loaded = Octree::<Vector3>::load(path);
+        std::fs::remove_file(path).unwrap();
+        let loaded = loaded.unwrap();
+
+        assert_eq!(octree.nodes.len(), loaded.nodes.len());
+        assert_eq!(octree.items(), loaded.items());
+
+        for query in [
+            Aabb::new(Vector3::zeros(), Vector3::new(0.1, 0.1, 0.1)),
+            Aabb::new(Vector3::new(0.2, 0.2, 0.2), Vector3::new(0.1, 0.1, 0.1)),
+            Aabb::new(Vector3::new(-0.4, 0.4, 0.), Vector3::new(0.05, 0.05, 0.05)),
+        ] {
+            let mut expected = octree.query(&query);
+            let mut results = loaded.query(&query);
+            expected.sort();
+            results.sort();
+
+            assert_eq!(expected, results);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn save_load_config() {
+        let config = OctreeConfig {
+            max_items_per_node: 8,
+            split_by_size: true,
+        };
+
+        let mut octree = Octree::<Aabb>::with_config(Aabb::unit(), config);
+        let big = Aabb::new(Vector3::zeros(), Vector3::new(0.45, 0.45, 0.1));
+
+        for i in 0..20 {
+            let center = Vector3::new(-0.45 + 0.045 * i as f64, 0.1, 0.3);
+            octree.insert(Aabb::new(center, Vector3::ones() * 0.01));
+        }
+
+        let path = std::env::temp_dir().join("meshr.save_load_config.octree");
+        let path = path.to_str().unwrap();
+        octree.save(path).unwrap();
+
+        let loaded = Octree::<Aabb>::load(path);
+        std::fs::remove_file(path).unwrap();
+        let mut loaded = loaded.unwrap();
+
+        assert_eq!(loaded.config, config);
+
+        // The large item stays on the root under the loaded policy
+        let index = loaded.insert(big);
+        octree.insert(big);
+
+        assert_eq!(loaded.item_leaves(index), vec![1]);
+        assert_eq!(loaded.item_leaves(index), octree.item_leaves(index));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn load_invalid() {
+        let path = std::env::temp_dir().join("meshr.load_invalid.octree");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, b"not an octree").unwrap();
+
+        let result = Octree::<Vector3>::load(path);
+        std::fs::remove_file(path).unwrap();
+
+        assert!(result.is_err_and(|e| e.to_string() == "invalid octree file"));
+    }
+
+    #[test]
+    fn stats() {
+        let octree = split_octree();
+        let count = octree.items().len();
+
+        let stats = octree.stats();
+
+        assert_eq!(9, stats.n_nodes());
+        assert_eq!(8, stats.n_leaves());
+        assert_eq!(1, stats.max_depth());
+        assert_eq!(count / 2 + 1, stats.max_items_per_leaf());
+        assert_eq!(
+            (2 * (count / 2 + 1) + 6) as f64 / 8.,
+            stats.mean_items_per_leaf()
+        );
+    }
+
     #[test]
     #[should_panic]
     fn insert_no_overlap() {
@@ -267,9 +1253,44 @@ mod test {
         octree.insert(point);
     }
 
+    #[test]
+    fn update() {
+        let bounds = Aabb::unit();
+        let mut octree = Octree::<Vector3>::new(bounds);
+
+        for i in 0..MAX_ITEMS_PER_NODE {
+            let t = i as f64 / MAX_ITEMS_PER_NODE as f64;
+            octree.insert(Vector3::new(-0.4 + 0.1 * t, -0.4, -0.4));
+        }
+
+        let index = octree.insert(Vector3::new(-0.25, -0.25, -0.25));
+        assert!(!octree.node(1).is_leaf());
+
+        let old = Aabb::new(Vector3::new(-0.25, -0.25, -0.25), Vector3::ones() * 0.01);
+        let new = Aabb::new(Vector3::new(0.25, 0.25, 0.25), Vector3::ones() * 0.01);
+        assert_eq!(octree.query_aabb(&old), vec![index]);
+        assert!(octree.query_aabb(&new).is_empty());
+
+        octree.update(index, Vector3::new(0.25, 0.25, 0.25));
+
+        assert!(octree.query_aabb(&old).is_empty());
+        assert_eq!(octree.query_aabb(&new), vec![index]);
+        assert_eq!(octree.items()[index], Vector3::new(0.25, 0.25, 0.25));
+        assert!(!octree.node(8).items().contains(&index));
+        assert!(octree.node(15).items().contains(&index));
+    }
+
+    #[test]
+    #[should_panic(expected = "item not inserted")]
+    fn update_no_overlap() {
+        let mut octree = Octree::<Vector3>::new(Aabb::unit());
+        let index = octree.insert(Vector3::zeros());
+        octree.update(index, Vector3::new(2., 0., 0.));
+    }
+
     #[test]
     fn query() {
-        assert!(MAX_ITEMS_PER_NODE <= 101);
+        const { assert!(MAX_ITEMS_PER_NODE <= 101) };
 
         let bounds = Aabb::unit();
         let mut octree = Octree::<Vector3>::new(bounds);
@@ -288,9 +1309,99 @@ mod test {
         assert_eq!(11, results.len());
     }
 
+    #[test]
+    fn build_parallel() {
+        let path = "tests/fixtures/sphere.obj";
+        let mut mesh = HeMesh::import_obj(path).unwrap();
+        mesh.subdivide_midpoint(2).unwrap();
+
+        let bounds = mesh.bounds();
+        let triangles: Vec<Triangle> = mesh.triangles().collect();
+        let mut sequential = Octree::<Triangle>::new(bounds);
+
+        for triangle in triangles.iter() {
+            sequential.insert(*triangle);
+        }
+
+        let parallel = Octree::build_parallel(bounds, triangles);
+
+        assert_eq!(parallel.items().len(), sequential.items().len());
+        assert!(!parallel.node(1).is_leaf());
+
+        for i in 0..50 {
+            let t = i as f64 / 49.;
+            let center = bounds.min() + (bounds.max() - bounds.min()) * t;
+            let query = Aabb::new(center, Vector3::ones() * (0.02 + 0.1 * t));
+
+            let mut expected = sequential.query(&query);
+            let mut results = parallel.query(&query);
+            expected.sort();
+            results.sort();
+
+            assert_eq!(expected, results);
+        }
+    }
+
+    #[test]
+    fn build_parallel_config() {
+        let bounds = Aabb::unit();
+        let config = OctreeConfig {
+            max_items_per_node: 8,
+            split_by_size: true,
+        };
+
+        let mut triangles = vec![Triangle::new(
+            Vector3::new(-0.5, -0.5, -0.1),
+            Vector3::new(0.5, -0.5, 0.1),
+            Vector3::new(0., 0.5, 0.),
+        )];
+
+        let n = 10;
+        let h = 0.5 / n as f64;
+
+        for j in 0..n {
+            for i in 0..n {
+                let p = Vector3::new(
+                    -0.45 + 0.9 * i as f64 / n as f64,
+                    -0.45 + 0.9 * j as f64 / n as f64,
+                    0.3,
+                );
+                triangles.push(Triangle::new(
+                    p,
+                    p + Vector3::new(h, 0., 0.),
+                    p + Vector3::new(0., h, 0.),
+                ));
+            }
+        }
+
+        let mut sequential = Octree::<Triangle>::with_config(bounds, config);
+
+        for triangle in triangles.iter() {
+            sequential.insert(*triangle);
+        }
+
+        let parallel = Octree::build_parallel_with_config(bounds, triangles, config);
+
+        assert_eq!(parallel.item_leaves(0), vec![1]);
+        assert!(parallel.stats().max_items_per_leaf() <= 8);
+
+        for i in 0..50 {
+            let t = i as f64 / 49.;
+            let center = Vector3::ones() * (t - 0.5);
+            let query = Aabb::new(center, Vector3::ones() * (0.02 + 0.1 * t));
+
+            let mut expected = sequential.query(&query);
+            let mut results = parallel.query(&query);
+            expected.sort();
+            results.sort();
+
+            assert_eq!(expected, results);
+        }
+    }
+
     #[test]
     fn query_no_results() {
-        assert!(MAX_ITEMS_PER_NODE <= 101);
+        const { assert!(MAX_ITEMS_PER_NODE <= 101) };
 
         let bounds = Aabb::unit();
         let mut octree = Octree::<Vector3>::new(bounds);
@@ -311,7 +1422,7 @@ mod test {
 
     #[test]
     fn query_many() {
-        assert!(MAX_ITEMS_PER_NODE <= 101);
+        const { assert!(MAX_ITEMS_PER_NODE <= 101) };
 
         let bounds = Aabb::unit();
         let mut octree = Octree::<Vector3>::new(bounds);
@@ -337,4 +1448,26 @@ mod test {
         assert_eq!(11, results[0].len());
         assert_eq!(0, results[1].len());
     }
+
+    #[test]
+    fn with_bounds_fn() {
+        let path = "tests/fixtures/box.obj";
+        let mesh = HeMesh::import_obj(path).unwrap();
+
+        let bounds = |face_id: &usize| Geometry::from(mesh.face_triangles(*face_id)[0]).bounds();
+        let mut octree = Octree::with_bounds_fn(Aabb::unit(), bounds);
+
+        for face_id in 0..mesh.n_faces() {
+            octree.insert(face_id);
+        }
+
+        let c = Vector3::new(-0.5, -0.5, -0.5);
+        let h = Vector3::new(0.05, 0.05, 0.05);
+        let q = Aabb::new(c, h);
+        let mut results = octree.query_aabb(&q);
+        results.sort();
+
+        assert_eq!(mesh.n_faces(), octree.items().len());
+        assert_eq!(vec![0, 1, 4, 5, 8, 9], results);
+    }
 }